@@ -0,0 +1,40 @@
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/index");
+
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|out| out.status.success())
+            .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+    };
+    let hash = match git(&["rev-parse", "--short=12", "HEAD"]) {
+        Some(hash) if git(&["status", "--porcelain"]).is_some_and(|s| !s.is_empty()) => {
+            format!("{hash}-dirty")
+        }
+        Some(hash) => hash,
+        None => "unknown".to_string(),
+    };
+    println!("cargo:rustc-env=KUHN_GIT_HASH={hash}");
+
+    for (key, var) in [
+        ("KUHN_PROFILE", "PROFILE"),
+        ("KUHN_OPT_LEVEL", "OPT_LEVEL"),
+        ("KUHN_TARGET", "TARGET"),
+    ] {
+        println!(
+            "cargo:rustc-env={key}={}",
+            env::var(var).unwrap_or_default()
+        );
+    }
+    let rustflags = env::var("CARGO_ENCODED_RUSTFLAGS").unwrap_or_default();
+    println!(
+        "cargo:rustc-env=KUHN_RUSTFLAGS={}",
+        rustflags.replace('\x1f', " ")
+    );
+}
@@ -0,0 +1,28 @@
+use std::env;
+use std::path::Path;
+use std::process;
+
+use kuhn::{hopcroft_karp, Adjacency, CsrGraph};
+
+fn main() {
+    let Some(path) = env::args().nth(1) else {
+        eprintln!("usage: assignment <edges.csv>");
+        process::exit(2);
+    };
+
+    let graph = CsrGraph::load_csv(Path::new(&path)).unwrap_or_else(|err| {
+        eprintln!("error: {err}");
+        process::exit(1);
+    });
+    let matching = hopcroft_karp(&graph);
+
+    eprintln!(
+        "matched {} of {} left vertices",
+        matching.len(),
+        graph.left_len()
+    );
+    println!("left,right");
+    for (i, j) in matching.as_coo() {
+        println!("{i},{j}");
+    }
+}
@@ -0,0 +1,36 @@
+use std::env;
+
+use rand::prelude::*;
+
+use kuhn::BlockModel;
+
+/// Augmenting-path lengths of Kuhn's algorithm on planted communities, from fully separated
+/// blocks (mixing 0) to no structure at all (mixing `(k - 1) / k`), at a fixed mean degree.
+fn main() {
+    let mut args = env::args().skip(1).map(|s| s.parse::<usize>().ok());
+    let n = args.next().flatten().unwrap_or(10000);
+    let blocks = args.next().flatten().unwrap_or(10).max(2);
+    let degree = 4.0;
+    let mut rng = StdRng::seed_from_u64(131254153212);
+
+    let inside = (n / blocks) as f64;
+    let outside = (n - n / blocks) as f64;
+    println!("mixing,edges,matching,mean_path,max_path");
+    for step in 0..=10 {
+        let mixing = step as f64 / 10.0 * (blocks - 1) as f64 / blocks as f64;
+        let p_in = (degree * (1.0 - mixing) / inside).min(1.0);
+        let p_out = (degree * mixing / outside).min(1.0);
+        let model = BlockModel::planted(blocks, n, n, p_in, p_out).unwrap();
+
+        let graph = model.graph::<()>(&mut rng);
+        let capped = graph.kuhn_capped(usize::MAX);
+        let lengths = &capped.path_lengths;
+        let mean = lengths.iter().sum::<usize>() as f64 / lengths.len().max(1) as f64;
+        println!(
+            "{mixing:.3},{},{},{mean:.3},{}",
+            graph.num_edges(),
+            capped.matching.len(),
+            lengths.iter().max().copied().unwrap_or(0)
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use std::env;
+use std::path::Path;
+
+use kuhn::GenConfig;
+
+fn main() {
+    let config = GenConfig::builder()
+        .sizes(8, 8)
+        .edges(16)
+        .seed(131254153212)
+        .build()
+        .unwrap();
+    let mut rng = config.rng();
+    let graph = config.graph::<()>(&mut rng).unwrap();
+
+    match env::args().nth(1) {
+        Some(dir) => {
+            let matching = graph
+                .write_dot_frames::<false>(&mut rng, Path::new(&dir))
+                .unwrap();
+            eprintln!("wrote {} frames to {dir}", matching.len());
+        }
+        None => {
+            let matching = graph.kuhn::<false>(&mut rng);
+            graph.print_as_dot(&matching);
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use std::env;
+
+use rand::prelude::*;
+
+use kuhn::bench::{test_graph, Metric, Variant};
+use kuhn::GenConfig;
+
+fn main() {
+    let n: usize = env::args()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(1000);
+    let mut rng = StdRng::seed_from_u64(131254153212);
+
+    let metric = Metric::Both;
+    println!("variant,{}", metric.csv_header());
+    for (name, variant) in [
+        ("plain", Variant::Plain),
+        ("shuffle", Variant::Shuffle),
+        ("degree", Variant::Degree),
+    ] {
+        for n_edges in (n..=10 * n).step_by(n) {
+            let config = GenConfig::builder()
+                .sizes(n, n)
+                .edges(n_edges)
+                .build()
+                .unwrap();
+            let sample = test_graph(&mut rng, &config, variant, metric).unwrap();
+            println!("{name},{},{n_edges},{}", n * n, sample.csv_fields());
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::env;
+use std::path::Path;
+use std::process;
+
+use kuhn::{
+    AssignmentSolver, CostMatrix, EdgeWeights, Hungarian, WeightedGreedy, WeightedMatchingSolver,
+};
+
+fn fail(err: impl std::fmt::Display) -> ! {
+    eprintln!("error: {err}");
+    process::exit(1);
+}
+
+fn main() {
+    let costs = match env::args().nth(1) {
+        Some(path) => CostMatrix::load(Path::new(&path)).unwrap_or_else(|err| fail(err)),
+        None => CostMatrix::new(
+            4,
+            4,
+            vec![
+                1, 2, 7, 8, //
+                2, 9, 3, 7, //
+                5, 8, 2, 8, //
+                7, 6, 9, 4,
+            ],
+        )
+        .unwrap(),
+    };
+
+    let (matching, cost) =
+        AssignmentSolver::solve(&Hungarian, &costs).unwrap_or_else(|err| fail(err));
+    println!("hungarian: cost {cost}, {} pairs", matching.len());
+
+    // Greedy maximizes weight, so turn every cost into a positive profit below the largest.
+    let (rows, cols) = (costs.rows(), costs.cols());
+    let cells = (0..rows).flat_map(|i| (0..cols).map(move |j| (i, j)));
+    let largest = cells
+        .clone()
+        .map(|(i, j)| costs.get(i, j))
+        .max()
+        .unwrap_or(0);
+    let mut weights = EdgeWeights::new();
+    for (i, j) in cells {
+        let profit = largest
+            .checked_sub(costs.get(i, j))
+            .and_then(|p| p.checked_add(1))
+            .unwrap_or_else(|| fail("cost range does not fit in an i64"));
+        weights.set(i, j, profit);
+    }
+    let (greedy, _) = WeightedGreedy
+        .solve(rows, cols, &weights)
+        .unwrap_or_else(|err| fail(err));
+    let greedy_cost: i128 = greedy
+        .pairs()
+        .map(|(i, j)| i128::from(costs.get(i, j)))
+        .sum();
+    println!("greedy: cost {greedy_cost}, {} pairs", greedy.len());
+
+    println!("row,col,cost");
+    for (i, j) in matching.as_coo() {
+        println!("{i},{j},{}", costs.get(i, j));
+    }
+}
@@ -0,0 +1,151 @@
+#ifndef KUHN_H
+#define KUHN_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Returned by the lookups for an unmatched vertex.
+ */
+#define KUHN_UNMATCHED SIZE_MAX
+
+typedef enum KuhnStatus {
+  KUHN_STATUS_OK = 0,
+  KUHN_STATUS_NULL_POINTER = 1,
+  KUHN_STATUS_OUT_OF_RANGE = 2,
+  KUHN_STATUS_PANIC = 3,
+} KuhnStatus;
+
+/**
+ * Opaque graph handle.
+ */
+typedef struct KuhnGraph KuhnGraph;
+
+/**
+ * Opaque matching handle.
+ */
+typedef struct KuhnMatching KuhnMatching;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Graph without edges; free it with [`kuhn_graph_free`].
+ */
+KuhnGraph *kuhn_graph_new(size_t left_len, size_t right_len);
+
+/**
+ * Graph with the `num_edges` edges `(left[k], right[k])`, or null if one is out of range.
+ *
+ * # Safety
+ *
+ * `left` and `right` must each point to `num_edges` readable values, or may be null when
+ * `num_edges` is 0.
+ */
+KuhnGraph *kuhn_graph_from_edges(size_t left_len,
+                                 size_t right_len,
+                                 const size_t *left,
+                                 const size_t *right,
+                                 size_t num_edges);
+
+/**
+ * # Safety
+ *
+ * `graph` must come from this library and not have been freed.
+ */
+KuhnStatus kuhn_graph_add_edge(KuhnGraph *graph, size_t left, size_t right);
+
+/**
+ * Adds the edges `(left[k], right[k])`. Nothing is added unless every endpoint is in range.
+ *
+ * # Safety
+ *
+ * `graph` must come from this library and not have been freed; `left` and `right` must each
+ * point to `num_edges` readable values, or may be null when `num_edges` is 0.
+ */
+KuhnStatus kuhn_graph_add_edges(KuhnGraph *graph,
+                                const size_t *left,
+                                const size_t *right,
+                                size_t num_edges);
+
+/**
+ * # Safety
+ *
+ * `graph` must be null or a live handle from this library.
+ */
+size_t kuhn_graph_num_edges(const KuhnGraph *graph);
+
+/**
+ * # Safety
+ *
+ * `graph` must be null or a handle from this library that has not been freed yet.
+ */
+void kuhn_graph_free(KuhnGraph *graph);
+
+/**
+ * Maximum matching of `graph`, or null if `graph` is null; free it with
+ * [`kuhn_matching_free`]. The graph stays valid and unchanged.
+ *
+ * # Safety
+ *
+ * `graph` must be null or a live handle from this library.
+ */
+KuhnMatching *kuhn_solve(const KuhnGraph *graph);
+
+/**
+ * # Safety
+ *
+ * `matching` must be null or a live handle from this library.
+ */
+size_t kuhn_matching_len(const KuhnMatching *matching);
+
+/**
+ * Partner of left vertex `i`, or [`KUHN_UNMATCHED`].
+ *
+ * # Safety
+ *
+ * `matching` must be null or a live handle from this library.
+ */
+size_t kuhn_matching_match_of_left(const KuhnMatching *matching, size_t i);
+
+/**
+ * Partner of right vertex `j`, or [`KUHN_UNMATCHED`].
+ *
+ * # Safety
+ *
+ * `matching` must be null or a live handle from this library.
+ */
+size_t kuhn_matching_match_of_right(const KuhnMatching *matching, size_t j);
+
+/**
+ * Writes up to `capacity` matched pairs, sorted by left vertex, into `left` and `right` and
+ * returns how many were written. Size the arrays with [`kuhn_matching_len`].
+ *
+ * # Safety
+ *
+ * `matching` must be null or a live handle from this library; `left` and `right` must each
+ * point to `capacity` writable values, or may be null when `capacity` is 0.
+ */
+size_t kuhn_matching_pairs(const KuhnMatching *matching,
+                           size_t *left,
+                           size_t *right,
+                           size_t capacity);
+
+/**
+ * # Safety
+ *
+ * `matching` must be null or a handle from this library that has not been freed yet.
+ */
+void kuhn_matching_free(KuhnMatching *matching);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* KUHN_H */
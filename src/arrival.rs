@@ -0,0 +1,101 @@
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::input::open_input;
+
+/// The order left vertices arrive in, each at most once. Vertices that are not listed never
+/// arrive, so replaying only part of the log leaves them unmatched.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArrivalOrder {
+    order: Vec<usize>,
+}
+
+impl ArrivalOrder {
+    pub fn new(left_len: usize, order: Vec<usize>) -> Result<ArrivalOrder> {
+        let mut seen = vec![false; left_len];
+        for &v in &order {
+            if v >= left_len {
+                return Err(Error::IndexOutOfRange {
+                    index: v,
+                    len: left_len,
+                });
+            }
+            if std::mem::replace(&mut seen[v], true) {
+                return Err(Error::InvalidParameter(format!(
+                    "left vertex {v} arrives twice"
+                )));
+            }
+        }
+        Ok(ArrivalOrder { order })
+    }
+
+    pub fn identity(left_len: usize) -> ArrivalOrder {
+        ArrivalOrder {
+            order: (0..left_len).collect(),
+        }
+    }
+
+    pub fn load(path: &Path, left_len: usize) -> Result<ArrivalOrder> {
+        ArrivalOrder::read(BufReader::new(open_input(path)?), left_len)
+    }
+
+    /// Reads one arrival per line, either `vertex` in arrival order or `vertex,timestamp`
+    /// with numeric timestamps, which are then sorted with ties kept in file order. A header
+    /// on the first line and `#` comments are skipped.
+    pub fn read<R: BufRead>(reader: R, left_len: usize) -> Result<ArrivalOrder> {
+        let mut arrivals = Vec::new();
+        let mut timestamped = None;
+        for (k, line) in reader.lines().enumerate() {
+            let line = line?;
+            let text = line.trim();
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+            let invalid = |message: String| Error::ParseError {
+                line: k + 1,
+                message,
+            };
+
+            let mut fields = text.split(',').map(str::trim);
+            let vertex = fields.next().unwrap_or_default();
+            let time = fields.next();
+            let Ok(vertex) = vertex.parse::<usize>() else {
+                if k == 0 {
+                    continue;
+                }
+                return Err(invalid(format!("invalid left vertex `{vertex}`")));
+            };
+            let time = match time {
+                None => None,
+                Some(time) => Some(
+                    time.parse::<f64>()
+                        .ok()
+                        .filter(|t| t.is_finite())
+                        .ok_or_else(|| invalid(format!("invalid timestamp `{time}`")))?,
+                ),
+            };
+            if *timestamped.get_or_insert(time.is_some()) != time.is_some() {
+                return Err(invalid(
+                    "either every arrival has a timestamp or none does".to_string(),
+                ));
+            }
+            arrivals.push((time.unwrap_or(0.0), vertex));
+        }
+
+        arrivals.sort_by(|a, b| a.0.total_cmp(&b.0));
+        ArrivalOrder::new(left_len, arrivals.into_iter().map(|(_, v)| v).collect())
+    }
+
+    pub fn as_slice(&self) -> &[usize] {
+        &self.order
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+}
@@ -0,0 +1,468 @@
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::input::{input_extension, open_input};
+use crate::matching::Matching;
+
+const NPY_MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+type DecodeFn = fn(&[u8]) -> Option<i64>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CostMatrix {
+    rows: usize,
+    cols: usize,
+    costs: Vec<i64>,
+}
+
+impl CostMatrix {
+    pub fn new(rows: usize, cols: usize, costs: Vec<i64>) -> Result<CostMatrix> {
+        if rows.checked_mul(cols) != Some(costs.len()) {
+            return Err(Error::InvalidFormat(format!(
+                "{} costs do not fill a {rows}x{cols} matrix",
+                costs.len()
+            )));
+        }
+        Ok(CostMatrix { rows, cols, costs })
+    }
+
+    pub fn load(path: &Path) -> Result<CostMatrix> {
+        if input_extension(path).is_some_and(|ext| ext == "npy") {
+            CostMatrix::read_npy(BufReader::new(open_input(path)?))
+        } else {
+            CostMatrix::read_text(BufReader::new(open_input(path)?))
+        }
+    }
+
+    pub fn read_text<R: BufRead>(reader: R) -> Result<CostMatrix> {
+        let mut rows = 0;
+        let mut cols = None;
+        let mut costs = Vec::new();
+
+        for (k, line) in reader.lines().enumerate() {
+            let line = line?;
+            let text = line.trim();
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+
+            let before = costs.len();
+            for field in text.split(|c: char| c == ',' || c.is_whitespace()) {
+                if field.is_empty() {
+                    continue;
+                }
+                let cost = parse_cost(field).ok_or_else(|| Error::ParseError {
+                    line: k + 1,
+                    message: format!("invalid cost `{field}`"),
+                })?;
+                costs.push(cost);
+            }
+
+            let width = costs.len() - before;
+            if *cols.get_or_insert(width) != width {
+                return Err(Error::ParseError {
+                    line: k + 1,
+                    message: format!("expected {} costs, found {width}", cols.unwrap_or(0)),
+                });
+            }
+            rows += 1;
+        }
+
+        CostMatrix::new(rows, cols.unwrap_or(0), costs)
+    }
+
+    pub fn read_npy<R: Read>(mut reader: R) -> Result<CostMatrix> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic[..6] != NPY_MAGIC {
+            return Err(Error::InvalidFormat("not a .npy file".to_string()));
+        }
+
+        let header_len = if magic[6] == 1 {
+            let mut len = [0; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_le_bytes(len) as usize
+        } else {
+            let mut len = [0; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        };
+        let mut header = vec![0; header_len];
+        reader.read_exact(&mut header)?;
+        let header = String::from_utf8_lossy(&header);
+
+        let descr = header_value(&header, "descr")
+            .map(|d| d.trim_matches(|c| c == '\'' || c == '"'))
+            .ok_or_else(|| Error::InvalidFormat("missing descr in .npy header".to_string()))?;
+        let fortran_order = header_value(&header, "fortran_order") == Some("True");
+        let shape: Vec<usize> = header_value(&header, "shape")
+            .ok_or_else(|| Error::InvalidFormat("missing shape in .npy header".to_string()))?
+            .trim_matches(|c| c == '(' || c == ')')
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse())
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|_| Error::InvalidFormat("invalid shape in .npy header".to_string()))?;
+        let &[rows, cols] = shape.as_slice() else {
+            return Err(Error::InvalidFormat(format!(
+                "expected a 2-dimensional array, found shape {shape:?}"
+            )));
+        };
+
+        let (width, decode): (usize, DecodeFn) = match descr {
+            "<i8" => (8, |b| Some(i64::from_le_bytes(b.try_into().ok()?))),
+            "<i4" => (4, |b| Some(i32::from_le_bytes(b.try_into().ok()?) as i64)),
+            "<i2" => (2, |b| Some(i16::from_le_bytes(b.try_into().ok()?) as i64)),
+            "|i1" => (1, |b| Some(b[0] as i8 as i64)),
+            "|u1" => (1, |b| Some(b[0] as i64)),
+            "<u2" => (2, |b| Some(u16::from_le_bytes(b.try_into().ok()?) as i64)),
+            "<u4" => (4, |b| Some(u32::from_le_bytes(b.try_into().ok()?) as i64)),
+            "<f8" => (8, |b| round_cost(f64::from_le_bytes(b.try_into().ok()?))),
+            "<f4" => (4, |b| {
+                round_cost(f32::from_le_bytes(b.try_into().ok()?) as f64)
+            }),
+            _ => {
+                return Err(Error::InvalidFormat(format!(
+                    "unsupported .npy dtype `{descr}`"
+                )))
+            }
+        };
+
+        let len = rows.checked_mul(cols).ok_or(Error::Overflow)?;
+        let mut costs = vec![0; len];
+        let mut value = vec![0; width];
+        for k in 0..len {
+            reader.read_exact(&mut value)?;
+            let index = if fortran_order {
+                (k % rows) * cols + k / rows
+            } else {
+                k
+            };
+            costs[index] = decode(&value).ok_or_else(|| {
+                Error::InvalidFormat(format!("cost at position {k} is not finite or does not fit in an i64"))
+            })?;
+        }
+
+        CostMatrix::new(rows, cols, costs)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> i64 {
+        self.costs[row * self.cols + col]
+    }
+
+    /// ```
+    /// use kuhn::CostMatrix;
+    ///
+    /// let costs = CostMatrix::new(2, 2, vec![1, 2, 2, 9]).unwrap();
+    /// let (matching, cost) = costs.min_cost_assignment().unwrap();
+    /// assert_eq!(cost, 4);
+    /// assert_eq!(matching.as_coo(), vec![(0, 1), (1, 0)]);
+    /// ```
+    pub fn min_cost_assignment(&self) -> Result<(Matching, i64)> {
+        let (matching, cost, _) = self.min_cost_assignment_with_duals()?;
+        Ok((matching, cost))
+    }
+
+    /// The potentials are kept in `i128` while solving, so any `i64` costs are solved exactly;
+    /// a total or a potential that does not fit back in an `i64` is an
+    /// [`Error::CostOverflow`] rather than a wrapped value.
+    pub fn min_cost_assignment_with_duals(&self) -> Result<(Matching, i64, AssignmentDuals)> {
+        let (matched_right, duals) = if self.rows <= self.cols {
+            let state = hungarian(self.rows, self.cols, |i, j| self.get(i, j));
+            let duals = AssignmentDuals {
+                row_potentials: narrow_potentials(&state.u[1..])?,
+                col_potentials: narrow_potentials(&state.v[1..])?,
+            };
+            (state.matched_right(), duals)
+        } else {
+            let state = hungarian(self.cols, self.rows, |j, i| self.get(i, j));
+            let mut matched_right = vec![None; self.cols];
+            for (i, j) in state.matched_right().into_iter().enumerate() {
+                if let Some(j) = j {
+                    matched_right[j] = Some(i);
+                }
+            }
+            let duals = AssignmentDuals {
+                row_potentials: narrow_potentials(&state.v[1..])?,
+                col_potentials: narrow_potentials(&state.u[1..])?,
+            };
+            (matched_right, duals)
+        };
+
+        let cost = checked_total(
+            "total cost",
+            matched_right
+                .iter()
+                .enumerate()
+                .filter_map(|(j, i)| i.map(|i| self.get(i, j))),
+        )?;
+        Ok((
+            Matching::from_matched_right(self.rows, matched_right),
+            cost,
+            duals,
+        ))
+    }
+
+    pub fn satisfies_complementary_slackness(
+        &self,
+        matching: &Matching,
+        duals: &AssignmentDuals,
+    ) -> bool {
+        if matching.matched_left().len() != self.rows
+            || matching.matched_right().len() != self.cols
+            || duals.row_potentials.len() != self.rows
+            || duals.col_potentials.len() != self.cols
+        {
+            return false;
+        }
+
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                let slack = i128::from(self.get(i, j))
+                    - i128::from(duals.row_potentials[i])
+                    - i128::from(duals.col_potentials[j]);
+                if slack < 0 || (matching.match_of_left(i) == Some(j) && slack != 0) {
+                    return false;
+                }
+            }
+        }
+
+        let (free_side, free_matches) = if self.rows <= self.cols {
+            (&duals.col_potentials, matching.matched_right())
+        } else {
+            (&duals.row_potentials, matching.matched_left())
+        };
+        let full_side_matched = matching.len() == self.rows.min(self.cols);
+        full_side_matched
+            && free_side
+                .iter()
+                .zip(free_matches)
+                .all(|(&p, m)| p <= 0 && (m.is_some() || p == 0))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssignmentDuals {
+    pub row_potentials: Vec<i64>,
+    pub col_potentials: Vec<i64>,
+}
+
+impl AssignmentDuals {
+    /// Exact, as the sum may not fit in an `i64` even when the total cost does.
+    pub fn objective(&self) -> i128 {
+        self.row_potentials
+            .iter()
+            .chain(&self.col_potentials)
+            .map(|&p| i128::from(p))
+            .sum()
+    }
+}
+
+fn hungarian<F: Fn(usize, usize) -> i64>(rows: usize, cols: usize, cost: F) -> HungarianState {
+    let mut state = HungarianState::new(cols);
+    for i in 0..rows {
+        state.add_row(i, &cost);
+    }
+    state
+}
+
+#[derive(Clone, Debug)]
+struct HungarianState {
+    u: Vec<i128>,
+    v: Vec<i128>,
+    row_of: Vec<usize>,
+    way: Vec<usize>,
+}
+
+impl HungarianState {
+    fn new(cols: usize) -> HungarianState {
+        HungarianState {
+            u: vec![0],
+            v: vec![0; cols + 1],
+            row_of: vec![0; cols + 1],
+            way: vec![0; cols + 1],
+        }
+    }
+
+    fn add_row<F: Fn(usize, usize) -> i64>(&mut self, row: usize, cost: F) {
+        let cols = self.v.len() - 1;
+        let i = row + 1;
+        self.u.push(0);
+
+        self.row_of[0] = i;
+        let mut j0 = 0;
+        let mut min_slack = vec![i128::MAX; cols + 1];
+        let mut used = vec![false; cols + 1];
+
+        loop {
+            used[j0] = true;
+            let i0 = self.row_of[j0];
+            let mut delta = i128::MAX;
+            let mut j1 = 0;
+            for j in 1..=cols {
+                if used[j] {
+                    continue;
+                }
+                let slack = i128::from(cost(i0 - 1, j - 1)) - self.u[i0] - self.v[j];
+                if slack < min_slack[j] {
+                    min_slack[j] = slack;
+                    self.way[j] = j0;
+                }
+                if min_slack[j] < delta {
+                    delta = min_slack[j];
+                    j1 = j;
+                }
+            }
+            for j in 0..=cols {
+                if used[j] {
+                    self.u[self.row_of[j]] += delta;
+                    self.v[j] -= delta;
+                } else {
+                    min_slack[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if self.row_of[j0] == 0 {
+                break;
+            }
+        }
+
+        while j0 != 0 {
+            let j1 = self.way[j0];
+            self.row_of[j0] = self.row_of[j1];
+            j0 = j1;
+        }
+    }
+
+    fn matched_right(&self) -> Vec<Option<usize>> {
+        self.row_of[1..]
+            .iter()
+            .map(|&i| (i != 0).then(|| i - 1))
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IncrementalHungarian {
+    costs: Vec<i64>,
+    rows: usize,
+    cols: usize,
+    state: HungarianState,
+}
+
+impl IncrementalHungarian {
+    pub fn new(cols: usize) -> IncrementalHungarian {
+        IncrementalHungarian {
+            costs: Vec::new(),
+            rows: 0,
+            cols,
+            state: HungarianState::new(cols),
+        }
+    }
+
+    pub fn add_row(&mut self, costs: &[i64]) -> Result<usize> {
+        if costs.len() != self.cols {
+            return Err(Error::InvalidParameter(format!(
+                "row has {} costs, expected {}",
+                costs.len(),
+                self.cols
+            )));
+        }
+        if self.rows == self.cols {
+            return Err(Error::InvalidParameter(
+                "every column is already assigned".to_string(),
+            ));
+        }
+
+        self.costs.extend_from_slice(costs);
+        let row = self.rows;
+        self.rows += 1;
+
+        let (all_costs, cols) = (&self.costs, self.cols);
+        self.state.add_row(row, |i, j| all_costs[i * cols + j]);
+        Ok(row)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn matching(&self) -> Matching {
+        Matching::from_matched_right(self.rows, self.state.matched_right())
+    }
+
+    pub fn duals(&self) -> Result<AssignmentDuals> {
+        Ok(AssignmentDuals {
+            row_potentials: narrow_potentials(&self.state.u[1..])?,
+            col_potentials: narrow_potentials(&self.state.v[1..])?,
+        })
+    }
+
+    pub fn cost(&self) -> Result<i64> {
+        checked_total(
+            "total cost",
+            self.state
+                .row_of
+                .iter()
+                .enumerate()
+                .skip(1)
+                .filter(|&(_, &i)| i != 0)
+                .map(|(j, &i)| self.costs[(i - 1) * self.cols + j - 1]),
+        )
+    }
+}
+
+/// Sums `values` without wrapping, failing with [`Error::CostOverflow`] naming `what` when
+/// the result leaves the `i64` range. Partial sums go through `i128`, so only the total
+/// matters, not the order.
+pub(crate) fn checked_total<I: IntoIterator<Item = i64>>(what: &str, values: I) -> Result<i64> {
+    let total: i128 = values.into_iter().map(i128::from).sum();
+    i64::try_from(total).map_err(|_| Error::CostOverflow(format!("{what} {total}")))
+}
+
+fn narrow_potentials(potentials: &[i128]) -> Result<Vec<i64>> {
+    potentials
+        .iter()
+        .map(|&p| i64::try_from(p).map_err(|_| Error::CostOverflow(format!("dual potential {p}"))))
+        .collect()
+}
+
+fn header_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let start = header.find(&format!("'{key}'"))? + key.len() + 2;
+    let rest = header[start..].trim_start().strip_prefix(':')?.trim_start();
+    let end = if rest.starts_with('(') {
+        rest.find(')')? + 1
+    } else {
+        rest.find([',', '}'])?
+    };
+    Some(rest[..end].trim())
+}
+
+fn parse_cost(field: &str) -> Option<i64> {
+    field
+        .parse()
+        .ok()
+        .or_else(|| field.parse().ok().and_then(round_cost))
+}
+
+/// `None` for costs an `i64` cannot hold, which `as` would silently saturate.
+fn round_cost(cost: f64) -> Option<i64> {
+    let cost = cost.round();
+    (-9_223_372_036_854_775_808.0..9_223_372_036_854_775_808.0)
+        .contains(&cost)
+        .then_some(cost as i64)
+}
@@ -0,0 +1,111 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::core::{self, CsrView};
+use crate::graph::Adjacency;
+use crate::hopcroft_karp::hopcroft_karp;
+use crate::matching::Matching;
+
+/// Graphs a worker claims at a time, so tiny instances do not contend on the shared counter.
+const CHUNK: usize = 64;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BatchAlgorithm {
+    /// Kuhn's algorithm from [`crate::core`], with one set of buffers per worker reused for
+    /// every graph it solves.
+    #[default]
+    Kuhn,
+    HopcroftKarp,
+}
+
+/// Buffers a worker keeps between graphs; they only grow.
+#[derive(Default)]
+struct Scratch {
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+    matched_right: Vec<usize>,
+    buffer: Vec<usize>,
+}
+
+impl Scratch {
+    fn solve<G: Adjacency>(&mut self, graph: &G, algorithm: BatchAlgorithm) -> Matching {
+        if algorithm == BatchAlgorithm::HopcroftKarp {
+            return hopcroft_karp(graph);
+        }
+        let (l, r) = (graph.left_len(), graph.right_len());
+        self.offsets.clear();
+        self.targets.clear();
+        self.offsets.push(0);
+        for v in 0..l {
+            self.targets.extend(graph.left_neighbours(v));
+            self.offsets.push(self.targets.len());
+        }
+        self.matched_right.clear();
+        self.matched_right.resize(r, usize::MAX);
+        self.buffer.resize(core::scratch_len(l).max(self.buffer.len()), 0);
+
+        let view = CsrView::new(r, &self.offsets, &self.targets).expect("adjacency is in range");
+        core::maximum_matching(&view, &mut self.matched_right, &mut self.buffer)
+            .expect("buffers are sized for the graph");
+        let matched_right = self
+            .matched_right
+            .iter()
+            .map(|&i| (i != usize::MAX).then_some(i))
+            .collect();
+        Matching::from_matched_right(l, matched_right)
+    }
+}
+
+/// Maximum matching of every graph, in order, solved on `threads` workers. Meant for many
+/// small instances, where spawning per graph or allocating per solve would dominate; one large
+/// graph is better served by [`crate::hopcroft_karp_parallel`]. [`crate::BipartiteGraph`]
+/// keeps its lists in `RefCell`s and is not `Sync`, so convert it with `to_csr` first.
+pub fn solve_batch<G: Adjacency + Sync>(
+    graphs: &[G],
+    algorithm: BatchAlgorithm,
+    threads: usize,
+) -> Vec<Matching> {
+    let threads = threads.max(1).min(graphs.len().div_ceil(CHUNK));
+    if threads <= 1 {
+        let mut scratch = Scratch::default();
+        return graphs
+            .iter()
+            .map(|graph| scratch.solve(graph, algorithm))
+            .collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let solved: Vec<Vec<(usize, Matching)>> = thread::scope(|s| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut scratch = Scratch::default();
+                    let mut solved = Vec::new();
+                    loop {
+                        let start = next.fetch_add(CHUNK, Ordering::Relaxed);
+                        if start >= graphs.len() {
+                            break solved;
+                        }
+                        let chunk = &graphs[start..graphs.len().min(start + CHUNK)];
+                        for (k, graph) in chunk.iter().enumerate() {
+                            solved.push((start + k, scratch.solve(graph, algorithm)));
+                        }
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("batch worker panicked"))
+            .collect()
+    });
+
+    let mut matchings: Vec<Option<Matching>> = vec![None; graphs.len()];
+    for (k, matching) in solved.into_iter().flatten() {
+        matchings[k] = Some(matching);
+    }
+    matchings
+        .into_iter()
+        .map(|matching| matching.expect("every graph is solved"))
+        .collect()
+}
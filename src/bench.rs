@@ -0,0 +1,1144 @@
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::prelude::*;
+
+use crate::error;
+use crate::flow::push_relabel_matching;
+use crate::generate::GenConfig;
+use crate::graph::{BipartiteGraph, Side};
+use crate::hopcroft_karp::{
+    hopcroft_karp, hopcroft_karp_full_reset_observed, hopcroft_karp_observed,
+    hopcroft_karp_parallel, hopcroft_karp_parallel_deterministic,
+};
+use crate::kuhn::{Deadline, Observer, OpCounts, PathLengths, PhaseTimings};
+use crate::matching::{Matching, MatchingSimilarity};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    Time,
+    Ops,
+    Both,
+    Phases,
+}
+
+impl Metric {
+    pub fn csv_header(self) -> &'static str {
+        match self {
+            Metric::Time => "n,m,time",
+            Metric::Ops => "n,m,edge_scans,match_flips",
+            Metric::Both => "n,m,time,edge_scans,match_flips",
+            Metric::Phases => {
+                "n,m,time,edge_scans,match_flips,bfs,dfs,bookkeeping,search_p50,search_p90,search_p99"
+            }
+        }
+    }
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Metric, String> {
+        match s {
+            "time" => Ok(Metric::Time),
+            "ops" => Ok(Metric::Ops),
+            "both" => Ok(Metric::Both),
+            "phases" => Ok(Metric::Phases),
+            _ => Err(format!(
+                "unknown metric `{s}` (expected time, ops, both or phases)"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    Plain,
+    Shuffle,
+    Offset,
+    Degree,
+    Bfs,
+    HopcroftKarp,
+    /// Hopcroft-Karp clearing its distance array every phase, the baseline for `HopcroftKarp`.
+    HopcroftKarpReset,
+    ParallelHopcroftKarp,
+    PushRelabel,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 9] = [
+        Variant::Plain,
+        Variant::Shuffle,
+        Variant::Offset,
+        Variant::Degree,
+        Variant::Bfs,
+        Variant::HopcroftKarp,
+        Variant::HopcroftKarpReset,
+        Variant::ParallelHopcroftKarp,
+        Variant::PushRelabel,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Plain => "plain",
+            Variant::Shuffle => "shuffle",
+            Variant::Offset => "offset",
+            Variant::Degree => "degree",
+            Variant::Bfs => "bfs",
+            Variant::HopcroftKarp => "hopcroft-karp",
+            Variant::HopcroftKarpReset => "hopcroft-karp-reset",
+            Variant::ParallelHopcroftKarp => "parallel-hopcroft-karp",
+            Variant::PushRelabel => "push-relabel",
+        }
+    }
+
+    pub fn is_observable(self) -> bool {
+        self != Variant::ParallelHopcroftKarp
+    }
+
+    /// Reorders `graph`'s lists the way this variant expects, once and before timing starts:
+    /// `degree` moves low-degree right vertices to the front, keeping the layout order among
+    /// ties. The other variants take the graph as it is.
+    pub fn prepare<T>(self, graph: &BipartiteGraph<T>) {
+        if self == Variant::Degree {
+            graph.sort_neighbours_by_degree();
+        }
+    }
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Variant, String> {
+        Variant::ALL
+            .into_iter()
+            .find(|v| v.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Variant::ALL.iter().map(|v| v.name()).collect();
+                format!("unknown variant `{s}` (expected {})", names.join(", "))
+            })
+    }
+}
+
+pub struct Sample {
+    pub time: Option<u128>,
+    pub ops: Option<OpCounts>,
+    pub phases: Option<PhaseTimings>,
+    pub censored: Option<bool>,
+    pub trace: Option<Trace>,
+}
+
+/// What a sample measured: the generator seed, a fingerprint of the RNG state right before
+/// the measured solve, and a hash of the solved instance. Rerunning from `seed` with the same
+/// options must reproduce both hashes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Trace {
+    pub seed: u64,
+    pub rng: u64,
+    pub instance: u64,
+}
+
+impl Trace {
+    pub const CSV_COLUMNS: &'static str = "seed,rng,instance";
+}
+
+/// FNV-1a over the side sizes and the sorted edge list.
+pub fn instance_hash<T>(graph: &BipartiteGraph<T>) -> u64 {
+    let mut edges = graph.edges();
+    edges.sort_unstable();
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let sizes = [graph.left_nodes.len(), graph.right_nodes.len()];
+    for value in sizes
+        .into_iter()
+        .chain(edges.into_iter().flat_map(|(i, j)| [i, j]))
+    {
+        for byte in (value as u64).to_le_bytes() {
+            hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// The next output of a copy of `rng`, which leaves `rng` itself untouched.
+pub fn rng_fingerprint(rng: &StdRng) -> u64 {
+    rng.clone().gen()
+}
+
+impl Sample {
+    pub fn csv_fields(&self) -> String {
+        let mut fields = Vec::new();
+        if let Some(time) = self.time {
+            fields.push(time.to_string());
+        }
+        if let Some(ops) = self.ops {
+            fields.push(ops.edge_scans.to_string());
+            fields.push(ops.match_flips.to_string());
+        }
+        if let Some(phases) = &self.phases {
+            for duration in [phases.bfs, phases.dfs, phases.bookkeeping] {
+                fields.push(duration.as_nanos().to_string());
+            }
+            for q in [0.5, 0.9, 0.99] {
+                let quantile = phases.search_quantile(q).unwrap_or_default();
+                fields.push(quantile.as_nanos().to_string());
+            }
+        }
+        if let Some(censored) = self.censored {
+            fields.push(u8::from(censored).to_string());
+        }
+        if let Some(trace) = self.trace {
+            fields.push(trace.seed.to_string());
+            fields.push(format!("{:016x}", trace.rng));
+            fields.push(format!("{:016x}", trace.instance));
+        }
+        fields.join(",")
+    }
+}
+
+pub fn test_graph(
+    rng: &mut StdRng,
+    config: &GenConfig,
+    variant: Variant,
+    metric: Metric,
+) -> error::Result<Sample> {
+    test_graph_with_timeout(rng, config, variant, metric, None)
+}
+
+pub fn test_graph_with_timeout(
+    rng: &mut StdRng,
+    config: &GenConfig,
+    variant: Variant,
+    metric: Metric,
+    timeout: Option<Duration>,
+) -> error::Result<Sample> {
+    let options = RunOptions {
+        timeout,
+        ..RunOptions::default()
+    };
+    test_graph_with_options(rng, config, variant, metric, &options)
+}
+
+/// The side a solver augments from; `Auto` picks the smaller one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Drive {
+    #[default]
+    Left,
+    Right,
+    Auto,
+}
+
+impl Drive {
+    pub fn side<T>(self, graph: &BipartiteGraph<T>) -> Side {
+        match self {
+            Drive::Left => Side::Left,
+            Drive::Right => Side::Right,
+            Drive::Auto => graph.smaller_side(),
+        }
+    }
+}
+
+impl FromStr for Drive {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Drive, String> {
+        match s {
+            "left" => Ok(Drive::Left),
+            "right" => Ok(Drive::Right),
+            "auto" => Ok(Drive::Auto),
+            _ => Err(format!(
+                "unknown drive `{s}` (expected left, right or auto)"
+            )),
+        }
+    }
+}
+
+/// Order of the neighbour lists in memory when timing starts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+    #[default]
+    Sorted,
+    Shuffled,
+}
+
+impl Layout {
+    pub fn name(self) -> &'static str {
+        match self {
+            Layout::Sorted => "sorted",
+            Layout::Shuffled => "shuffled",
+        }
+    }
+
+    /// Puts `graph`'s lists in this order; shuffling draws from `rng`.
+    pub fn arrange<T>(self, graph: &BipartiteGraph<T>, rng: &mut StdRng) {
+        match self {
+            Layout::Sorted => graph.sort_neighbours(),
+            Layout::Shuffled => graph.shuffle_neighbours(rng),
+        }
+    }
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Layout, String> {
+        match s {
+            "sorted" => Ok(Layout::Sorted),
+            "shuffled" => Ok(Layout::Shuffled),
+            _ => Err(format!(
+                "unknown layout `{s}` (expected sorted or shuffled)"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RunOptions {
+    /// Aborts a solve through its observer once it runs this long. Variants that are not
+    /// [`Variant::is_observable`] always finish and are only marked censored afterwards.
+    pub timeout: Option<Duration>,
+    /// Unmeasured solves on the same graph before the measured one.
+    pub warmup: usize,
+    /// Driving from the right solves the transpose, which is built before timing starts.
+    pub drive: Drive,
+    /// Attach a [`Trace`] to every sample.
+    pub trace: bool,
+    /// Pushes between global relabelings in the push-relabel variant; `None` uses the number
+    /// of vertices and `Some(0)` relabels only at the start.
+    pub relabel_every: Option<usize>,
+    /// Neighbour list order, arranged before warm-up and timing.
+    pub layout: Layout,
+    /// Run the parallel variant in its deterministic mode.
+    pub deterministic: bool,
+}
+
+/// Generates the instance from `config.rng()` and measures `variant` on it. `rng` only feeds
+/// the layout and the solver, so how many draws a randomized variant makes never changes the
+/// instances later calls generate.
+pub fn test_graph_with_options(
+    rng: &mut StdRng,
+    config: &GenConfig,
+    variant: Variant,
+    metric: Metric,
+    options: &RunOptions,
+) -> error::Result<Sample> {
+    let graph = config.graph::<()>(&mut config.rng())?;
+    let graph = match options.drive.side(&graph) {
+        Side::Left => graph,
+        Side::Right => graph.transpose(),
+    };
+    options.layout.arrange(&graph, rng);
+    variant.prepare(&graph);
+    let timeout = options.timeout;
+    for _ in 0..options.warmup {
+        timed_run(&graph, rng, variant, &mut (), options);
+    }
+    let censored =
+        |aborted: bool, time: u128| timeout.map(|timeout| aborted || time > timeout.as_nanos());
+    let trace = options.trace.then(|| Trace {
+        seed: config.seed(),
+        rng: rng_fingerprint(rng),
+        instance: instance_hash(&graph),
+    });
+
+    Ok(match metric {
+        Metric::Time => {
+            let (time, aborted) = timed_run(&graph, rng, variant, &mut (), options);
+            Sample {
+                time: Some(time),
+                ops: None,
+                phases: None,
+                censored: censored(aborted, time),
+                trace,
+            }
+        }
+        Metric::Ops | Metric::Both => {
+            let mut ops = OpCounts::default();
+            let (time, aborted) = timed_run(&graph, rng, variant, &mut ops, options);
+            Sample {
+                time: (metric == Metric::Both).then_some(time),
+                ops: Some(ops),
+                phases: None,
+                censored: censored(aborted, time),
+                trace,
+            }
+        }
+        Metric::Phases => {
+            let mut phases = PhaseTimings::default();
+            let (time, aborted) = timed_run(&graph, rng, variant, &mut phases, options);
+            Sample {
+                time: Some(time),
+                ops: Some(phases.ops),
+                phases: Some(phases),
+                censored: censored(aborted, time),
+                trace,
+            }
+        }
+    })
+}
+
+fn timed_run<T, O: Observer>(
+    graph: &BipartiteGraph<T>,
+    rng: &mut StdRng,
+    variant: Variant,
+    observer: &mut O,
+    options: &RunOptions,
+) -> (u128, bool) {
+    let start = Instant::now();
+    let aborted = match options.timeout {
+        Some(timeout) => {
+            let mut deadline = Deadline::new(observer, timeout);
+            let _matched = run_variant(graph, rng, variant, options, &mut deadline);
+            deadline.expired()
+        }
+        None => {
+            let _matched = run_variant(graph, rng, variant, options, observer);
+            false
+        }
+    };
+    (start.elapsed().as_nanos(), aborted)
+}
+
+pub(crate) fn run_variant<T, O: Observer>(
+    graph: &BipartiteGraph<T>,
+    rng: &mut StdRng,
+    variant: Variant,
+    options: &RunOptions,
+    observer: &mut O,
+) -> Matching {
+    match variant {
+        Variant::Plain => graph.kuhn_observed::<false, O>(rng, observer),
+        Variant::Shuffle => graph.kuhn_observed::<true, O>(rng, observer),
+        Variant::Offset => graph.kuhn_random_offset_observed(rng, observer),
+        Variant::Degree => graph.kuhn_observed::<false, O>(rng, observer),
+        Variant::Bfs => graph.kuhn_bfs_observed(observer),
+        Variant::HopcroftKarp => hopcroft_karp_observed(graph, observer),
+        Variant::HopcroftKarpReset => hopcroft_karp_full_reset_observed(graph, observer),
+        Variant::ParallelHopcroftKarp => {
+            let threads = thread::available_parallelism().map_or(1, |n| n.get());
+            if options.deterministic {
+                hopcroft_karp_parallel_deterministic(&graph.to_csr(), threads)
+            } else {
+                hopcroft_karp_parallel(&graph.to_csr(), threads)
+            }
+        }
+        Variant::PushRelabel => {
+            let vertices = graph.left_nodes.len() + graph.right_nodes.len();
+            let relabel_every = options.relabel_every.unwrap_or(vertices);
+            push_relabel_matching(graph, relabel_every, observer)
+        }
+    }
+}
+
+pub fn cell_seed(base: u64, cell: usize) -> u64 {
+    let mut z = base.wrapping_add(
+        (cell as u64)
+            .wrapping_add(1)
+            .wrapping_mul(0x9e37_79b9_7f4a_7c15),
+    );
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+pub fn run_ordered<T, F, E>(cells: usize, jobs: usize, run: F, mut emit: E)
+where
+    T: Send,
+    F: Fn(usize) -> T + Sync,
+    E: FnMut(usize, T),
+{
+    let jobs = jobs.clamp(1, cells.max(1));
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|s| {
+        for _ in 0..jobs {
+            let sender = sender.clone();
+            let (next, run) = (&next, &run);
+            s.spawn(move || loop {
+                let cell = next.fetch_add(1, Ordering::Relaxed);
+                if cell >= cells || sender.send((cell, run(cell))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut expected = 0;
+        for (cell, result) in receiver {
+            pending.insert(cell, result);
+            while let Some(result) = pending.remove(&expected) {
+                emit(expected, result);
+                expected += 1;
+            }
+        }
+    });
+}
+
+/// One instance solved from both sides; times are in nanoseconds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OrientationRow {
+    pub left: usize,
+    pub right: usize,
+    pub edges: usize,
+    pub size: usize,
+    pub left_time: u128,
+    pub right_time: u128,
+}
+
+impl OrientationRow {
+    pub const CSV_HEADER: &'static str =
+        "left,right,edges,size,left_time,right_time,smaller,faster";
+
+    pub fn faster_side(&self) -> Side {
+        if self.right_time < self.left_time {
+            Side::Right
+        } else {
+            Side::Left
+        }
+    }
+
+    pub fn csv_fields(&self) -> String {
+        let name = |side| match side {
+            Side::Left => "left",
+            Side::Right => "right",
+        };
+        let smaller = if self.right < self.left {
+            Side::Right
+        } else {
+            Side::Left
+        };
+        format!(
+            "{},{},{},{},{},{},{},{}",
+            self.left,
+            self.right,
+            self.edges,
+            self.size,
+            self.left_time,
+            self.right_time,
+            name(smaller),
+            name(self.faster_side())
+        )
+    }
+}
+
+/// Times `variant` driven from the left and, on the transpose, from the right.
+pub fn compare_orientations(
+    rng: &mut StdRng,
+    config: &GenConfig,
+    variant: Variant,
+) -> error::Result<OrientationRow> {
+    let graph = config.graph::<()>(&mut config.rng())?;
+    let transposed = graph.transpose();
+    variant.prepare(&graph);
+    variant.prepare(&transposed);
+
+    let start = Instant::now();
+    let size = run_variant(&graph, rng, variant, &RunOptions::default(), &mut ()).len();
+    let left_time = start.elapsed().as_nanos();
+    let start = Instant::now();
+    let right_size = run_variant(&transposed, rng, variant, &RunOptions::default(), &mut ()).len();
+    let right_time = start.elapsed().as_nanos();
+    debug_assert_eq!(size, right_size);
+
+    Ok(OrientationRow {
+        left: config.left_len(),
+        right: config.right_len(),
+        edges: graph.num_edges(),
+        size,
+        left_time,
+        right_time,
+    })
+}
+
+/// One instance solved with sorted and then shuffled neighbour lists; times are in
+/// nanoseconds. Shuffling also changes the order searches try edges in, so the edge scans
+/// tell a change in work apart from a change in cost per scan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LocalityRow {
+    pub left: usize,
+    pub right: usize,
+    pub edges: usize,
+    pub size: usize,
+    pub sorted_time: u128,
+    pub sorted_scans: u64,
+    pub shuffled_time: u128,
+    pub shuffled_scans: u64,
+}
+
+impl LocalityRow {
+    pub const CSV_HEADER: &'static str =
+        "left,right,edges,size,sorted_time,sorted_scans,shuffled_time,shuffled_scans,slowdown_per_scan";
+
+    /// Ratio of nanoseconds per edge scan, shuffled over sorted.
+    pub fn slowdown_per_scan(&self) -> f64 {
+        let per_scan = |time: u128, scans: u64| time as f64 / scans.max(1) as f64;
+        per_scan(self.shuffled_time, self.shuffled_scans)
+            / per_scan(self.sorted_time, self.sorted_scans)
+    }
+
+    pub fn csv_fields(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{}",
+            self.left,
+            self.right,
+            self.edges,
+            self.size,
+            self.sorted_time,
+            self.sorted_scans,
+            self.shuffled_time,
+            self.shuffled_scans,
+            self.slowdown_per_scan()
+        )
+    }
+}
+
+/// Times `variant` on one instance in both layouts. Edge scans come from a counted run on the
+/// same arrangement just before, so the timed runs carry no observer.
+pub fn compare_layouts(
+    rng: &mut StdRng,
+    config: &GenConfig,
+    variant: Variant,
+) -> error::Result<LocalityRow> {
+    let graph = config.graph::<()>(&mut config.rng())?;
+    let options = RunOptions::default();
+    let measure = |layout: Layout, rng: &mut StdRng| {
+        layout.arrange(&graph, rng);
+        variant.prepare(&graph);
+        let mut ops = OpCounts::default();
+        let size = run_variant(&graph, rng, variant, &options, &mut ops).len();
+        let start = Instant::now();
+        let _matched = run_variant(&graph, rng, variant, &options, &mut ());
+        (size, start.elapsed().as_nanos(), ops.edge_scans)
+    };
+    let (size, sorted_time, sorted_scans) = measure(Layout::Sorted, rng);
+    let (shuffled_size, shuffled_time, shuffled_scans) = measure(Layout::Shuffled, rng);
+    debug_assert_eq!(size, shuffled_size);
+
+    Ok(LocalityRow {
+        left: config.left_len(),
+        right: config.right_len(),
+        edges: graph.num_edges(),
+        size,
+        sorted_time,
+        sorted_scans,
+        shuffled_time,
+        shuffled_scans,
+    })
+}
+
+pub struct TransitionRow {
+    pub n: usize,
+    pub degree: f64,
+    pub trials: usize,
+    pub perfect: usize,
+    pub size_fraction: Summary,
+}
+
+impl TransitionRow {
+    pub const CSV_HEADER: &'static str =
+        "n,degree,trials,perfect_probability,fraction_mean,fraction_stddev,fraction_min,fraction_max";
+
+    pub fn perfect_probability(&self) -> f64 {
+        self.perfect as f64 / self.trials as f64
+    }
+
+    pub fn csv_fields(&self) -> String {
+        let fraction = &self.size_fraction;
+        format!(
+            "{},{},{},{},{},{},{},{}",
+            self.n,
+            self.degree,
+            self.trials,
+            self.perfect_probability(),
+            fraction.mean,
+            fraction.stddev,
+            fraction.min,
+            fraction.max
+        )
+    }
+}
+
+/// Matching size on `n x n` random graphs with `degree * n` edges, swept over average degree.
+/// Every (degree, trial) cell draws its graph from `cell_seed(seed, cell)`, so the rows do not
+/// depend on `jobs`.
+pub fn phase_transition(
+    seed: u64,
+    n: usize,
+    degrees: &[f64],
+    trials: usize,
+    jobs: usize,
+) -> error::Result<Vec<TransitionRow>> {
+    if n == 0 || trials == 0 {
+        return Err(error::Error::InvalidParameter(
+            "phase transition needs n > 0 and at least one trial".to_string(),
+        ));
+    }
+    if let Some(degree) = degrees.iter().find(|d| !(0.0..=n as f64).contains(*d)) {
+        return Err(error::Error::InvalidParameter(format!(
+            "average degree {degree} is not in [0, {n}]"
+        )));
+    }
+
+    let mut sizes = vec![Vec::with_capacity(trials); degrees.len()];
+    let mut failure = None;
+    run_ordered(
+        degrees.len() * trials,
+        jobs,
+        |cell| {
+            let config = GenConfig::builder()
+                .sizes(n, n)
+                .edges((degrees[cell / trials] * n as f64).round() as usize)
+                .seed(cell_seed(seed, cell))
+                .build()?;
+            let graph = config.graph::<()>(&mut config.rng())?;
+            Ok(hopcroft_karp(&graph).len())
+        },
+        |cell, size| match size {
+            Ok(size) => sizes[cell / trials].push(size),
+            Err(err) => {
+                failure.get_or_insert(err);
+            }
+        },
+    );
+    if let Some(err) = failure {
+        return Err(err);
+    }
+
+    Ok(degrees
+        .iter()
+        .zip(sizes)
+        .map(|(&degree, sizes)| {
+            let fractions: Vec<f64> = sizes.iter().map(|&size| size as f64 / n as f64).collect();
+            TransitionRow {
+                n,
+                degree,
+                trials,
+                perfect: sizes.iter().filter(|&&size| size == n).count(),
+                size_fraction: Summary::of(&fractions),
+            }
+        })
+        .collect())
+}
+
+pub struct PathLengthRow {
+    pub left: usize,
+    pub right: usize,
+    pub edges: usize,
+    pub repetitions: usize,
+    pub length: usize,
+    pub count: u64,
+}
+
+impl PathLengthRow {
+    pub const CSV_HEADER: &'static str = "left,right,edges,repetitions,length,count";
+
+    pub fn csv_fields(&self) -> String {
+        format!(
+            "{},{},{},{},{},{}",
+            self.left, self.right, self.edges, self.repetitions, self.length, self.count
+        )
+    }
+}
+
+/// Histogram of the augmenting-path lengths `variant` takes on `left x right` random graphs,
+/// one density point per entry of `edges`, summed over `repetitions` graphs each. Rows come
+/// in long format, one per (density, length) that occurred, lengths ascending. Every
+/// (density, repetition) cell draws from `cell_seed(seed, cell)`, so the rows do not depend
+/// on `jobs`.
+pub fn path_length_histograms(
+    seed: u64,
+    (left, right): (usize, usize),
+    edges: &[usize],
+    repetitions: usize,
+    variant: Variant,
+    jobs: usize,
+) -> error::Result<Vec<PathLengthRow>> {
+    if repetitions == 0 {
+        return Err(error::Error::InvalidParameter(
+            "path length histograms need at least one repetition".to_string(),
+        ));
+    }
+    // Push-relabel interleaves the pushes of different vertices, so its flips do not form
+    // paths, and the parallel variant reports nothing to an observer.
+    if !variant.is_observable() || variant == Variant::PushRelabel {
+        return Err(error::Error::InvalidParameter(format!(
+            "`{}` does not augment along single paths",
+            variant.name()
+        )));
+    }
+
+    let mut histograms = vec![BTreeMap::new(); edges.len()];
+    let mut failure = None;
+    run_ordered(
+        edges.len() * repetitions,
+        jobs,
+        |cell| {
+            let config = GenConfig::builder()
+                .sizes(left, right)
+                .edges(edges[cell / repetitions])
+                .seed(cell_seed(seed, cell))
+                .build()?;
+            let graph = config.graph::<()>(&mut config.rng())?;
+            variant.prepare(&graph);
+            let mut lengths = PathLengths::default();
+            let mut rng = config.solver_rng();
+            run_variant(&graph, &mut rng, variant, &RunOptions::default(), &mut lengths);
+            Ok(lengths.counts)
+        },
+        |cell, counts| match counts {
+            Ok(counts) => {
+                for (length, count) in counts {
+                    *histograms[cell / repetitions].entry(length).or_default() += count;
+                }
+            }
+            Err(err) => {
+                failure.get_or_insert(err);
+            }
+        },
+    );
+    if let Some(err) = failure {
+        return Err(err);
+    }
+
+    Ok(edges
+        .iter()
+        .zip(histograms)
+        .flat_map(|(&edges, histogram)| {
+            histogram.into_iter().map(move |(length, count)| PathLengthRow {
+                left,
+                right,
+                edges,
+                repetitions,
+                length,
+                count,
+            })
+        })
+        .collect())
+}
+
+/// Flags values whose modified z-score `0.6745 * |x - median| / MAD` exceeds `threshold`
+/// (3.5 is the usual choice). Nothing is flagged when the MAD is zero.
+pub fn mad_outliers(values: &[f64], threshold: f64) -> Vec<bool> {
+    let median = Summary::of(values).median;
+    let deviations: Vec<f64> = values.iter().map(|x| (x - median).abs()).collect();
+    let mad = Summary::of(&deviations).median;
+    values
+        .iter()
+        .map(|x| mad > 0.0 && 0.6745 * (x - median).abs() / mad > threshold)
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary {
+    pub min: f64,
+    pub median: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub stddev: f64,
+}
+
+impl Summary {
+    pub fn of(values: &[f64]) -> Summary {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        let n = sorted.len();
+        if n == 0 {
+            return Summary {
+                min: f64::NAN,
+                median: f64::NAN,
+                max: f64::NAN,
+                mean: f64::NAN,
+                stddev: f64::NAN,
+            };
+        }
+
+        let median = if n % 2 == 1 {
+            sorted[n / 2]
+        } else {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+        };
+        let mean = sorted.iter().sum::<f64>() / n as f64;
+        let variance = sorted.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n as f64;
+
+        Summary {
+            min: sorted[0],
+            median,
+            max: sorted[n - 1],
+            mean,
+            stddev: variance.sqrt(),
+        }
+    }
+}
+
+/// Candidate growth curves for running time, in vertices `V` and edges `E`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComplexityModel {
+    Edges,
+    EdgesSqrtVertices,
+    VerticesEdges,
+}
+
+impl ComplexityModel {
+    pub const ALL: [ComplexityModel; 3] = [
+        ComplexityModel::Edges,
+        ComplexityModel::EdgesSqrtVertices,
+        ComplexityModel::VerticesEdges,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ComplexityModel::Edges => "E",
+            ComplexityModel::EdgesSqrtVertices => "E*sqrt(V)",
+            ComplexityModel::VerticesEdges => "V*E",
+        }
+    }
+
+    pub fn eval(self, vertices: f64, edges: f64) -> f64 {
+        match self {
+            ComplexityModel::Edges => edges,
+            ComplexityModel::EdgesSqrtVertices => edges * vertices.sqrt(),
+            ComplexityModel::VerticesEdges => vertices * edges,
+        }
+    }
+}
+
+/// One measured run for [`fit_complexity`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimingPoint {
+    pub vertices: f64,
+    pub edges: f64,
+    pub time: f64,
+}
+
+impl TimingPoint {
+    /// Reads timings from a CSV with a header naming an `m` (or `edges`) and a `time` column,
+    /// as `kuhn bench` writes them. The vertex count comes from a `v` (or `vertices`) column
+    /// when there is one and from `vertices` otherwise. Rows flagged `censored` hit a timeout
+    /// and are skipped, as are `#` comments.
+    pub fn read_csv<R: BufRead>(reader: R, vertices: Option<f64>) -> error::Result<Vec<TimingPoint>> {
+        let mut lines = reader
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !matches!(line, Ok(text) if text.trim().is_empty() || text.starts_with('#')));
+        let Some((_, header)) = lines.next() else {
+            return Ok(Vec::new());
+        };
+        let header = header?;
+        let columns: Vec<&str> = header.split(',').map(str::trim).collect();
+        let column = |names: &[&str]| columns.iter().position(|c| names.contains(c));
+        let missing = |name: &str| {
+            error::Error::InvalidFormat(format!("timings have no `{name}` column"))
+        };
+        let edges = column(&["m", "edges"]).ok_or_else(|| missing("m"))?;
+        let time = column(&["time"]).ok_or_else(|| missing("time"))?;
+        let vertex_column = column(&["v", "vertices"]);
+        if vertex_column.is_none() && vertices.is_none() {
+            return Err(missing("v"));
+        }
+        let censored = column(&["censored"]);
+
+        let mut points = Vec::new();
+        for (k, line) in lines {
+            let line = line?;
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let value = |index: usize| {
+                fields
+                    .get(index)
+                    .and_then(|field| field.parse::<f64>().ok())
+                    .ok_or_else(|| error::Error::ParseError {
+                        line: k + 1,
+                        message: format!("invalid `{}` value", columns[index]),
+                    })
+            };
+            if censored.map(value).transpose()? == Some(1.0) {
+                continue;
+            }
+            points.push(TimingPoint {
+                vertices: match vertex_column {
+                    Some(index) => value(index)?,
+                    None => vertices.expect("checked above"),
+                },
+                edges: value(edges)?,
+                time: value(time)?,
+            });
+        }
+        Ok(points)
+    }
+}
+
+/// `time ~ coefficient * model(V, E)`, with each point's relative residual
+/// `(predicted - time) / time` in input order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComplexityFit {
+    pub model: ComplexityModel,
+    pub coefficient: f64,
+    pub residuals: Vec<f64>,
+}
+
+impl ComplexityFit {
+    pub const CSV_HEADER: &'static str = "model,coefficient,rms_residual,max_residual";
+
+    pub fn rms_residual(&self) -> f64 {
+        let squares: f64 = self.residuals.iter().map(|r| r * r).sum();
+        (squares / self.residuals.len().max(1) as f64).sqrt()
+    }
+
+    pub fn max_residual(&self) -> f64 {
+        self.residuals.iter().fold(0.0, |max, r| r.abs().max(max))
+    }
+
+    pub fn csv_fields(&self) -> String {
+        format!(
+            "{},{},{},{}",
+            self.model.name(),
+            self.coefficient,
+            self.rms_residual(),
+            self.max_residual()
+        )
+    }
+}
+
+/// Fits every [`ComplexityModel`] to `points`, best first by RMS relative residual. The
+/// coefficient minimises the squared relative error, so small instances weigh as much as large
+/// ones, whose absolute timings would otherwise dominate. Curves that only differ by a factor
+/// of `V` are indistinguishable unless the points vary `V`; ties go to the simpler one.
+pub fn fit_complexity(points: &[TimingPoint]) -> error::Result<Vec<ComplexityFit>> {
+    if points.is_empty() {
+        return Err(error::Error::InvalidParameter(
+            "fitting needs at least one timing".to_string(),
+        ));
+    }
+    if let Some(p) = points
+        .iter()
+        .find(|p| !(p.time > 0.0 && p.time.is_finite() && p.vertices >= 0.0 && p.edges >= 0.0))
+    {
+        return Err(error::Error::InvalidParameter(format!(
+            "cannot fit a time of {} at V = {}, E = {}",
+            p.time, p.vertices, p.edges
+        )));
+    }
+
+    let mut fits: Vec<ComplexityFit> = ComplexityModel::ALL
+        .into_iter()
+        .map(|model| {
+            let ratios: Vec<f64> = points
+                .iter()
+                .map(|p| model.eval(p.vertices, p.edges) / p.time)
+                .collect();
+            let squares: f64 = ratios.iter().map(|x| x * x).sum();
+            let coefficient = if squares > 0.0 {
+                ratios.iter().sum::<f64>() / squares
+            } else {
+                0.0
+            };
+            ComplexityFit {
+                model,
+                coefficient,
+                residuals: ratios.iter().map(|x| coefficient * x - 1.0).collect(),
+            }
+        })
+        .collect();
+    // Stable, and rounding noise does not count, so exact ties keep the simpler curve first.
+    fits.sort_by(|a, b| {
+        let (x, y) = (a.rms_residual(), b.rms_residual());
+        if (x - y).abs() <= 1e-9 * x.max(y) {
+            std::cmp::Ordering::Equal
+        } else {
+            x.total_cmp(&y)
+        }
+    });
+    Ok(fits)
+}
+
+pub struct VarianceReport {
+    pub baseline_time: u128,
+    pub baseline_ops: OpCounts,
+    pub times: Vec<u128>,
+    pub ops: Vec<OpCounts>,
+    /// Each shuffled run's matching against the deterministic one.
+    pub similarities: Vec<MatchingSimilarity>,
+}
+
+impl VarianceReport {
+    pub fn time_summary(&self) -> Summary {
+        let times: Vec<f64> = self.times.iter().map(|&t| t as f64).collect();
+        Summary::of(&times)
+    }
+
+    pub fn edge_scan_summary(&self) -> Summary {
+        let scans: Vec<f64> = self.ops.iter().map(|o| o.edge_scans as f64).collect();
+        Summary::of(&scans)
+    }
+
+    pub fn jaccard_summary(&self) -> Summary {
+        let jaccard: Vec<f64> = self.similarities.iter().map(|s| s.jaccard()).collect();
+        Summary::of(&jaccard)
+    }
+
+    pub fn print_csv(&self) {
+        let time = self.time_summary();
+        let scans = self.edge_scan_summary();
+        let jaccard = self.jaccard_summary();
+
+        println!("statistic,time,edge_scans,jaccard");
+        println!(
+            "deterministic,{},{},1",
+            self.baseline_time, self.baseline_ops.edge_scans
+        );
+        for (name, t, e, j) in [
+            ("min", time.min, scans.min, jaccard.min),
+            ("median", time.median, scans.median, jaccard.median),
+            ("max", time.max, scans.max, jaccard.max),
+            ("mean", time.mean, scans.mean, jaccard.mean),
+            ("stddev", time.stddev, scans.stddev, jaccard.stddev),
+        ] {
+            println!("{name},{t},{e},{j}");
+        }
+    }
+}
+
+pub fn variance_study(
+    rng: &mut StdRng,
+    config: &GenConfig,
+    runs: usize,
+) -> error::Result<VarianceReport> {
+    let graph = config.graph::<()>(&mut config.rng())?;
+    // Run `k` reshuffles from its own seed, drawn from `rng` rather than from the instance
+    // seed, so no run replays the generator's stream.
+    let shuffle_seed: u64 = rng.gen();
+
+    let mut baseline_ops = OpCounts::default();
+    let start = Instant::now();
+    let baseline = graph.kuhn_observed::<false, _>(rng, &mut baseline_ops);
+    let baseline_time = start.elapsed().as_nanos();
+
+    let mut times = Vec::with_capacity(runs);
+    let mut ops = Vec::with_capacity(runs);
+    let mut similarities = Vec::with_capacity(runs);
+    for k in 0..runs {
+        graph.sort_neighbours();
+        let mut shuffle_rng = StdRng::seed_from_u64(cell_seed(shuffle_seed, k));
+
+        let mut counts = OpCounts::default();
+        let start = Instant::now();
+        let matched = graph.kuhn_observed::<true, _>(&mut shuffle_rng, &mut counts);
+        times.push(start.elapsed().as_nanos());
+        ops.push(counts);
+        similarities.push(baseline.similarity(&matched));
+    }
+
+    Ok(VarianceReport {
+        baseline_time,
+        baseline_ops,
+        times,
+        ops,
+        similarities,
+    })
+}
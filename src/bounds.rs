@@ -0,0 +1,49 @@
+use crate::graph::Adjacency;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatchingBounds {
+    pub lower: usize,
+    pub upper: usize,
+}
+
+impl MatchingBounds {
+    pub fn is_exact(&self) -> bool {
+        self.lower == self.upper
+    }
+}
+
+pub fn estimate_matching_bounds<G: Adjacency>(graph: &G) -> MatchingBounds {
+    let mut left_degree = vec![0; graph.left_len()];
+    let mut right_degree = vec![0; graph.right_len()];
+    for (v, degree) in left_degree.iter_mut().enumerate() {
+        for to in graph.left_neighbours(v) {
+            *degree += 1;
+            right_degree[to] += 1;
+        }
+    }
+
+    let mut order: Vec<usize> = (0..graph.left_len())
+        .filter(|&v| left_degree[v] > 0)
+        .collect();
+    order.sort_by_key(|&v| left_degree[v]);
+
+    let mut right_taken = vec![false; graph.right_len()];
+    let mut greedy = 0;
+    for v in order {
+        let best = graph
+            .left_neighbours(v)
+            .filter(|&to| !right_taken[to])
+            .min_by_key(|&to| right_degree[to]);
+        if let Some(to) = best {
+            right_taken[to] = true;
+            greedy += 1;
+        }
+    }
+
+    let left_active = left_degree.iter().filter(|&&d| d > 0).count();
+    let right_active = right_degree.iter().filter(|&&d| d > 0).count();
+    MatchingBounds {
+        lower: greedy,
+        upper: left_active.min(right_active).min(2 * greedy),
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use crate::csr::CsrGraph;
+use crate::graph::BipartiteGraph;
+use crate::hopcroft_karp::hopcroft_karp;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EdgeColoring {
+    pub edges: Vec<(usize, usize)>,
+    pub colors: Vec<usize>,
+    pub num_colors: usize,
+}
+
+impl EdgeColoring {
+    pub fn color_classes(&self) -> Vec<Vec<(usize, usize)>> {
+        let mut classes = vec![Vec::new(); self.num_colors];
+        for (&edge, &color) in self.edges.iter().zip(&self.colors) {
+            classes[color].push(edge);
+        }
+        classes
+    }
+}
+
+impl<T> BipartiteGraph<T> {
+    pub fn edge_coloring(&self) -> EdgeColoring {
+        let edges = self.edges();
+        let n = self.left_nodes.len().max(self.right_nodes.len());
+
+        let mut left_degree = vec![0; n];
+        let mut right_degree = vec![0; n];
+        for &(i, j) in &edges {
+            left_degree[i] += 1;
+            right_degree[j] += 1;
+        }
+        let max_degree = left_degree
+            .iter()
+            .chain(&right_degree)
+            .copied()
+            .max()
+            .unwrap_or(0);
+
+        let mut remaining: HashMap<(usize, usize), Vec<Option<usize>>> = HashMap::new();
+        for (id, &edge) in edges.iter().enumerate() {
+            remaining.entry(edge).or_default().push(Some(id));
+        }
+
+        let (mut i, mut j) = (0, 0);
+        loop {
+            while i < n && left_degree[i] == max_degree {
+                i += 1;
+            }
+            while j < n && right_degree[j] == max_degree {
+                j += 1;
+            }
+            if i == n || j == n {
+                break;
+            }
+            remaining.entry((i, j)).or_default().insert(0, None);
+            left_degree[i] += 1;
+            right_degree[j] += 1;
+        }
+
+        let mut colors = vec![usize::MAX; edges.len()];
+        for color in 0..max_degree {
+            let mut adjacency = vec![Vec::new(); n];
+            for (&(i, j), ids) in &remaining {
+                adjacency[i].extend(std::iter::repeat_n(j, ids.len()));
+            }
+            let mut offsets = vec![0];
+            let mut targets = Vec::new();
+            for list in adjacency {
+                targets.extend(list);
+                offsets.push(targets.len());
+            }
+            let graph = CsrGraph::from_parts(n, offsets, targets)
+                .expect("regular supergraph is well formed");
+
+            let matching = hopcroft_karp(&graph);
+            debug_assert_eq!(
+                matching.len(),
+                n,
+                "regular bipartite graph has a perfect matching"
+            );
+            for edge in matching.pairs() {
+                let ids = remaining.get_mut(&edge).expect("matched edge exists");
+                if let Some(id) = ids.pop().expect("matched edge has a copy left") {
+                    colors[id] = color;
+                }
+                if ids.is_empty() {
+                    remaining.remove(&edge);
+                }
+            }
+        }
+
+        EdgeColoring {
+            edges,
+            colors,
+            num_colors: max_degree,
+        }
+    }
+}
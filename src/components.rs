@@ -0,0 +1,100 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::csr::CsrGraph;
+use crate::graph::Adjacency;
+use crate::hopcroft_karp::hopcroft_karp;
+use crate::matching::Matching;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Component {
+    pub left: Vec<usize>,
+    pub right: Vec<usize>,
+}
+
+impl Component {
+    pub fn is_trivial(&self) -> bool {
+        self.left.is_empty() || self.right.is_empty()
+    }
+}
+
+pub fn connected_components<G: Adjacency>(graph: &G) -> Vec<Component> {
+    let l = graph.left_len();
+    let mut parent: Vec<usize> = (0..l + graph.right_len()).collect();
+    for v in 0..l {
+        for to in graph.left_neighbours(v) {
+            let (a, b) = (find(&mut parent, v), find(&mut parent, l + to));
+            if a != b {
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+    }
+
+    let mut index = vec![usize::MAX; parent.len()];
+    let mut components: Vec<Component> = Vec::new();
+    for x in 0..parent.len() {
+        let root = find(&mut parent, x);
+        if index[root] == usize::MAX {
+            index[root] = components.len();
+            components.push(Component::default());
+        }
+        let component = &mut components[index[root]];
+        if x < l {
+            component.left.push(x);
+        } else {
+            component.right.push(x - l);
+        }
+    }
+
+    components
+}
+
+fn find(parent: &mut [usize], mut x: usize) -> usize {
+    while parent[x] != x {
+        parent[x] = parent[parent[x]];
+        x = parent[x];
+    }
+    x
+}
+
+pub fn solve_components_parallel<G: Adjacency + Sync>(graph: &G, threads: usize) -> Matching {
+    let components: Vec<Component> = connected_components(graph)
+        .into_iter()
+        .filter(|c| !c.is_trivial())
+        .collect();
+
+    let mut local_right = vec![0; graph.right_len()];
+    for component in &components {
+        for (k, &j) in component.right.iter().enumerate() {
+            local_right[j] = k;
+        }
+    }
+
+    let matched_right = Mutex::new(vec![None; graph.right_len()]);
+    let next = AtomicUsize::new(0);
+    thread::scope(|s| {
+        for _ in 0..threads.max(1) {
+            s.spawn(|| {
+                while let Some(component) = components.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let mut offsets = vec![0];
+                    let mut targets = Vec::new();
+                    for &v in &component.left {
+                        targets.extend(graph.left_neighbours(v).map(|j| local_right[j]));
+                        offsets.push(targets.len());
+                    }
+                    let subgraph = CsrGraph::from_parts(component.right.len(), offsets, targets)
+                        .expect("component subgraph is well formed");
+
+                    let matching = hopcroft_karp(&subgraph);
+                    let mut matched_right = matched_right.lock().unwrap();
+                    for (i, j) in matching.pairs() {
+                        matched_right[component.right[j]] = Some(component.left[i]);
+                    }
+                }
+            });
+        }
+    });
+
+    Matching::from_matched_right(graph.left_len(), matched_right.into_inner().unwrap())
+}
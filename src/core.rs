@@ -0,0 +1,169 @@
+//! Allocation-free matching over caller-provided CSR slices. Needs neither `std` nor `alloc`,
+//! so it is all that builds with `default-features = false`.
+
+use ::core::fmt;
+
+use crate::index::Index;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoreError {
+    InvalidOffsets,
+    TargetOutOfRange { index: usize, len: usize },
+    BufferTooSmall { needed: usize, len: usize },
+    InvalidMatching,
+    Overflow,
+}
+
+impl fmt::Display for CoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoreError::InvalidOffsets => write!(f, "offsets do not describe the targets array"),
+            CoreError::TargetOutOfRange { index, len } => {
+                write!(f, "index {index} out of range for {len} vertices")
+            }
+            CoreError::BufferTooSmall { needed, len } => {
+                write!(f, "buffer of {len} entries where {needed} are needed")
+            }
+            CoreError::InvalidMatching => write!(f, "matching is not valid for this graph"),
+            CoreError::Overflow => write!(f, "vertex index does not fit the index type"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CoreError {}
+
+/// Borrowed CSR adjacency: left vertex `v` is adjacent to `targets[offsets[v]..offsets[v + 1]]`.
+#[derive(Clone, Copy, Debug)]
+pub struct CsrView<'a, I: Index = usize> {
+    right_len: usize,
+    offsets: &'a [usize],
+    targets: &'a [I],
+}
+
+impl<'a, I: Index> CsrView<'a, I> {
+    pub fn new(
+        right_len: usize,
+        offsets: &'a [usize],
+        targets: &'a [I],
+    ) -> Result<CsrView<'a, I>, CoreError> {
+        if offsets.first() != Some(&0)
+            || offsets.last() != Some(&targets.len())
+            || offsets.windows(2).any(|w| w[0] > w[1])
+        {
+            return Err(CoreError::InvalidOffsets);
+        }
+        if let Some(j) = targets.iter().find(|j| j.to_usize() >= right_len) {
+            return Err(CoreError::TargetOutOfRange {
+                index: j.to_usize(),
+                len: right_len,
+            });
+        }
+        Ok(CsrView {
+            right_len,
+            offsets,
+            targets,
+        })
+    }
+
+    pub fn left_len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn right_len(&self) -> usize {
+        self.right_len
+    }
+
+    pub fn neighbours(&self, v: usize) -> &'a [I] {
+        &self.targets[self.offsets[v]..self.offsets[v + 1]]
+    }
+}
+
+/// Scratch entries [`maximum_matching`] needs for a graph with `left_len` left vertices.
+pub const fn scratch_len(left_len: usize) -> usize {
+    4 * left_len
+}
+
+/// Kuhn's algorithm with an explicit stack. `matched_right` holds the left partner of every
+/// right vertex, or `I::NONE`, and may start from any valid matching, which is extended to a
+/// maximum one. `scratch` needs [`scratch_len`] entries. Returns the matching size.
+pub fn maximum_matching<I: Index>(
+    graph: &CsrView<'_, I>,
+    matched_right: &mut [I],
+    scratch: &mut [usize],
+) -> Result<usize, CoreError> {
+    let (l, r) = (graph.left_len(), graph.right_len());
+    if matched_right.len() != r {
+        return Err(CoreError::BufferTooSmall {
+            needed: r,
+            len: matched_right.len(),
+        });
+    }
+    if scratch.len() < scratch_len(l) {
+        return Err(CoreError::BufferTooSmall {
+            needed: scratch_len(l),
+            len: scratch.len(),
+        });
+    }
+    if l > 0 && I::from_usize(l - 1).is_none() {
+        return Err(CoreError::Overflow);
+    }
+
+    let (matched_left, rest) = scratch.split_at_mut(l);
+    let (visited, rest) = rest.split_at_mut(l);
+    let (stack, cursors) = rest.split_at_mut(l);
+    matched_left.fill(0);
+    visited.fill(usize::MAX);
+    let mut size = 0;
+    for (j, &u) in matched_right.iter().enumerate() {
+        if u == I::NONE {
+            continue;
+        }
+        let u = u.to_usize();
+        if u >= l || matched_left[u] != 0 || !graph.neighbours(u).iter().any(|t| t.to_usize() == j)
+        {
+            return Err(CoreError::InvalidMatching);
+        }
+        matched_left[u] = 1;
+        size += 1;
+    }
+
+    for root in 0..l {
+        if matched_left[root] != 0 {
+            continue;
+        }
+        visited[root] = root;
+        stack[0] = root;
+        cursors[0] = graph.offsets[root];
+        let mut depth = 1;
+
+        while depth > 0 {
+            let v = stack[depth - 1];
+            if cursors[depth - 1] == graph.offsets[v + 1] {
+                depth -= 1;
+                continue;
+            }
+            let to = graph.targets[cursors[depth - 1]].to_usize();
+            cursors[depth - 1] += 1;
+
+            let u = matched_right[to];
+            if u == I::NONE {
+                for k in 0..depth {
+                    let right = graph.targets[cursors[k] - 1];
+                    matched_right[right.to_usize()] = I::from_usize(stack[k]).expect("checked");
+                }
+                matched_left[root] = 1;
+                size += 1;
+                break;
+            }
+            let u = u.to_usize();
+            if visited[u] != root {
+                visited[u] = root;
+                stack[depth] = u;
+                cursors[depth] = graph.offsets[u];
+                depth += 1;
+            }
+        }
+    }
+    Ok(size)
+}
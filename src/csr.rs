@@ -0,0 +1,347 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::core::CsrView;
+use crate::error::{Error, Result};
+use crate::graph::{Adjacency, BipartiteGraph, MAX_INFERRED_VERTICES};
+use crate::index::Index;
+use crate::input::open_input;
+
+const CHUNK_SIZE: usize = 1 << 20;
+
+pub(crate) const BINARY_MAGIC: &[u8; 8] = b"KUHNCSR1";
+pub(crate) const BIPARTITE_COMMENT: &str = "bipartite";
+
+pub struct CsrGraph<I: Index = usize> {
+    right_len: usize,
+    offsets: Vec<usize>,
+    targets: Vec<I>,
+}
+
+impl CsrGraph {
+    pub fn load_csv(path: &Path) -> Result<CsrGraph> {
+        CsrGraph::load_csv_indexed(path)
+    }
+}
+
+impl<I: Index> CsrGraph<I> {
+    pub fn from_parts(
+        right_len: usize,
+        offsets: Vec<usize>,
+        targets: Vec<I>,
+    ) -> Result<CsrGraph<I>> {
+        if offsets.first() != Some(&0) || offsets.last() != Some(&targets.len()) {
+            return Err(Error::InvalidFormat(
+                "offsets do not describe the targets array".to_string(),
+            ));
+        }
+        if offsets.windows(2).any(|w| w[0] > w[1]) {
+            return Err(Error::InvalidFormat(
+                "offsets are not monotonic".to_string(),
+            ));
+        }
+        if let Some(&j) = targets.iter().find(|&&j| j.to_usize() >= right_len) {
+            return Err(Error::IndexOutOfRange {
+                index: j.to_usize(),
+                len: right_len,
+            });
+        }
+
+        Ok(CsrGraph {
+            right_len,
+            offsets,
+            targets,
+        })
+    }
+
+    /// Reads an edge CSV in two passes, counting degrees and then filling the targets. The
+    /// sizes come from a `# bipartite L R` comment, or else from the largest indices, each
+    /// side then holding at most [`MAX_INFERRED_VERTICES`].
+    ///
+    /// [`MAX_INFERRED_VERTICES`]: crate::MAX_INFERRED_VERTICES
+    pub fn load_csv_indexed(path: &Path) -> Result<CsrGraph<I>> {
+        let declared = read_csv_sizes(BufReader::new(open_input(path)?))?;
+        let (left_limit, right_limit) =
+            declared.unwrap_or((MAX_INFERRED_VERTICES, MAX_INFERRED_VERTICES));
+        let mut degrees: Vec<usize> = Vec::new();
+        let mut right_len = 0;
+        let mut out_of_range = None;
+        for_each_edge(path, |i, j| {
+            if i >= left_limit {
+                out_of_range.get_or_insert((i, left_limit));
+            } else if j >= right_limit {
+                out_of_range.get_or_insert((j, right_limit));
+            } else {
+                if i >= degrees.len() {
+                    degrees.resize(i + 1, 0);
+                }
+                degrees[i] += 1;
+                right_len = right_len.max(j + 1);
+            }
+        })?;
+        if let Some((index, len)) = out_of_range {
+            return Err(Error::IndexOutOfRange { index, len });
+        }
+        if let Some((l, r)) = declared {
+            degrees.resize(l, 0);
+            right_len = r;
+        }
+
+        let mut offsets = Vec::with_capacity(degrees.len() + 1);
+        offsets.push(0);
+        for degree in degrees {
+            offsets.push(offsets[offsets.len() - 1] + degree);
+        }
+
+        if right_len > 0 && I::from_usize(right_len - 1).is_none() {
+            return Err(Error::Overflow);
+        }
+
+        let num_edges = offsets[offsets.len() - 1];
+        let mut targets = vec![I::NONE; num_edges];
+        let mut cursor = offsets[..offsets.len() - 1].to_vec();
+        let mut seen = 0;
+        for_each_edge(path, |i, j| {
+            if seen < num_edges && i < cursor.len() && cursor[i] < offsets[i + 1] {
+                targets[cursor[i]] = I::from_usize(j).unwrap_or(I::NONE);
+                cursor[i] += 1;
+            }
+            seen += 1;
+        })?;
+        if seen != num_edges {
+            return Err(Error::InvalidFormat(
+                "file changed between loading passes".to_string(),
+            ));
+        }
+
+        for v in 0..offsets.len() - 1 {
+            targets[offsets[v]..offsets[v + 1]].sort_unstable_by_key(|j| j.to_usize());
+        }
+
+        Ok(CsrGraph {
+            right_len,
+            offsets,
+            targets,
+        })
+    }
+
+    pub fn write_binary(&self, path: &Path) -> Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        w.write_all(BINARY_MAGIC)?;
+        for value in [self.left_len(), self.right_len, self.targets.len()] {
+            w.write_all(&(value as u64).to_le_bytes())?;
+        }
+        for &value in &self.offsets {
+            w.write_all(&(value as u64).to_le_bytes())?;
+        }
+        for &value in &self.targets {
+            w.write_all(&(value.to_usize() as u64).to_le_bytes())?;
+        }
+        w.flush()?;
+        Ok(())
+    }
+
+    pub fn num_edges(&self) -> usize {
+        self.targets.len()
+    }
+
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    pub fn targets(&self) -> &[I] {
+        &self.targets
+    }
+
+    pub fn neighbours(&self, v: usize) -> &[I] {
+        &self.targets[self.offsets[v]..self.offsets[v + 1]]
+    }
+
+    /// The borrowed form taken by the solvers in [`crate::core`].
+    pub fn view(&self) -> CsrView<'_, I> {
+        CsrView::new(self.right_len, &self.offsets, &self.targets)
+            .expect("a CsrGraph is always a valid view")
+    }
+}
+
+impl<T> BipartiteGraph<T> {
+    pub fn to_csr(&self) -> CsrGraph {
+        self.to_csr_indexed().expect("usize indices always fit")
+    }
+
+    pub fn to_csr_indexed<I: Index>(&self) -> Result<CsrGraph<I>> {
+        if !self.right_nodes.is_empty() && I::from_usize(self.right_nodes.len() - 1).is_none() {
+            return Err(Error::Overflow);
+        }
+
+        let mut offsets = Vec::with_capacity(self.left_nodes.len() + 1);
+        let mut targets = Vec::new();
+        offsets.push(0);
+        for node in &self.left_nodes {
+            let neighbours = node.neighbours.borrow();
+            targets.extend(
+                neighbours
+                    .iter()
+                    .map(|&j| I::from_usize(j).unwrap_or(I::NONE)),
+            );
+            offsets.push(targets.len());
+        }
+
+        Ok(CsrGraph {
+            right_len: self.right_nodes.len(),
+            offsets,
+            targets,
+        })
+    }
+}
+
+impl<I: Index> Adjacency for CsrGraph<I> {
+    fn left_len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn right_len(&self) -> usize {
+        self.right_len
+    }
+
+    fn left_neighbours(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        self.neighbours(v).iter().map(|j| j.to_usize())
+    }
+}
+
+fn for_each_edge<F: FnMut(usize, usize)>(path: &Path, f: F) -> Result<Option<(usize, usize)>> {
+    let reader = BufReader::with_capacity(CHUNK_SIZE, open_input(path)?);
+    read_csv_edges(reader, f)
+}
+
+/// Calls `f(left, right)` for every edge and returns the side sizes given by a
+/// `# bipartite L R` comment ahead of the edges, if the file has one.
+pub fn read_csv_edges<R: BufRead, F: FnMut(usize, usize)>(
+    reader: R,
+    mut f: F,
+) -> Result<Option<(usize, usize)>> {
+    let mut declared = None;
+    let mut first_edge = None;
+    let comment = |line: usize, text: &str| {
+        if let Some(sizes) = parse_bipartite_comment(line, text)? {
+            if declared.is_some() {
+                return Err(Error::ParseError {
+                    line,
+                    message: "second `# bipartite L R` comment".to_string(),
+                });
+            }
+            declared = Some((line, sizes));
+        }
+        Ok(())
+    };
+    let mut data_lines = 0;
+    read_csv_lines(reader, comment, |line, i, j| {
+        data_lines += 1;
+        match (i.parse(), j.parse()) {
+            (Ok(i), Ok(j)) => {
+                first_edge.get_or_insert(line);
+                f(i, j);
+                Ok(())
+            }
+            // The first line after any comments may be a header.
+            _ if data_lines == 1 => Ok(()),
+            _ => Err(Error::ParseError {
+                line,
+                message: format!("invalid edge `{i},{j}`"),
+            }),
+        }
+    })?;
+    match (declared, first_edge) {
+        (Some((line, _)), Some(edge)) if line > edge => Err(Error::ParseError {
+            line,
+            message: "`# bipartite L R` must come before the edges".to_string(),
+        }),
+        _ => Ok(declared.map(|(_, sizes)| sizes)),
+    }
+}
+
+/// The sizes of a `# bipartite L R` comment among the comments that open an edge CSV, reading
+/// no further than the first other line.
+pub fn read_csv_sizes<R: BufRead>(reader: R) -> Result<Option<(usize, usize)>> {
+    for (k, line) in reader.lines().enumerate() {
+        let line = line?;
+        let Some(text) = line.trim().strip_prefix('#') else {
+            break;
+        };
+        if let Some(sizes) = parse_bipartite_comment(k + 1, text)? {
+            return Ok(Some(sizes));
+        }
+    }
+    Ok(None)
+}
+
+fn parse_bipartite_comment(line: usize, text: &str) -> Result<Option<(usize, usize)>> {
+    let mut fields = text.split_whitespace();
+    if fields.next() != Some(BIPARTITE_COMMENT) {
+        return Ok(None);
+    }
+    match (fields.next(), fields.next(), fields.next()) {
+        (Some(l), Some(r), None) => match (l.parse(), r.parse()) {
+            (Ok(l), Ok(r)) => Ok(Some((l, r))),
+            _ => Err(Error::ParseError {
+                line,
+                message: format!("bad sizes in `#{text}`"),
+            }),
+        },
+        _ => Err(Error::ParseError {
+            line,
+            message: format!("expected `# bipartite L R`, found `#{text}`"),
+        }),
+    }
+}
+
+/// Calls `f(line, left, right)` with the first two trimmed fields of every non-empty,
+/// non-comment line.
+pub fn read_csv_fields<R, F>(reader: R, f: F) -> Result<()>
+where
+    R: BufRead,
+    F: FnMut(usize, &str, &str) -> Result<()>,
+{
+    read_csv_lines(reader, |_, _| Ok(()), f)
+}
+
+/// [`read_csv_fields`], also passing the text after the `#` of each comment line to `comment`.
+fn read_csv_lines<R, C, F>(mut reader: R, mut comment: C, mut f: F) -> Result<()>
+where
+    R: BufRead,
+    C: FnMut(usize, &str) -> Result<()>,
+    F: FnMut(usize, &str, &str) -> Result<()>,
+{
+    let mut line = Vec::new();
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(());
+        }
+        line_number += 1;
+
+        let text = std::str::from_utf8(&line).map_err(|_| Error::ParseError {
+            line: line_number,
+            message: "line is not valid UTF-8".to_string(),
+        })?;
+        let text = text.trim();
+        if let Some(text) = text.strip_prefix('#') {
+            comment(line_number, text)?;
+            continue;
+        }
+        if text.is_empty() {
+            continue;
+        }
+
+        let mut fields = text.split(',').map(str::trim);
+        let (Some(i), Some(j)) = (fields.next(), fields.next()) else {
+            return Err(Error::ParseError {
+                line: line_number,
+                message: format!("expected `left,right`, found `{text}`"),
+            });
+        };
+        f(line_number, i, j)?;
+    }
+}
@@ -0,0 +1,198 @@
+use rand::prelude::*;
+use rand::seq::index::sample;
+
+use crate::error::{Error, Result};
+use crate::graph::{Adjacency, BipartiteGraph};
+use crate::kuhn::maximum_matching;
+use crate::matching::Matching;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+const DENSE_MAX_SIDE: usize = 4096;
+
+pub struct DenseBipartiteGraph {
+    left_len: usize,
+    right_len: usize,
+    row_words: usize,
+    bits: Vec<u64>,
+}
+
+impl DenseBipartiteGraph {
+    pub fn new(l: usize, r: usize) -> Result<DenseBipartiteGraph> {
+        let row_words = r.div_ceil(WORD_BITS);
+        let len = l.checked_mul(row_words).ok_or(Error::Overflow)?;
+        Ok(DenseBipartiteGraph {
+            left_len: l,
+            right_len: r,
+            row_words,
+            bits: vec![0; len],
+        })
+    }
+
+    pub fn random(
+        rng: &mut StdRng,
+        l: usize,
+        r: usize,
+        num_edges: usize,
+    ) -> Result<DenseBipartiteGraph> {
+        let max_edges = l.checked_mul(r).ok_or(Error::Overflow)?;
+        if num_edges > max_edges {
+            return Err(Error::TooManyEdges {
+                requested: num_edges,
+                max: max_edges,
+            });
+        }
+
+        let mut graph = DenseBipartiteGraph::new(l, r)?;
+        for code in sample(rng, max_edges, num_edges) {
+            graph.set(code / r, code % r);
+        }
+        Ok(graph)
+    }
+
+    pub fn from_edges<I>(l: usize, r: usize, edges: I) -> Result<DenseBipartiteGraph>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        let mut graph = DenseBipartiteGraph::new(l, r)?;
+        for (i, j) in edges {
+            if i >= l {
+                return Err(Error::IndexOutOfRange { index: i, len: l });
+            }
+            if j >= r {
+                return Err(Error::IndexOutOfRange { index: j, len: r });
+            }
+            graph.set(i, j);
+        }
+        Ok(graph)
+    }
+
+    pub fn prefers(l: usize, r: usize, num_edges: usize) -> bool {
+        l <= DENSE_MAX_SIDE && r <= DENSE_MAX_SIDE && num_edges.saturating_mul(5) > l * r
+    }
+
+    fn set(&mut self, i: usize, j: usize) {
+        self.bits[i * self.row_words + j / WORD_BITS] |= 1 << (j % WORD_BITS);
+    }
+
+    fn row(&self, i: usize) -> &[u64] {
+        &self.bits[i * self.row_words..(i + 1) * self.row_words]
+    }
+
+    pub fn has_edge(&self, i: usize, j: usize) -> bool {
+        i < self.left_len
+            && j < self.right_len
+            && self.row(i)[j / WORD_BITS] >> (j % WORD_BITS) & 1 == 1
+    }
+
+    pub fn num_edges(&self) -> usize {
+        self.bits.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn maximum_matching(&self) -> Matching {
+        let mut matched_right = vec![None; self.right_len];
+        let mut unvisited = vec![0u64; self.row_words];
+        let mut stack: Vec<(usize, Option<usize>, usize)> = Vec::new();
+
+        for root in 0..self.left_len {
+            self.reset_unvisited(&mut unvisited);
+            stack.push((root, None, 0));
+
+            while let Some((v, _, word)) = stack.last_mut() {
+                let row = self.row(*v);
+                let Some(k) = (*word..self.row_words).find(|&k| row[k] & unvisited[k] != 0) else {
+                    stack.pop();
+                    continue;
+                };
+                *word = k;
+
+                let bit = (row[k] & unvisited[k]).trailing_zeros() as usize;
+                unvisited[k] &= !(1 << bit);
+                let to = k * WORD_BITS + bit;
+
+                match matched_right[to] {
+                    None => {
+                        matched_right[to] = Some(*v);
+                        while let Some((_, via, _)) = stack.pop() {
+                            if let (Some(via), Some((u, _, _))) = (via, stack.last()) {
+                                matched_right[via] = Some(*u);
+                            }
+                        }
+                    }
+                    Some(u) => stack.push((u, Some(to), 0)),
+                }
+            }
+        }
+
+        Matching::from_matched_right(self.left_len, matched_right)
+    }
+
+    fn reset_unvisited(&self, unvisited: &mut [u64]) {
+        unvisited.fill(u64::MAX);
+        let tail = self.right_len % WORD_BITS;
+        if tail != 0 {
+            unvisited[self.row_words - 1] = (1 << tail) - 1;
+        }
+    }
+}
+
+impl Adjacency for DenseBipartiteGraph {
+    fn left_len(&self) -> usize {
+        self.left_len
+    }
+
+    fn right_len(&self) -> usize {
+        self.right_len
+    }
+
+    fn left_neighbours(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        self.row(v).iter().enumerate().flat_map(|(k, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(k * WORD_BITS + bit)
+            })
+        })
+    }
+}
+
+impl<T> BipartiteGraph<T> {
+    pub fn to_dense(&self) -> Result<DenseBipartiteGraph> {
+        DenseBipartiteGraph::from_edges(self.left_nodes.len(), self.right_nodes.len(), self.edges())
+    }
+}
+
+pub enum GeneratedGraph {
+    Lists(BipartiteGraph<()>),
+    Dense(DenseBipartiteGraph),
+}
+
+impl GeneratedGraph {
+    pub fn random(
+        rng: &mut StdRng,
+        l: usize,
+        r: usize,
+        num_edges: usize,
+    ) -> Result<GeneratedGraph> {
+        if DenseBipartiteGraph::prefers(l, r, num_edges) {
+            DenseBipartiteGraph::random(rng, l, r, num_edges).map(GeneratedGraph::Dense)
+        } else {
+            BipartiteGraph::random(rng, l, r, num_edges).map(GeneratedGraph::Lists)
+        }
+    }
+
+    pub fn is_dense(&self) -> bool {
+        matches!(self, GeneratedGraph::Dense(_))
+    }
+
+    pub fn maximum_matching(&self) -> Matching {
+        match self {
+            GeneratedGraph::Lists(graph) => maximum_matching(graph),
+            GeneratedGraph::Dense(graph) => graph.maximum_matching(),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::graph::BipartiteGraph;
+use crate::input::open_input;
+
+impl<T> BipartiteGraph<T> {
+    pub fn load_dimacs(path: &Path) -> Result<BipartiteGraph<T>>
+    where
+        T: Default,
+    {
+        BipartiteGraph::read_dimacs(BufReader::new(open_input(path)?))
+    }
+
+    /// A DIMACS assignment problem (`p asn N M`). The nodes named on `n ID` lines form the
+    /// left side and the remaining nodes the right, each kept in increasing id order; `a U V
+    /// [cost]` arcs become edges and their costs are read past.
+    pub fn read_dimacs<R: BufRead>(reader: R) -> Result<BipartiteGraph<T>>
+    where
+        T: Default,
+    {
+        let parse_error = |line: usize, message: String| Error::ParseError { line, message };
+        let parse_id = |k: usize, field: Option<&str>, n: usize| -> Result<usize> {
+            let field = field.ok_or_else(|| parse_error(k, "missing node id".to_string()))?;
+            let id: usize = field
+                .parse()
+                .map_err(|err| parse_error(k, format!("bad node id `{field}`: {err}")))?;
+            if id == 0 || id > n {
+                return Err(parse_error(k, format!("node {id} is not in 1..={n}")));
+            }
+            Ok(id)
+        };
+
+        let mut problem = None;
+        let mut sources = Vec::new();
+        let mut arcs = Vec::new();
+        for (k, line) in reader.lines().enumerate() {
+            let k = k + 1;
+            let line = line?;
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                None | Some("c") => {}
+                Some("p") => {
+                    if problem.is_some() {
+                        return Err(parse_error(k, "second problem line".to_string()));
+                    }
+                    let numbers: Vec<&str> = fields.collect();
+                    let [kind, n, m] = numbers[..] else {
+                        return Err(parse_error(k, "problem line is not `p asn N M`".to_string()));
+                    };
+                    if kind != "asn" {
+                        return Err(parse_error(
+                            k,
+                            format!("`{kind}` is not an assignment problem"),
+                        ));
+                    }
+                    let count = |field: &str| {
+                        field
+                            .parse::<usize>()
+                            .map_err(|err| parse_error(k, format!("bad count `{field}`: {err}")))
+                    };
+                    problem = Some((count(n)?, count(m)?));
+                }
+                Some(kind @ ("n" | "a")) => {
+                    let Some((n, _)) = problem else {
+                        return Err(parse_error(k, "descriptor before the problem line".to_string()));
+                    };
+                    if kind == "n" {
+                        sources.push(parse_id(k, fields.next(), n)?);
+                    } else {
+                        let u = parse_id(k, fields.next(), n)?;
+                        let v = parse_id(k, fields.next(), n)?;
+                        arcs.push((k, u, v));
+                    }
+                }
+                Some(other) => {
+                    return Err(parse_error(k, format!("unknown descriptor `{other}`")));
+                }
+            }
+        }
+
+        let (n, m) = problem
+            .ok_or_else(|| Error::InvalidFormat("missing DIMACS problem line".to_string()))?;
+        if arcs.len() != m {
+            return Err(Error::InvalidFormat(format!(
+                "problem line declares {m} arcs but the file holds {}",
+                arcs.len()
+            )));
+        }
+        sources.sort_unstable();
+        sources.dedup();
+        // A right node's index is its id less the source ids below it.
+        let side = |id: usize| match sources.binary_search(&id) {
+            Ok(i) => (true, i),
+            Err(below) => (false, id - 1 - below),
+        };
+        let mut edges = Vec::with_capacity(m);
+        for (k, u, v) in arcs {
+            match (side(u), side(v)) {
+                ((true, i), (false, j)) => edges.push((i, j)),
+                _ => {
+                    return Err(parse_error(
+                        k,
+                        format!("arc {u} {v} does not run from a source node to a sink node"),
+                    ))
+                }
+            }
+        }
+
+        BipartiteGraph::from_edges(sources.len(), n - sources.len(), edges)
+    }
+}
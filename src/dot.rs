@@ -0,0 +1,448 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use rand::prelude::*;
+
+use crate::graph::{BipartiteGraph, Node, Side};
+use crate::kuhn::{Observer, PathRecorder};
+use crate::matching::Matching;
+use crate::weights::EdgeWeights;
+
+/// Vertical order of each side in a [`DotLayout`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DotOrder {
+    /// Whatever Graphviz picks to reduce crossings.
+    #[default]
+    Free,
+    Index,
+    /// By decreasing degree, ties by index.
+    Degree,
+}
+
+impl FromStr for DotOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<DotOrder, String> {
+        match s {
+            "free" => Ok(DotOrder::Free),
+            "index" => Ok(DotOrder::Index),
+            "degree" => Ok(DotOrder::Degree),
+            _ => Err(format!(
+                "unknown order `{s}` (expected free, index or degree)"
+            )),
+        }
+    }
+}
+
+/// Rank constraints that keep drawings of the same graph comparable. `same_rank` puts each
+/// side in one column; a fixed order also chains the column with invisible edges, which
+/// implies `same_rank`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DotLayout {
+    pub same_rank: bool,
+    pub order: DotOrder,
+}
+
+/// Hover text and link target of one vertex, for SVG output: Graphviz keeps `tooltip` as
+/// the element's title and wraps it in an `<a>` to `URL`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NodeLink {
+    pub tooltip: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Default)]
+struct DotStyle<'a> {
+    path: &'a [(usize, usize)],
+    weights: Option<&'a EdgeWeights>,
+    groups: Option<&'a [(String, Vec<usize>)]>,
+    previous: Option<&'a [Option<usize>]>,
+    /// Attribute lists for the left and the right vertices, empty when a vertex has none.
+    links: Option<(&'a [String], &'a [String])>,
+    layout: DotLayout,
+}
+
+impl<T> BipartiteGraph<T> {
+    pub fn print_as_dot(&self, matching: &Matching) {
+        let stdout = io::stdout();
+        self.write_dot(&mut stdout.lock(), matching)
+            .expect("failed to write to stdout");
+    }
+
+    pub fn write_dot<W: Write>(&self, w: &mut W, matching: &Matching) -> io::Result<()> {
+        self.write_dot_with_path(w, matching, &[])
+    }
+
+    pub fn write_dot_with_path<W: Write>(
+        &self,
+        w: &mut W,
+        matching: &Matching,
+        path: &[(usize, usize)],
+    ) -> io::Result<()> {
+        let style = DotStyle {
+            path,
+            ..DotStyle::default()
+        };
+        self.write_dot_inner(w, matching.matched_right(), &style)
+    }
+
+    pub fn write_dot_with_layout<W: Write>(
+        &self,
+        w: &mut W,
+        matching: &Matching,
+        layout: DotLayout,
+    ) -> io::Result<()> {
+        let style = DotStyle {
+            layout,
+            ..DotStyle::default()
+        };
+        self.write_dot_inner(w, matching.matched_right(), &style)
+    }
+
+    pub fn write_dot_weighted<W: Write>(
+        &self,
+        w: &mut W,
+        matching: &Matching,
+        weights: &EdgeWeights,
+    ) -> io::Result<()> {
+        let style = DotStyle {
+            weights: Some(weights),
+            ..DotStyle::default()
+        };
+        self.write_dot_inner(w, matching.matched_right(), &style)
+    }
+
+    pub fn write_dot_grouped<W, K, F>(
+        &self,
+        w: &mut W,
+        matching: &Matching,
+        category: F,
+    ) -> io::Result<()>
+    where
+        W: Write,
+        K: Ord + Display,
+        F: Fn(&T) -> K,
+    {
+        let mut groups: BTreeMap<K, Vec<usize>> = BTreeMap::new();
+        for (j, node) in self.right_nodes.iter().enumerate() {
+            groups.entry(category(&node.data)).or_default().push(j);
+        }
+        let groups: Vec<(String, Vec<usize>)> = groups
+            .into_iter()
+            .map(|(key, members)| (key.to_string(), members))
+            .collect();
+        let style = DotStyle {
+            groups: Some(&groups),
+            ..DotStyle::default()
+        };
+        self.write_dot_inner(w, matching.matched_right(), &style)
+    }
+
+    /// Adds the `tooltip` and `URL` that `link` returns for each vertex, given its side, index
+    /// and data, so that the rendered SVG can be inspected and clicked through.
+    pub fn write_dot_linked<W, F>(&self, w: &mut W, matching: &Matching, link: F) -> io::Result<()>
+    where
+        W: Write,
+        F: Fn(Side, usize, &T) -> NodeLink,
+    {
+        let attrs = |side, nodes: &[Node<T>]| -> Vec<String> {
+            nodes
+                .iter()
+                .enumerate()
+                .map(|(v, node)| {
+                    let NodeLink { tooltip, url } = link(side, v, &node.data);
+                    let tooltip = tooltip.map(|t| format!("tooltip=\"{}\"", dot_escape(&t)));
+                    let url = url.map(|u| format!("URL=\"{}\"", dot_escape(&u)));
+                    tooltip.into_iter().chain(url).collect::<Vec<_>>().join(",")
+                })
+                .collect()
+        };
+        let left = attrs(Side::Left, &self.left_nodes);
+        let right = attrs(Side::Right, &self.right_nodes);
+        let style = DotStyle {
+            links: Some((&left, &right)),
+            ..DotStyle::default()
+        };
+        self.write_dot_inner(w, matching.matched_right(), &style)
+    }
+
+    /// Edges only in `after` are green, edges only in `before` orange, and edges in both red.
+    pub fn write_dot_diff<W: Write>(
+        &self,
+        w: &mut W,
+        before: &Matching,
+        after: &Matching,
+    ) -> io::Result<()> {
+        self.write_dot_diff_with_layout(w, before, after, DotLayout::default())
+    }
+
+    /// [`write_dot_diff`](BipartiteGraph::write_dot_diff) with the vertices held in place, so
+    /// that drawings of successive matchings line up.
+    pub fn write_dot_diff_with_layout<W: Write>(
+        &self,
+        w: &mut W,
+        before: &Matching,
+        after: &Matching,
+        layout: DotLayout,
+    ) -> io::Result<()> {
+        let style = DotStyle {
+            previous: Some(before.matched_right()),
+            layout,
+            ..DotStyle::default()
+        };
+        self.write_dot_inner(w, after.matched_right(), &style)
+    }
+
+    /// One side's vertices in `order`, or `None` when the layout leaves them unconstrained.
+    fn dot_column(&self, nodes: &[Node<T>], layout: DotLayout) -> Option<Vec<usize>> {
+        let mut column: Vec<usize> = (0..nodes.len()).collect();
+        match layout.order {
+            DotOrder::Free if !layout.same_rank => return None,
+            DotOrder::Free | DotOrder::Index => {}
+            DotOrder::Degree => {
+                column.sort_by_key(|&v| (Reverse(nodes[v].neighbours.borrow().len()), v))
+            }
+        }
+        Some(column)
+    }
+
+    fn write_dot_column<W: Write>(
+        &self,
+        w: &mut W,
+        name: char,
+        nodes: &[Node<T>],
+        layout: DotLayout,
+    ) -> io::Result<()> {
+        let Some(column) = self.dot_column(nodes, layout) else {
+            for v in 0..nodes.len() {
+                writeln!(w, "\t\t{name}{v}")?;
+            }
+            return Ok(());
+        };
+        writeln!(w, "\t\tsubgraph {{")?;
+        writeln!(w, "\t\t\trank=same")?;
+        for v in &column {
+            writeln!(w, "\t\t\t{name}{v}")?;
+        }
+        if layout.order != DotOrder::Free && column.len() > 1 {
+            let chain: Vec<String> = column.iter().map(|v| format!("{name}{v}")).collect();
+            writeln!(w, "\t\t\t{} [style=invis]", chain.join(" -> "))?;
+        }
+        writeln!(w, "\t\t}}")
+    }
+
+    fn write_dot_inner<W: Write>(
+        &self,
+        w: &mut W,
+        matched_right: &[Option<usize>],
+        style: &DotStyle<'_>,
+    ) -> io::Result<()> {
+        let path: HashSet<(usize, usize)> = style.path.iter().copied().collect();
+        let (weights, groups, previous) = (style.weights, style.groups, style.previous);
+
+        writeln!(w, "digraph A {{")?;
+        writeln!(w, "\trankdir=LR")?;
+        writeln!(w, "\tsplines=false")?;
+
+        let mut weight_range = None;
+        if let Some(weights) = weights {
+            let total: i128 = matched_right
+                .iter()
+                .enumerate()
+                .filter_map(|(j, m)| m.and_then(|i| weights.get(i, j)))
+                .map(i128::from)
+                .sum();
+            writeln!(w, "\tlabel=\"total weight = {total}\"")?;
+            writeln!(w, "\tlabelloc=t")?;
+
+            let min = weights.iter().map(|(_, x)| x).min().unwrap_or(0);
+            let max = weights.iter().map(|(_, x)| x).max().unwrap_or(0);
+            weight_range = Some((weights, min, max));
+        }
+
+        if let Some(previous) = previous {
+            let changed = |from: &[Option<usize>], to: &[Option<usize>]| {
+                (0..self.right_nodes.len())
+                    .filter(|&j| from[j].is_some() && from[j] != to[j])
+                    .count()
+            };
+            let entered = changed(matched_right, previous);
+            let left = changed(previous, matched_right);
+            writeln!(w, "\tlabel=\"+{entered} entered, -{left} left\"")?;
+            writeln!(w, "\tlabelloc=t")?;
+        }
+
+        writeln!(w, "\tsubgraph cluster1 {{")?;
+        writeln!(w, "\t\tmargin=30")?;
+        writeln!(w, "\t\tstyle=invis")?;
+        self.write_dot_column(w, 'A', &self.left_nodes, style.layout)?;
+        writeln!(w, "\t}}")?;
+        writeln!(w, "\tsubgraph cluster2 {{")?;
+        writeln!(w, "\t\tmargin=30")?;
+        writeln!(w, "\t\tstyle=invis")?;
+        match groups {
+            None => self.write_dot_column(w, 'B', &self.right_nodes, style.layout)?,
+            Some(groups) => {
+                for (k, (name, members)) in groups.iter().enumerate() {
+                    let matched = members
+                        .iter()
+                        .filter(|&&j| matched_right[j].is_some())
+                        .count();
+                    let name = dot_escape(name);
+                    writeln!(w, "\t\tsubgraph cluster2_{k} {{")?;
+                    writeln!(w, "\t\t\tlabel=\"{name} ({matched}/{})\"", members.len())?;
+                    writeln!(w, "\t\t\tstyle=rounded")?;
+                    for j in members {
+                        writeln!(w, "\t\t\tB{j}")?;
+                    }
+                    writeln!(w, "\t\t}}")?;
+                }
+            }
+        }
+        writeln!(w, "\t}}")?;
+
+        if let Some((left, right)) = style.links {
+            for (name, attrs) in [('A', left), ('B', right)] {
+                for (v, attrs) in attrs.iter().enumerate() {
+                    if !attrs.is_empty() {
+                        writeln!(w, "\t{name}{v} [{attrs}]")?;
+                    }
+                }
+            }
+        }
+
+        for (i, node) in self.right_nodes.iter().enumerate() {
+            let neighbours = node.neighbours.borrow();
+            for &j in &*neighbours {
+                let mut attrs = String::from("arrowhead=none");
+                let now = matched_right[i] == Some(j);
+                match previous.map(|previous| previous[i] == Some(j)) {
+                    None | Some(true) if now => attrs.push_str(",color=red"),
+                    Some(false) if now => attrs.push_str(",color=green,penwidth=2"),
+                    Some(true) => attrs.push_str(",color=orange,penwidth=2"),
+                    _ => {}
+                }
+                if path.contains(&(j, i)) {
+                    attrs.push_str(",style=dashed");
+                }
+                if let Some((weights, min, max)) = weight_range {
+                    if let Some(weight) = weights.get(j, i) {
+                        // Weights may span the whole i64 range, so subtract in i128.
+                        let above_min = |x: i64| (i128::from(x) - i128::from(min)) as f64;
+                        let scale = if max > min {
+                            above_min(weight) / above_min(max)
+                        } else {
+                            1.0
+                        };
+                        let penwidth = 1.0 + 4.0 * scale;
+                        attrs.push_str(&format!(",label=\"{weight}\",penwidth={penwidth:.2}"));
+                    }
+                }
+                writeln!(w, "\t\tB{i} -> A{j} [{attrs}]")?;
+            }
+        }
+
+        writeln!(w, "}}")
+    }
+
+    pub fn write_dot_frames<const R: bool>(
+        &self,
+        rng: &mut StdRng,
+        dir: &Path,
+    ) -> io::Result<Matching> {
+        self.write_dot_frames_with_layout::<R>(rng, dir, DotLayout::default())
+    }
+
+    /// Frames that all share `layout`, so vertices stay put from one frame to the next.
+    pub fn write_dot_frames_with_layout<const R: bool>(
+        &self,
+        rng: &mut StdRng,
+        dir: &Path,
+        layout: DotLayout,
+    ) -> io::Result<Matching> {
+        self.write_dot_frames_with(dir, layout, |graph, frames| {
+            graph.kuhn_observed::<R, _>(rng, frames)
+        })
+    }
+
+    pub fn write_dot_frames_deterministic(&self, dir: &Path) -> io::Result<Matching> {
+        self.write_dot_frames_with(dir, DotLayout::default(), |graph, frames| {
+            graph.kuhn_deterministic_observed(frames)
+        })
+    }
+
+    fn write_dot_frames_with<F>(&self, dir: &Path, layout: DotLayout, solve: F) -> io::Result<Matching>
+    where
+        F: FnOnce(&Self, &mut FrameWriter<'_, T>) -> Matching,
+    {
+        let mut frames = FrameWriter {
+            graph: self,
+            dir,
+            width: self.left_nodes.len().to_string().len(),
+            next_frame: 0,
+            path: PathRecorder::default(),
+            layout,
+            error: None,
+        };
+        let matching = solve(self, &mut frames);
+
+        match frames.error {
+            Some(err) => Err(err),
+            None => Ok(matching),
+        }
+    }
+}
+
+struct FrameWriter<'a, T> {
+    graph: &'a BipartiteGraph<T>,
+    dir: &'a Path,
+    width: usize,
+    next_frame: usize,
+    path: PathRecorder,
+    layout: DotLayout,
+    error: Option<io::Error>,
+}
+
+impl<T> FrameWriter<'_, T> {
+    fn write_frame(&mut self, matched_right: &[Option<usize>]) -> io::Result<()> {
+        let name = format!("frame_{:0width$}.dot", self.next_frame, width = self.width);
+        let mut w = BufWriter::new(File::create(self.dir.join(name))?);
+        let path = self.path.edges();
+        let style = DotStyle {
+            path: &path,
+            layout: self.layout,
+            ..DotStyle::default()
+        };
+        self.graph
+            .write_dot_inner(&mut w, matched_right, &style)?;
+        w.flush()
+    }
+}
+
+impl<T> Observer for FrameWriter<'_, T> {
+    fn match_flip(&mut self, left: usize, right: usize) {
+        self.path.match_flip(left, right);
+    }
+
+    fn augmented(&mut self, _root: usize, matched_right: &[Option<usize>]) {
+        if self.error.is_none() {
+            if let Err(err) = self.write_frame(matched_right) {
+                self.error = Some(err);
+            }
+        }
+        self.next_frame += 1;
+        self.path.clear();
+    }
+}
+
+/// Contents of a double-quoted DOT string.
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
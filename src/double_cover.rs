@@ -0,0 +1,39 @@
+use std::collections::BTreeMap;
+
+use crate::graph::BipartiteGraph;
+use crate::graph6::SimpleGraph;
+use crate::hopcroft_karp::hopcroft_karp;
+use crate::matching::Matching;
+
+impl SimpleGraph {
+    pub fn double_cover<T: Default>(&self) -> BipartiteGraph<T> {
+        let n = self.num_vertices();
+        let edges = self
+            .edges()
+            .iter()
+            .filter(|&&(u, v)| u != v)
+            .flat_map(|&(u, v)| [(u, v), (v, u)]);
+        BipartiteGraph::from_edges(n, n, edges).expect("edge endpoints are below the vertex count")
+    }
+
+    pub fn double_cover_matching(&self) -> Matching {
+        hopcroft_karp(&self.double_cover::<()>())
+    }
+
+    pub fn fractional_matching_size(&self) -> f64 {
+        self.double_cover_matching().len() as f64 / 2.0
+    }
+
+    pub fn has_perfect_fractional_matching(&self) -> bool {
+        self.double_cover_matching().len() == self.num_vertices()
+    }
+
+    pub fn fractional_matching(&self) -> Vec<((usize, usize), f64)> {
+        let matching = self.double_cover_matching();
+        let mut weights = BTreeMap::new();
+        for (u, v) in matching.pairs() {
+            *weights.entry((u.min(v), u.max(v))).or_insert(0.0) += 0.5;
+        }
+        weights.into_iter().collect()
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::HashSet;
+
+use rand::prelude::*;
+use rand::seq::index::sample;
+
+use crate::error::{Error, Result};
+use crate::graph::BipartiteGraph;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphDiff {
+    pub added: Vec<(usize, usize)>,
+    pub removed: Vec<(usize, usize)>,
+}
+
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl<T: Clone> BipartiteGraph<T> {
+    pub fn perturb(
+        &self,
+        rng: &mut StdRng,
+        add_k: usize,
+        remove_k: usize,
+    ) -> Result<BipartiteGraph<T>> {
+        let (l, r) = (self.left_nodes.len(), self.right_nodes.len());
+        let edges = self.edges();
+        if remove_k > edges.len() {
+            return Err(Error::TooManyEdges {
+                requested: remove_k,
+                max: edges.len(),
+            });
+        }
+
+        // Parallel edges occupy one slot, so count the distinct edges.
+        let existing: HashSet<(usize, usize)> = edges.iter().copied().collect();
+        let max_edges = l.checked_mul(r).ok_or(Error::Overflow)?;
+        let free = max_edges - existing.len();
+        if add_k > free {
+            return Err(Error::TooManyEdges {
+                requested: add_k,
+                max: free,
+            });
+        }
+
+        let removed: HashSet<usize> = sample(rng, edges.len(), remove_k).into_iter().collect();
+        let mut added = HashSet::new();
+        while added.len() < add_k {
+            let edge = (rng.gen_range(0..l), rng.gen_range(0..r));
+            if !existing.contains(&edge) {
+                added.insert(edge);
+            }
+        }
+
+        let kept = edges
+            .into_iter()
+            .enumerate()
+            .filter(|(k, _)| !removed.contains(k))
+            .map(|(_, edge)| edge);
+        let left_data = self.left_nodes.iter().map(|n| n.data.clone()).collect();
+        let right_data = self.right_nodes.iter().map(|n| n.data.clone()).collect();
+        BipartiteGraph::from_edges_with_data(left_data, right_data, kept.chain(added))
+    }
+}
+
+impl<T> BipartiteGraph<T> {
+    pub fn diff<U>(&self, other: &BipartiteGraph<U>) -> GraphDiff {
+        let ours: HashSet<(usize, usize)> = self.edges().into_iter().collect();
+        let theirs: HashSet<(usize, usize)> = other.edges().into_iter().collect();
+
+        let mut added: Vec<_> = theirs.difference(&ours).copied().collect();
+        let mut removed: Vec<_> = ours.difference(&theirs).copied().collect();
+        added.sort_unstable();
+        removed.sort_unstable();
+
+        GraphDiff { added, removed }
+    }
+}
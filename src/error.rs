@@ -0,0 +1,62 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    TooManyEdges { requested: usize, max: usize },
+    IndexOutOfRange { index: usize, len: usize },
+    DuplicateEdge { left: usize, right: usize },
+    ParseError { line: usize, message: String },
+    InvalidFormat(String),
+    InvalidParameter(String),
+    InvalidMatching,
+    Overflow,
+    /// A cost or weight total that does not fit in an `i64`; says which.
+    CostOverflow(String),
+    Cancelled,
+    Io(io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::TooManyEdges { requested, max } => {
+                write!(
+                    f,
+                    "requested {requested} edges but at most {max} are possible"
+                )
+            }
+            Error::IndexOutOfRange { index, len } => {
+                write!(f, "index {index} out of range for {len} vertices")
+            }
+            Error::DuplicateEdge { left, right } => {
+                write!(f, "edge ({left}, {right}) appears more than once")
+            }
+            Error::ParseError { line, message } => write!(f, "line {line}: {message}"),
+            Error::InvalidFormat(message) => write!(f, "invalid format: {message}"),
+            Error::InvalidParameter(message) => write!(f, "invalid parameter: {message}"),
+            Error::InvalidMatching => write!(f, "matching is not valid for this graph"),
+            Error::Overflow => write!(f, "arithmetic overflow"),
+            Error::CostOverflow(what) => write!(f, "{what} does not fit in an i64"),
+            Error::Cancelled => write!(f, "solve was cancelled"),
+            Error::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::graph::{Adjacency, BipartiteGraph, Side};
+use crate::hopcroft_karp::hopcroft_karp;
+use crate::matching::Matching;
+
+/// Vertices with at most this many distinct neighbours are grouped by neighbourhood.
+const LOW_DEGREE: usize = 3;
+
+/// Why a graph has no perfect matching.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Obstruction {
+    UnequalSides {
+        left: usize,
+        right: usize,
+    },
+    Isolated {
+        side: Side,
+        vertex: usize,
+    },
+    /// More `vertices` on `side` than the `neighbours` they share between them, a Hall
+    /// violation.
+    Crowded {
+        side: Side,
+        vertices: Vec<usize>,
+        neighbours: Vec<usize>,
+    },
+    /// The cheap checks passed but the maximum matching is smaller.
+    Deficient {
+        matched: usize,
+        required: usize,
+    },
+}
+
+impl fmt::Display for Obstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = |side: &Side| match side {
+            Side::Left => ("left", "right"),
+            Side::Right => ("right", "left"),
+        };
+        match self {
+            Obstruction::UnequalSides { left, right } => {
+                write!(f, "{left} left vertices but {right} right vertices")
+            }
+            Obstruction::Isolated { side, vertex } => {
+                write!(f, "{} {vertex} has no neighbours", names(side).0)
+            }
+            Obstruction::Crowded {
+                side,
+                vertices,
+                neighbours,
+            } => {
+                let (this, other) = names(side);
+                write!(
+                    f,
+                    "{this} vertices {vertices:?} only reach {other} vertices {neighbours:?}"
+                )
+            }
+            Obstruction::Deficient { matched, required } => {
+                write!(f, "maximum matching has {matched} of {required} edges")
+            }
+        }
+    }
+}
+
+/// Necessary conditions for a perfect matching, checked in `O(V + E)`: equal sides, no
+/// isolated vertex, and no more than `k` low-degree vertices on one side sharing the same `k`
+/// neighbours. Returns the first that fails; `None` does not mean a perfect matching exists.
+pub fn perfect_matching_obstruction<G: Adjacency>(graph: &G) -> Option<Obstruction> {
+    let (left, right) = (graph.left_len(), graph.right_len());
+    if left != right {
+        return Some(Obstruction::UnequalSides { left, right });
+    }
+
+    let left_lists: Vec<Vec<usize>> = (0..left)
+        .map(|v| graph.left_neighbours(v).collect())
+        .collect();
+    let mut right_lists = vec![Vec::new(); right];
+    for (i, list) in left_lists.iter().enumerate() {
+        for &j in list {
+            right_lists[j].push(i);
+        }
+    }
+
+    for (side, lists) in [(Side::Left, &left_lists), (Side::Right, &right_lists)] {
+        if let Some(vertex) = lists.iter().position(Vec::is_empty) {
+            return Some(Obstruction::Isolated { side, vertex });
+        }
+    }
+    for (side, lists) in [(Side::Left, &left_lists), (Side::Right, &right_lists)] {
+        if let Some(obstruction) = crowded(side, lists) {
+            return Some(obstruction);
+        }
+    }
+    None
+}
+
+/// Groups the vertices of one side with at most [`LOW_DEGREE`] distinct neighbours by
+/// neighbourhood and reports the first group, in vertex order, to outgrow its neighbourhood.
+fn crowded(side: Side, lists: &[Vec<usize>]) -> Option<Obstruction> {
+    let mut groups: HashMap<Vec<usize>, Vec<usize>> = HashMap::new();
+    for (v, list) in lists.iter().enumerate() {
+        let Some(neighbours) = small_neighbourhood(list) else {
+            continue;
+        };
+        let group = groups.entry(neighbours.clone()).or_default();
+        group.push(v);
+        if group.len() > neighbours.len() {
+            return Some(Obstruction::Crowded {
+                side,
+                vertices: std::mem::take(group),
+                neighbours,
+            });
+        }
+    }
+    None
+}
+
+/// The sorted distinct neighbours, or `None` once there are more than [`LOW_DEGREE`].
+fn small_neighbourhood(list: &[usize]) -> Option<Vec<usize>> {
+    let mut distinct = Vec::with_capacity(LOW_DEGREE + 1);
+    for &w in list {
+        if !distinct.contains(&w) {
+            if distinct.len() == LOW_DEGREE {
+                return None;
+            }
+            distinct.push(w);
+        }
+    }
+    distinct.sort_unstable();
+    Some(distinct)
+}
+
+/// A perfect matching, or why there is none. The checks of
+/// [`perfect_matching_obstruction`] run first, so many infeasible graphs are rejected
+/// without a solve.
+pub fn perfect_matching<G: Adjacency>(graph: &G) -> Result<Matching, Obstruction> {
+    if let Some(obstruction) = perfect_matching_obstruction(graph) {
+        return Err(obstruction);
+    }
+    let matching = hopcroft_karp(graph);
+    if matching.len() < graph.left_len() {
+        return Err(Obstruction::Deficient {
+            matched: matching.len(),
+            required: graph.left_len(),
+        });
+    }
+    Ok(matching)
+}
+
+impl<T> BipartiteGraph<T> {
+    pub fn perfect_matching_obstruction(&self) -> Option<Obstruction> {
+        perfect_matching_obstruction(self)
+    }
+
+    pub fn perfect_matching(&self) -> Result<Matching, Obstruction> {
+        perfect_matching(self)
+    }
+}
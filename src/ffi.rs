@@ -0,0 +1,249 @@
+//! C interface over [`BipartiteGraph`] and [`hopcroft_karp`]. `include/kuhn.h` is generated
+//! from this module by `cbindgen --config cbindgen.toml --output include/kuhn.h`. No function
+//! unwinds into C: a panic is reported as [`KuhnStatus::Panic`] or a null pointer.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+use crate::graph::BipartiteGraph;
+use crate::hopcroft_karp::hopcroft_karp;
+use crate::matching::Matching;
+
+/// Returned by the lookups for an unmatched vertex.
+pub const KUHN_UNMATCHED: usize = usize::MAX;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KuhnStatus {
+    Ok = 0,
+    NullPointer = 1,
+    OutOfRange = 2,
+    Panic = 3,
+}
+
+/// Opaque graph handle.
+pub struct KuhnGraph(BipartiteGraph<()>);
+
+/// Opaque matching handle.
+pub struct KuhnMatching(Matching);
+
+fn guard<R>(on_panic: R, f: impl FnOnce() -> R) -> R {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(on_panic)
+}
+
+/// Graph without edges; free it with [`kuhn_graph_free`].
+#[no_mangle]
+pub extern "C" fn kuhn_graph_new(left_len: usize, right_len: usize) -> *mut KuhnGraph {
+    guard(ptr::null_mut(), || {
+        let graph = BipartiteGraph::from_edges(left_len, right_len, []).expect("no edges");
+        Box::into_raw(Box::new(KuhnGraph(graph)))
+    })
+}
+
+/// Graph with the `num_edges` edges `(left[k], right[k])`, or null if one is out of range.
+///
+/// # Safety
+///
+/// `left` and `right` must each point to `num_edges` readable values, or may be null when
+/// `num_edges` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn kuhn_graph_from_edges(
+    left_len: usize,
+    right_len: usize,
+    left: *const usize,
+    right: *const usize,
+    num_edges: usize,
+) -> *mut KuhnGraph {
+    let graph = kuhn_graph_new(left_len, right_len);
+    if graph.is_null() {
+        return graph;
+    }
+    if kuhn_graph_add_edges(graph, left, right, num_edges) != KuhnStatus::Ok {
+        kuhn_graph_free(graph);
+        return ptr::null_mut();
+    }
+    graph
+}
+
+/// # Safety
+///
+/// `graph` must come from this library and not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn kuhn_graph_add_edge(
+    graph: *mut KuhnGraph,
+    left: usize,
+    right: usize,
+) -> KuhnStatus {
+    kuhn_graph_add_edges(graph, &left, &right, 1)
+}
+
+/// Adds the edges `(left[k], right[k])`. Nothing is added unless every endpoint is in range.
+///
+/// # Safety
+///
+/// `graph` must come from this library and not have been freed; `left` and `right` must each
+/// point to `num_edges` readable values, or may be null when `num_edges` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn kuhn_graph_add_edges(
+    graph: *mut KuhnGraph,
+    left: *const usize,
+    right: *const usize,
+    num_edges: usize,
+) -> KuhnStatus {
+    if graph.is_null() {
+        return KuhnStatus::NullPointer;
+    }
+    if num_edges == 0 {
+        return KuhnStatus::Ok;
+    }
+    if left.is_null() || right.is_null() {
+        return KuhnStatus::NullPointer;
+    }
+    // SAFETY: the caller guarantees both arrays hold `num_edges` values.
+    let (left, right) = unsafe {
+        (
+            slice::from_raw_parts(left, num_edges),
+            slice::from_raw_parts(right, num_edges),
+        )
+    };
+    // SAFETY: the caller guarantees `graph` is a live handle.
+    let graph = unsafe { &mut (*graph).0 };
+    guard(KuhnStatus::Panic, || {
+        let (l, r) = (graph.left_nodes.len(), graph.right_nodes.len());
+        if left.iter().any(|&i| i >= l) || right.iter().any(|&j| j >= r) {
+            return KuhnStatus::OutOfRange;
+        }
+        for (&i, &j) in left.iter().zip(right) {
+            graph.left_nodes[i].neighbours.borrow_mut().push(j);
+            graph.right_nodes[j].neighbours.borrow_mut().push(i);
+        }
+        KuhnStatus::Ok
+    })
+}
+
+/// # Safety
+///
+/// `graph` must be null or a live handle from this library.
+#[no_mangle]
+pub unsafe extern "C" fn kuhn_graph_num_edges(graph: *const KuhnGraph) -> usize {
+    // SAFETY: the caller guarantees `graph` is null or live.
+    unsafe { graph.as_ref() }.map_or(0, |graph| graph.0.num_edges())
+}
+
+/// # Safety
+///
+/// `graph` must be null or a handle from this library that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn kuhn_graph_free(graph: *mut KuhnGraph) {
+    if !graph.is_null() {
+        // SAFETY: the handle was created by `Box::into_raw` and is freed once.
+        drop(unsafe { Box::from_raw(graph) });
+    }
+}
+
+/// Maximum matching of `graph`, or null if `graph` is null; free it with
+/// [`kuhn_matching_free`]. The graph stays valid and unchanged.
+///
+/// # Safety
+///
+/// `graph` must be null or a live handle from this library.
+#[no_mangle]
+pub unsafe extern "C" fn kuhn_solve(graph: *const KuhnGraph) -> *mut KuhnMatching {
+    // SAFETY: the caller guarantees `graph` is null or live.
+    let Some(graph) = (unsafe { graph.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    guard(ptr::null_mut(), || {
+        Box::into_raw(Box::new(KuhnMatching(hopcroft_karp(&graph.0))))
+    })
+}
+
+/// # Safety
+///
+/// `matching` must be null or a live handle from this library.
+#[no_mangle]
+pub unsafe extern "C" fn kuhn_matching_len(matching: *const KuhnMatching) -> usize {
+    // SAFETY: the caller guarantees `matching` is null or live.
+    unsafe { matching.as_ref() }.map_or(0, |matching| matching.0.len())
+}
+
+/// Partner of left vertex `i`, or [`KUHN_UNMATCHED`].
+///
+/// # Safety
+///
+/// `matching` must be null or a live handle from this library.
+#[no_mangle]
+pub unsafe extern "C" fn kuhn_matching_match_of_left(
+    matching: *const KuhnMatching,
+    i: usize,
+) -> usize {
+    // SAFETY: the caller guarantees `matching` is null or live.
+    unsafe { matching.as_ref() }
+        .and_then(|matching| matching.0.match_of_left(i))
+        .unwrap_or(KUHN_UNMATCHED)
+}
+
+/// Partner of right vertex `j`, or [`KUHN_UNMATCHED`].
+///
+/// # Safety
+///
+/// `matching` must be null or a live handle from this library.
+#[no_mangle]
+pub unsafe extern "C" fn kuhn_matching_match_of_right(
+    matching: *const KuhnMatching,
+    j: usize,
+) -> usize {
+    // SAFETY: the caller guarantees `matching` is null or live.
+    unsafe { matching.as_ref() }
+        .and_then(|matching| matching.0.match_of_right(j))
+        .unwrap_or(KUHN_UNMATCHED)
+}
+
+/// Writes up to `capacity` matched pairs, sorted by left vertex, into `left` and `right` and
+/// returns how many were written. Size the arrays with [`kuhn_matching_len`].
+///
+/// # Safety
+///
+/// `matching` must be null or a live handle from this library; `left` and `right` must each
+/// point to `capacity` writable values, or may be null when `capacity` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn kuhn_matching_pairs(
+    matching: *const KuhnMatching,
+    left: *mut usize,
+    right: *mut usize,
+    capacity: usize,
+) -> usize {
+    // SAFETY: the caller guarantees `matching` is null or live.
+    let Some(matching) = (unsafe { matching.as_ref() }) else {
+        return 0;
+    };
+    if capacity == 0 || left.is_null() || right.is_null() {
+        return 0;
+    }
+    // SAFETY: the caller guarantees both arrays have room for `capacity` values.
+    let (left, right) = unsafe {
+        (
+            slice::from_raw_parts_mut(left, capacity),
+            slice::from_raw_parts_mut(right, capacity),
+        )
+    };
+    let mut written = 0;
+    for ((i, j), (l, r)) in matching.0.as_coo().into_iter().zip(left.iter_mut().zip(right)) {
+        *l = i;
+        *r = j;
+        written += 1;
+    }
+    written
+}
+
+/// # Safety
+///
+/// `matching` must be null or a handle from this library that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn kuhn_matching_free(matching: *mut KuhnMatching) {
+    if !matching.is_null() {
+        // SAFETY: the handle was created by `Box::into_raw` and is freed once.
+        drop(unsafe { Box::from_raw(matching) });
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::VecDeque;
+
+use crate::error::{Error, Result};
+use crate::graph::Adjacency;
+use crate::kuhn::{Observer, Phase};
+use crate::matching::Matching;
+
+const UNREACHABLE: usize = usize::MAX;
+
+/// Directed network for Dinic's algorithm. Edge `e` and its residual twin are `e` and `e ^ 1`.
+#[derive(Clone, Debug, Default)]
+pub struct FlowNetwork {
+    adjacency: Vec<Vec<usize>>,
+    to: Vec<usize>,
+    capacity: Vec<usize>,
+    original: Vec<usize>,
+}
+
+impl FlowNetwork {
+    pub fn new(nodes: usize) -> FlowNetwork {
+        FlowNetwork {
+            adjacency: vec![Vec::new(); nodes],
+            ..FlowNetwork::default()
+        }
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize, capacity: usize) -> Result<usize> {
+        let nodes = self.num_nodes();
+        for v in [from, to] {
+            if v >= nodes {
+                return Err(Error::IndexOutOfRange {
+                    index: v,
+                    len: nodes,
+                });
+            }
+        }
+
+        let id = self.to.len();
+        for (head, cap) in [(to, capacity), (from, 0)] {
+            self.to.push(head);
+            self.capacity.push(cap);
+            self.original.push(cap);
+        }
+        self.adjacency[from].push(id);
+        self.adjacency[to].push(id + 1);
+        Ok(id)
+    }
+
+    pub fn flow(&self, edge: usize) -> usize {
+        self.original[edge] - self.capacity[edge]
+    }
+
+    pub fn max_flow(&mut self, source: usize, sink: usize) -> usize {
+        let mut total = 0;
+        if source == sink {
+            return total;
+        }
+
+        while let Some(mut level) = self.levels(source, sink) {
+            let mut next = vec![0; self.num_nodes()];
+            let mut path: Vec<usize> = Vec::new();
+            let mut v = source;
+
+            loop {
+                if v == sink {
+                    let pushed = path.iter().map(|&e| self.capacity[e]).min().unwrap_or(0);
+                    for &e in &path {
+                        self.capacity[e] -= pushed;
+                        self.capacity[e ^ 1] += pushed;
+                    }
+                    total += pushed;
+                    path.clear();
+                    v = source;
+                    continue;
+                }
+
+                let advance = self.adjacency[v][next[v]..].iter().position(|&e| {
+                    self.capacity[e] > 0 && level[self.to[e]] == level[v].wrapping_add(1)
+                });
+                match advance {
+                    Some(offset) => {
+                        next[v] += offset;
+                        let e = self.adjacency[v][next[v]];
+                        path.push(e);
+                        v = self.to[e];
+                    }
+                    None => {
+                        level[v] = usize::MAX;
+                        let Some(e) = path.pop() else { break };
+                        v = self.to[e ^ 1];
+                        next[v] += 1;
+                    }
+                }
+            }
+        }
+        total
+    }
+
+    /// Nodes reachable from `source` through edges with capacity left; after
+    /// [`FlowNetwork::max_flow`], the source side of a minimum cut.
+    pub fn source_side(&self, source: usize) -> Vec<bool> {
+        let mut seen = vec![false; self.num_nodes()];
+        seen[source] = true;
+        let mut stack = vec![source];
+        while let Some(v) = stack.pop() {
+            for &e in &self.adjacency[v] {
+                let w = self.to[e];
+                if self.capacity[e] > 0 && !seen[w] {
+                    seen[w] = true;
+                    stack.push(w);
+                }
+            }
+        }
+        seen
+    }
+
+    fn levels(&self, source: usize, sink: usize) -> Option<Vec<usize>> {
+        let mut level = vec![usize::MAX; self.num_nodes()];
+        level[source] = 0;
+        let mut queue = VecDeque::from([source]);
+        while let Some(v) = queue.pop_front() {
+            for &e in &self.adjacency[v] {
+                let w = self.to[e];
+                if self.capacity[e] > 0 && level[w] == usize::MAX {
+                    level[w] = level[v] + 1;
+                    queue.push_back(w);
+                }
+            }
+        }
+        (level[sink] != usize::MAX).then_some(level)
+    }
+}
+
+/// Maximum matching by push-relabel on the unit network, with single pushes from a FIFO of
+/// free left vertices and labels kept on the right side only. A push takes the lowest-labelled
+/// neighbour, evicting its partner, and relabels it to the second lowest label plus two.
+///
+/// Labels are recomputed exactly by a backwards BFS from the free right vertices at the start
+/// and then after every `relabel_every` pushes; `0` keeps only the initial one. Without these
+/// global relabelings the local relabels alone can take quadratically many pushes on
+/// adversarial instances.
+pub fn push_relabel_matching<G: Adjacency, O: Observer>(
+    graph: &G,
+    relabel_every: usize,
+    observer: &mut O,
+) -> Matching {
+    let (l, r) = (graph.left_len(), graph.right_len());
+    observer.phase_started(Phase::Bookkeeping);
+    let mut offsets = vec![0; r + 1];
+    for i in 0..l {
+        for j in graph.left_neighbours(i) {
+            offsets[j + 1] += 1;
+        }
+    }
+    for j in 0..r {
+        offsets[j + 1] += offsets[j];
+    }
+    let mut fill = offsets.clone();
+    let mut right_neighbours = vec![0; offsets[r]];
+    for i in 0..l {
+        for j in graph.left_neighbours(i) {
+            right_neighbours[fill[j]] = i;
+            fill[j] += 1;
+        }
+    }
+
+    let mut match_left = vec![None; l];
+    let mut match_right = vec![None; r];
+    let mut label = vec![0; r];
+    let mut queue = VecDeque::new();
+    let mut active: VecDeque<usize> = (0..l).collect();
+    // Alternating paths visit distinct left vertices, so finite distances are at most 2L.
+    let limit = 2 * l;
+    observer.phase_finished(Phase::Bookkeeping);
+
+    let mut aborted = false;
+    while !aborted && !active.is_empty() {
+        observer.phase_started(Phase::Bfs);
+        label.fill(UNREACHABLE);
+        for (j, m) in match_right.iter().enumerate() {
+            if m.is_none() {
+                label[j] = 0;
+                queue.push_back(j);
+            }
+        }
+        while let Some(j) = queue.pop_front() {
+            for &i in &right_neighbours[offsets[j]..offsets[j + 1]] {
+                if let Some(k) = match_left[i] {
+                    if k != j && label[k] == UNREACHABLE {
+                        label[k] = label[j] + 2;
+                        queue.push_back(k);
+                    }
+                }
+            }
+        }
+        observer.phase_finished(Phase::Bfs);
+
+        observer.phase_started(Phase::Dfs);
+        let mut pushes = 0;
+        while let Some(u) = active.pop_front() {
+            if observer.should_abort() {
+                aborted = true;
+                break;
+            }
+            let (mut first, mut second) = (None, UNREACHABLE);
+            for j in graph.left_neighbours(u) {
+                observer.edge_scan(u, j);
+                match first {
+                    Some(f) if label[j] >= label[f] => second = second.min(label[j]),
+                    _ => {
+                        if let Some(f) = first {
+                            second = second.min(label[f]);
+                        }
+                        first = Some(j);
+                    }
+                }
+            }
+            let Some(j) = first.filter(|&j| label[j] <= limit) else {
+                continue;
+            };
+
+            observer.match_flip(u, j);
+            label[j] = if second < limit { second + 2 } else { UNREACHABLE };
+            match_left[u] = Some(j);
+            match match_right[j].replace(u) {
+                Some(evicted) => {
+                    match_left[evicted] = None;
+                    active.push_back(evicted);
+                }
+                None => observer.augmented(u, &match_right),
+            }
+            pushes += 1;
+            if relabel_every > 0 && pushes >= relabel_every {
+                break;
+            }
+        }
+        observer.phase_finished(Phase::Dfs);
+    }
+
+    Matching::from_parts(match_left, match_right)
+}
+
+/// A subgraph where every vertex has at most its capacity of incident edges.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DegreeConstrainedMatching {
+    left_partners: Vec<Vec<usize>>,
+    right_partners: Vec<Vec<usize>>,
+}
+
+impl DegreeConstrainedMatching {
+    pub fn partners_of_left(&self, i: usize) -> &[usize] {
+        self.left_partners.get(i).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn partners_of_right(&self, j: usize) -> &[usize] {
+        self.right_partners.get(j).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn len(&self) -> usize {
+        self.left_partners.iter().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.left_partners.iter().all(Vec::is_empty)
+    }
+
+    pub fn pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.left_partners
+            .iter()
+            .enumerate()
+            .flat_map(|(i, partners)| partners.iter().map(move |&j| (i, j)))
+    }
+}
+
+/// Maximum set of distinct edges such that left vertex `i` has at most `left_capacities[i]`
+/// of them and right vertex `j` at most `right_capacities[j]`.
+pub fn degree_constrained_matching<G: Adjacency>(
+    graph: &G,
+    left_capacities: &[usize],
+    right_capacities: &[usize],
+) -> Result<DegreeConstrainedMatching> {
+    let (l, r) = (graph.left_len(), graph.right_len());
+    for (side, capacities, len) in [("left", left_capacities, l), ("right", right_capacities, r)] {
+        if capacities.len() != len {
+            return Err(Error::InvalidParameter(format!(
+                "{} {side} capacities for {len} {side} vertices",
+                capacities.len()
+            )));
+        }
+    }
+
+    let (source, sink) = (l + r, l + r + 1);
+    let mut network = FlowNetwork::new(l + r + 2);
+    for (i, &capacity) in left_capacities.iter().enumerate() {
+        network.add_edge(source, i, capacity)?;
+    }
+    for (j, &capacity) in right_capacities.iter().enumerate() {
+        network.add_edge(l + j, sink, capacity)?;
+    }
+
+    let mut edges = Vec::new();
+    for i in 0..l {
+        let mut neighbours: Vec<usize> = graph.left_neighbours(i).collect();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        for j in neighbours {
+            edges.push((i, j, network.add_edge(i, l + j, 1)?));
+        }
+    }
+
+    network.max_flow(source, sink);
+
+    let mut left_partners = vec![Vec::new(); l];
+    let mut right_partners = vec![Vec::new(); r];
+    for (i, j, e) in edges {
+        if network.flow(e) > 0 {
+            left_partners[i].push(j);
+            right_partners[j].push(i);
+        }
+    }
+    Ok(DegreeConstrainedMatching {
+        left_partners,
+        right_partners,
+    })
+}
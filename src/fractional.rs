@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+
+use crate::error::{Error, Result};
+use crate::graph::{Adjacency, BipartiteGraph};
+use crate::matching::Matching;
+
+const EPSILON: f64 = 1e-9;
+
+/// Edge values in `[0, 1]` with every vertex load at most 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FractionalMatching {
+    left_len: usize,
+    right_len: usize,
+    values: Vec<((usize, usize), f64)>,
+}
+
+impl FractionalMatching {
+    pub fn new(
+        left_len: usize,
+        right_len: usize,
+        values: Vec<((usize, usize), f64)>,
+    ) -> Result<FractionalMatching> {
+        let mut merged: HashMap<(usize, usize), f64> = HashMap::new();
+        for ((i, j), x) in values {
+            if i >= left_len {
+                return Err(Error::IndexOutOfRange {
+                    index: i,
+                    len: left_len,
+                });
+            }
+            if j >= right_len {
+                return Err(Error::IndexOutOfRange {
+                    index: j,
+                    len: right_len,
+                });
+            }
+            *merged.entry((i, j)).or_default() += x;
+        }
+
+        let mut values: Vec<((usize, usize), f64)> = merged.into_iter().collect();
+        values.sort_by_key(|&(edge, _)| edge);
+        let matching = FractionalMatching {
+            left_len,
+            right_len,
+            values,
+        };
+        if !matching.is_feasible() {
+            return Err(Error::InvalidParameter(
+                "fractional values must lie in [0, 1] with vertex loads at most 1".to_string(),
+            ));
+        }
+        Ok(matching)
+    }
+
+    pub fn from_matching(matching: &Matching) -> FractionalMatching {
+        FractionalMatching {
+            left_len: matching.matched_left().len(),
+            right_len: matching.matched_right().len(),
+            values: matching.as_coo().into_iter().map(|e| (e, 1.0)).collect(),
+        }
+    }
+
+    pub fn values(&self) -> &[((usize, usize), f64)] {
+        &self.values
+    }
+
+    pub fn size(&self) -> f64 {
+        self.values.iter().map(|&(_, x)| x).sum()
+    }
+
+    pub fn is_integral(&self) -> bool {
+        self.values
+            .iter()
+            .all(|&(_, x)| x.abs() <= EPSILON || (1.0 - x).abs() <= EPSILON)
+    }
+
+    fn is_feasible(&self) -> bool {
+        let (left, right) = self.loads();
+        self.values
+            .iter()
+            .all(|&(_, x)| (-EPSILON..=1.0 + EPSILON).contains(&x))
+            && left.iter().chain(&right).all(|&load| load <= 1.0 + EPSILON)
+    }
+
+    fn loads(&self) -> (Vec<f64>, Vec<f64>) {
+        let mut left = vec![0.0; self.left_len];
+        let mut right = vec![0.0; self.right_len];
+        for &((i, j), x) in &self.values {
+            left[i] += x;
+            right[j] += x;
+        }
+        (left, right)
+    }
+
+    /// Integral matching of at least the fractional size. Repeatedly takes a cycle or a maximal
+    /// path of fractional edges and shifts value alternately along it. Path endpoints touch no
+    /// other fractional edge, so loads stay within 1, the size never drops and at least one
+    /// more edge becomes integral each time.
+    pub fn round(&self) -> Matching {
+        let l = self.left_len;
+        let mut x: Vec<f64> = self.values.iter().map(|&(_, x)| x).collect();
+        let ends: Vec<(usize, usize)> = self.values.iter().map(|&((i, j), _)| (i, l + j)).collect();
+        let mut incident = vec![Vec::new(); l + self.right_len];
+        for (e, &(u, v)) in ends.iter().enumerate() {
+            incident[u].push(e);
+            incident[v].push(e);
+        }
+
+        let fractional = |x: &[f64], e: usize| x[e] > EPSILON && x[e] < 1.0 - EPSILON;
+        let other = |e: usize, v: usize| if ends[e].0 == v { ends[e].1 } else { ends[e].0 };
+        // Follows fractional edges from `start` until stuck or a vertex repeats; returns the
+        // edges from the repeated vertex on, or the whole path and the vertex it ended at.
+        let walk = |x: &[f64], start: usize| -> (Vec<usize>, Option<usize>) {
+            let mut position = HashMap::from([(start, 0)]);
+            let mut edges: Vec<usize> = Vec::new();
+            let mut v = start;
+            loop {
+                let last = edges.last().copied();
+                let next = incident[v]
+                    .iter()
+                    .copied()
+                    .find(|&e| Some(e) != last && fractional(x, e));
+                let Some(e) = next else {
+                    return (edges, Some(v));
+                };
+                edges.push(e);
+                v = other(e, v);
+                if let Some(&p) = position.get(&v) {
+                    return (edges.split_off(p), None);
+                }
+                position.insert(v, edges.len());
+            }
+        };
+
+        for (start, &(u, _)) in ends.iter().enumerate() {
+            while fractional(&x, start) {
+                let edges = match walk(&x, u) {
+                    (cycle, None) => cycle,
+                    (_, Some(end)) => walk(&x, end).0,
+                };
+
+                let mut step = f64::INFINITY;
+                for (k, &e) in edges.iter().enumerate() {
+                    step = step.min(if k % 2 == 0 { 1.0 - x[e] } else { x[e] });
+                }
+                for (k, &e) in edges.iter().enumerate() {
+                    x[e] += if k % 2 == 0 { step } else { -step };
+                    if x[e] < EPSILON {
+                        x[e] = 0.0;
+                    } else if x[e] > 1.0 - EPSILON {
+                        x[e] = 1.0;
+                    }
+                }
+            }
+        }
+
+        let mut matched_right = vec![None; self.right_len];
+        for (e, &((i, j), _)) in self.values.iter().enumerate() {
+            if x[e] > 0.5 {
+                matched_right[j] = Some(i);
+            }
+        }
+        Matching::from_matched_right(l, matched_right)
+    }
+}
+
+impl<T> BipartiteGraph<T> {
+    /// Fractional matching from iterative proportional (Sinkhorn) scaling of the adjacency
+    /// matrix, scaled down at the end so no vertex load exceeds 1.
+    pub fn scaled_fractional_matching(&self, iterations: usize) -> FractionalMatching {
+        let (l, r) = (self.left_len(), self.right_len());
+        let mut edges: Vec<(usize, usize)> = self.edges();
+        edges.sort_unstable();
+        edges.dedup();
+        let mut x = vec![1.0; edges.len()];
+
+        let sums = |x: &[f64]| {
+            let mut left = vec![0.0; l];
+            let mut right = vec![0.0; r];
+            for (&(i, j), &value) in edges.iter().zip(x) {
+                left[i] += value;
+                right[j] += value;
+            }
+            (left, right)
+        };
+
+        for _ in 0..iterations {
+            let (left, _) = sums(&x);
+            for (value, &(i, _)) in x.iter_mut().zip(&edges) {
+                *value /= left[i];
+            }
+            let (_, right) = sums(&x);
+            for (value, &(_, j)) in x.iter_mut().zip(&edges) {
+                *value /= right[j];
+            }
+        }
+
+        let (left, right) = sums(&x);
+        for (value, &(i, j)) in x.iter_mut().zip(&edges) {
+            *value /= left[i].max(right[j]).max(1.0);
+        }
+
+        FractionalMatching {
+            left_len: l,
+            right_len: r,
+            values: edges.into_iter().zip(x).collect(),
+        }
+    }
+}
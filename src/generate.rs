@@ -0,0 +1,708 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use rand::prelude::*;
+use rand::seq::index::sample;
+
+use crate::csr::BINARY_MAGIC;
+use crate::error::{Error, Result};
+use crate::graph::BipartiteGraph;
+use crate::weights::{EdgeWeights, WeightDistribution};
+
+/// Mixed into the seed for [`GenConfig::solver_rng`].
+const SOLVER_STREAM: u64 = 0x5851_f42d_4c95_7f2d;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GenModel {
+    /// Exactly `m` distinct edges chosen uniformly.
+    #[default]
+    Gnm,
+    /// Every possible edge present independently with probability `p`.
+    Gnp,
+    /// `m` edges with endpoints drawn uniformly and independently, so parallel edges occur.
+    /// A density above 1 gives the mean multiplicity per vertex pair.
+    Multigraph,
+}
+
+impl FromStr for GenModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<GenModel, String> {
+        match s {
+            "gnm" => Ok(GenModel::Gnm),
+            "gnp" => Ok(GenModel::Gnp),
+            "multigraph" => Ok(GenModel::Multigraph),
+            _ => Err(format!(
+                "unknown model `{s}` (expected gnm, gnp or multigraph)"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Edges {
+    Count(usize),
+    Probability(f64),
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GenConfigBuilder {
+    sizes: Option<(usize, usize)>,
+    model: GenModel,
+    edges: Option<usize>,
+    density: Option<f64>,
+    weights: Option<WeightDistribution>,
+    seed: u64,
+}
+
+impl GenConfigBuilder {
+    pub fn sizes(mut self, left: usize, right: usize) -> GenConfigBuilder {
+        self.sizes = Some((left, right));
+        self
+    }
+
+    pub fn model(mut self, model: GenModel) -> GenConfigBuilder {
+        self.model = model;
+        self
+    }
+
+    pub fn edges(mut self, edges: usize) -> GenConfigBuilder {
+        self.edges = Some(edges);
+        self
+    }
+
+    pub fn density(mut self, density: f64) -> GenConfigBuilder {
+        self.density = Some(density);
+        self
+    }
+
+    pub fn weights(mut self, distribution: WeightDistribution) -> GenConfigBuilder {
+        self.weights = Some(distribution);
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> GenConfigBuilder {
+        self.seed = seed;
+        self
+    }
+
+    pub fn build(self) -> Result<GenConfig> {
+        let invalid = |message: &str| Error::InvalidParameter(message.to_string());
+
+        let (left, right) = self.sizes.ok_or_else(|| invalid("missing graph sizes"))?;
+        let max_edges = left.checked_mul(right).ok_or(Error::Overflow)?;
+
+        let edges = match (self.model, self.edges, self.density) {
+            (_, Some(_), Some(_)) => return Err(invalid("set either an edge count or a density")),
+            (_, None, None) => return Err(invalid("missing edge count or density")),
+            (GenModel::Multigraph, Some(edges), None) if edges > 0 && max_edges == 0 => {
+                return Err(Error::TooManyEdges {
+                    requested: edges,
+                    max: max_edges,
+                })
+            }
+            (GenModel::Multigraph, None, Some(density))
+                if !(density >= 0.0 && density.is_finite()) =>
+            {
+                return Err(Error::InvalidParameter(format!(
+                    "density {density} is not a non-negative number"
+                )))
+            }
+            (GenModel::Multigraph, Some(edges), None) => Edges::Count(edges),
+            (GenModel::Multigraph, None, Some(density)) => {
+                Edges::Count((density * max_edges as f64).round() as usize)
+            }
+            (_, Some(edges), None) if edges > max_edges => {
+                return Err(Error::TooManyEdges {
+                    requested: edges,
+                    max: max_edges,
+                })
+            }
+            (_, None, Some(density)) if !(0.0..=1.0).contains(&density) => {
+                return Err(Error::InvalidParameter(format!(
+                    "density {density} is not in [0, 1]"
+                )))
+            }
+            (GenModel::Gnm, Some(edges), None) => Edges::Count(edges),
+            (GenModel::Gnm, None, Some(density)) => {
+                Edges::Count((density * max_edges as f64).round() as usize)
+            }
+            (GenModel::Gnp, Some(0), None) => Edges::Probability(0.0),
+            (GenModel::Gnp, Some(edges), None) => {
+                Edges::Probability(edges as f64 / max_edges as f64)
+            }
+            (GenModel::Gnp, None, Some(density)) => Edges::Probability(density),
+        };
+
+        if let Some(distribution) = self.weights {
+            distribution.validate()?;
+        }
+        Ok(GenConfig {
+            left,
+            right,
+            model: self.model,
+            edges,
+            weights: self.weights,
+            seed: self.seed,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GenConfig {
+    left: usize,
+    right: usize,
+    model: GenModel,
+    edges: Edges,
+    weights: Option<WeightDistribution>,
+    seed: u64,
+}
+
+impl GenConfig {
+    pub fn builder() -> GenConfigBuilder {
+        GenConfigBuilder::default()
+    }
+
+    pub fn left_len(&self) -> usize {
+        self.left
+    }
+
+    pub fn right_len(&self) -> usize {
+        self.right
+    }
+
+    pub fn model(&self) -> GenModel {
+        self.model
+    }
+
+    pub fn weights(&self) -> Option<WeightDistribution> {
+        self.weights
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn expected_edges(&self) -> f64 {
+        match self.edges {
+            Edges::Count(edges) => edges as f64,
+            Edges::Probability(p) => p * (self.left * self.right) as f64,
+        }
+    }
+
+    /// A fresh generator seeded from the configuration.
+    pub fn rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.seed)
+    }
+
+    /// A generator for what runs on the instance, such as a randomized solver: derived from
+    /// the seed but a separate stream from [`GenConfig::rng`].
+    pub fn solver_rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.seed ^ SOLVER_STREAM)
+    }
+
+    pub fn graph<T: Default>(&self, rng: &mut StdRng) -> Result<BipartiteGraph<T>> {
+        match self.edges {
+            Edges::Count(edges) if self.model == GenModel::Multigraph => {
+                let (l, r) = (self.left, self.right);
+                let edges: Vec<(usize, usize)> = (0..edges)
+                    .map(|_| (rng.gen_range(0..l), rng.gen_range(0..r)))
+                    .collect();
+                BipartiteGraph::from_edges(l, r, edges)
+            }
+            Edges::Count(edges) => BipartiteGraph::random(rng, self.left, self.right, edges),
+            Edges::Probability(p) => {
+                let edges = binomial_codes(rng, self.left * self.right, p)
+                    .map(|code| (code / self.right, code % self.right));
+                BipartiteGraph::from_edges(self.left, self.right, edges)
+            }
+        }
+    }
+
+    /// Like [`GenConfig::graph`], also drawing edge weights when a distribution is set.
+    pub fn weighted_graph<T: Default>(
+        &self,
+        rng: &mut StdRng,
+    ) -> Result<(BipartiteGraph<T>, Option<EdgeWeights>)> {
+        let graph = self.graph(rng)?;
+        let weights = match self.weights {
+            Some(distribution) => Some(EdgeWeights::random(rng, graph.edges(), distribution)?),
+            None => None,
+        };
+        Ok((graph, weights))
+    }
+}
+
+/// Running statistics of a streamed instance. Only completed left vertices enter
+/// `left_degrees`; `right_degrees` covers every right vertex with its degree so far.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GenStats {
+    pub rows: usize,
+    pub edges: usize,
+    pub left_degrees: Vec<usize>,
+    pub right_degrees: Vec<usize>,
+}
+
+impl GenStats {
+    pub fn isolated_left(&self) -> usize {
+        self.left_degrees.first().copied().unwrap_or(0)
+    }
+
+    pub fn isolated_right(&self) -> usize {
+        self.right_degrees.first().copied().unwrap_or(0)
+    }
+}
+
+impl GenConfig {
+    /// Generates the same instance as [`GenConfig::graph`] for the same `rng`, in CSR order,
+    /// without building it. `on_stats` runs after every `report_every` completed left
+    /// vertices (never when 0). `Gnp` draws its edges lazily; the other models hold one
+    /// sorted code per edge.
+    pub fn stream_stats(
+        &self,
+        rng: &mut StdRng,
+        report_every: usize,
+        on_stats: impl FnMut(&GenStats),
+    ) -> GenStats {
+        self.stream(rng, report_every, on_stats, |_, _| Ok(()))
+            .expect("counting edges cannot fail")
+    }
+
+    /// Like [`GenConfig::stream_stats`], writing the edges to `path` in the binary format of
+    /// [`CsrGraph::write_binary`](crate::csr::CsrGraph::write_binary). Only the offsets are
+    /// kept in memory; they and the header are filled in once the targets are on disk.
+    pub fn stream_csr(
+        &self,
+        rng: &mut StdRng,
+        path: &Path,
+        report_every: usize,
+        on_stats: impl FnMut(&GenStats),
+    ) -> Result<GenStats> {
+        let mut w = BufWriter::new(File::create(path)?);
+        w.write_all(BINARY_MAGIC)?;
+        for _ in 0..self.left + 4 {
+            w.write_all(&0u64.to_le_bytes())?;
+        }
+
+        let mut offsets: Vec<u64> = Vec::with_capacity(self.left + 1);
+        offsets.push(0);
+        let stats = self.stream(rng, report_every, on_stats, |i, j| {
+            while offsets.len() <= i + 1 {
+                offsets.push(offsets[offsets.len() - 1]);
+            }
+            offsets[i + 1] += 1;
+            w.write_all(&(j as u64).to_le_bytes())?;
+            Ok(())
+        })?;
+        while offsets.len() <= self.left {
+            offsets.push(offsets[offsets.len() - 1]);
+        }
+
+        w.seek(SeekFrom::Start(BINARY_MAGIC.len() as u64))?;
+        for value in [self.left, self.right, stats.edges] {
+            w.write_all(&(value as u64).to_le_bytes())?;
+        }
+        for offset in offsets {
+            w.write_all(&offset.to_le_bytes())?;
+        }
+        w.flush()?;
+        Ok(stats)
+    }
+
+    fn stream<F>(
+        &self,
+        rng: &mut StdRng,
+        report_every: usize,
+        mut on_stats: impl FnMut(&GenStats),
+        mut edge: F,
+    ) -> Result<GenStats>
+    where
+        F: FnMut(usize, usize) -> Result<()>,
+    {
+        let (l, r) = (self.left, self.right);
+        let codes: Box<dyn Iterator<Item = usize> + '_> = match self.edges {
+            Edges::Count(edges) => {
+                let mut codes: Vec<usize> = if self.model == GenModel::Multigraph {
+                    (0..edges)
+                        .map(|_| rng.gen_range(0..l) * r + rng.gen_range(0..r))
+                        .collect()
+                } else {
+                    sample(rng, l * r, edges).into_vec()
+                };
+                codes.sort_unstable();
+                Box::new(codes.into_iter())
+            }
+            Edges::Probability(p) => Box::new(binomial_codes(rng, l * r, p)),
+        };
+
+        let mut stats = GenStats {
+            right_degrees: vec![r],
+            ..GenStats::default()
+        };
+        let mut right_degree = vec![0; r];
+        let mut row_degree = 0;
+        let mut finish_row = |stats: &mut GenStats, row_degree: &mut usize| {
+            bump(&mut stats.left_degrees, None, *row_degree);
+            *row_degree = 0;
+            stats.rows += 1;
+            if report_every > 0 && stats.rows.is_multiple_of(report_every) {
+                on_stats(stats);
+            }
+        };
+
+        for code in codes {
+            let (i, j) = (code / r, code % r);
+            while stats.rows < i {
+                finish_row(&mut stats, &mut row_degree);
+            }
+            edge(i, j)?;
+            row_degree += 1;
+            stats.edges += 1;
+            bump(
+                &mut stats.right_degrees,
+                Some(right_degree[j]),
+                right_degree[j] + 1,
+            );
+            right_degree[j] += 1;
+        }
+        while stats.rows < l {
+            finish_row(&mut stats, &mut row_degree);
+        }
+        Ok(stats)
+    }
+}
+
+/// Left and right degree sequences realized by some simple bipartite graph.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DegreeSequence {
+    left: Vec<usize>,
+    right: Vec<usize>,
+}
+
+impl DegreeSequence {
+    /// Checks the Gale-Ryser conditions: equal sums and, with the left degrees in
+    /// non-increasing order, every `k` largest summing to at most `sum_j min(right[j], k)`.
+    pub fn new(left: Vec<usize>, right: Vec<usize>) -> Result<DegreeSequence> {
+        let (left_sum, right_sum) = (left.iter().sum::<usize>(), right.iter().sum::<usize>());
+        if left_sum != right_sum {
+            return Err(Error::InvalidParameter(format!(
+                "left degrees sum to {left_sum} but right degrees to {right_sum}"
+            )));
+        }
+
+        let mut sorted = left.clone();
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+        // at_least[t] counts the right vertices of degree at least t; k never exceeds |L|.
+        let mut at_least = vec![0; left.len() + 2];
+        for &degree in &right {
+            at_least[degree.min(left.len() + 1)] += 1;
+        }
+        for t in (0..=left.len()).rev() {
+            at_least[t] += at_least[t + 1];
+        }
+        let (mut prefix, mut capacity) = (0, 0);
+        for (k, &degree) in (1..).zip(&sorted) {
+            prefix += degree;
+            capacity += at_least[k];
+            if prefix > capacity {
+                return Err(Error::InvalidParameter(format!(
+                    "degree sequences fail the Gale-Ryser condition at k = {k}"
+                )));
+            }
+        }
+        Ok(DegreeSequence { left, right })
+    }
+
+    pub fn left(&self) -> &[usize] {
+        &self.left
+    }
+
+    pub fn right(&self) -> &[usize] {
+        &self.right
+    }
+
+    pub fn num_edges(&self) -> usize {
+        self.left.iter().sum()
+    }
+
+    /// The configuration model: left and right stubs paired uniformly, so parallel edges occur.
+    pub fn multigraph<T: Default>(&self, rng: &mut StdRng) -> BipartiteGraph<T> {
+        BipartiteGraph::from_edges(self.left.len(), self.right.len(), self.pairing(rng))
+            .expect("stubs are in range")
+    }
+
+    /// A simple graph with exactly these degrees. The configuration-model pairing is repaired
+    /// by switching each parallel edge with a random other edge. Should that stall, the graph
+    /// is built greedily, joining each left vertex to the right vertices of most remaining
+    /// degree, and then randomized by `10 |E|` switches.
+    pub fn graph<T: Default>(&self, rng: &mut StdRng) -> BipartiteGraph<T> {
+        let mut edges = self.pairing(rng);
+        let mut counts = edge_counts(&edges);
+        let budget = 100 * edges.len();
+        let mut attempts = 0;
+        loop {
+            let parallel: Vec<usize> = (0..edges.len())
+                .filter(|&e| counts[&edges[e]] > 1)
+                .collect();
+            if parallel.is_empty() {
+                break;
+            }
+            if attempts > budget {
+                edges = self.greedy_edges();
+                counts = edge_counts(&edges);
+                for _ in 0..10 * edges.len() {
+                    let (e, f) = (rng.gen_range(0..edges.len()), rng.gen_range(0..edges.len()));
+                    switch(&mut edges, &mut counts, e, f);
+                }
+                break;
+            }
+            for e in parallel {
+                if counts[&edges[e]] > 1 {
+                    let f = rng.gen_range(0..edges.len());
+                    switch(&mut edges, &mut counts, e, f);
+                    attempts += 1;
+                }
+            }
+        }
+        BipartiteGraph::from_edges(self.left.len(), self.right.len(), edges)
+            .expect("edges are in range")
+    }
+
+    fn pairing(&self, rng: &mut StdRng) -> Vec<(usize, usize)> {
+        let stubs = |degrees: &[usize]| -> Vec<usize> {
+            (0..degrees.len())
+                .flat_map(|v| std::iter::repeat_n(v, degrees[v]))
+                .collect()
+        };
+        let mut right = stubs(&self.right);
+        right.shuffle(rng);
+        stubs(&self.left).into_iter().zip(right).collect()
+    }
+
+    fn greedy_edges(&self) -> Vec<(usize, usize)> {
+        let mut remaining: BinaryHeap<(usize, Reverse<usize>)> = (0..self.right.len())
+            .filter(|&j| self.right[j] > 0)
+            .map(|j| (self.right[j], Reverse(j)))
+            .collect();
+        let mut edges = Vec::with_capacity(self.num_edges());
+        let mut taken = Vec::new();
+        for (i, &degree) in self.left.iter().enumerate() {
+            for _ in 0..degree {
+                let (d, Reverse(j)) = remaining.pop().expect("the sequences are graphical");
+                edges.push((i, j));
+                taken.push((d - 1, Reverse(j)));
+            }
+            remaining.extend(taken.drain(..).filter(|&(d, _)| d > 0));
+        }
+        edges
+    }
+}
+
+/// A bipartite stochastic block model: each side is cut into consecutive blocks, and every
+/// edge between left block `a` and right block `b` is present independently with probability
+/// `probabilities[a * right_blocks + b]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockModel {
+    left_sizes: Vec<usize>,
+    right_sizes: Vec<usize>,
+    probabilities: Vec<f64>,
+}
+
+impl BlockModel {
+    pub fn new(
+        left_sizes: Vec<usize>,
+        right_sizes: Vec<usize>,
+        probabilities: Vec<f64>,
+    ) -> Result<BlockModel> {
+        if left_sizes.len() * right_sizes.len() != probabilities.len() {
+            return Err(Error::InvalidParameter(format!(
+                "{} probabilities for {}x{} blocks",
+                probabilities.len(),
+                left_sizes.len(),
+                right_sizes.len()
+            )));
+        }
+        if let Some(p) = probabilities.iter().find(|p| !(0.0..=1.0).contains(*p)) {
+            return Err(Error::InvalidParameter(format!(
+                "probability {p} is not in [0, 1]"
+            )));
+        }
+        for sizes in [&left_sizes, &right_sizes] {
+            sizes
+                .iter()
+                .try_fold(0usize, |total, &size| total.checked_add(size))
+                .ok_or(Error::Overflow)?;
+        }
+        Ok(BlockModel {
+            left_sizes,
+            right_sizes,
+            probabilities,
+        })
+    }
+
+    /// `blocks` communities splitting both sides as evenly as possible, with edges inside a
+    /// community drawn with probability `p_in` and between communities with `p_out`.
+    pub fn planted(
+        blocks: usize,
+        left: usize,
+        right: usize,
+        p_in: f64,
+        p_out: f64,
+    ) -> Result<BlockModel> {
+        if blocks == 0 {
+            return Err(Error::InvalidParameter("need at least one block".to_string()));
+        }
+        let split = |n: usize| -> Vec<usize> {
+            (0..blocks)
+                .map(|b| n / blocks + usize::from(b < n % blocks))
+                .collect()
+        };
+        let probabilities = (0..blocks * blocks)
+            .map(|k| if k / blocks == k % blocks { p_in } else { p_out })
+            .collect();
+        BlockModel::new(split(left), split(right), probabilities)
+    }
+
+    pub fn left_len(&self) -> usize {
+        self.left_sizes.iter().sum()
+    }
+
+    pub fn right_len(&self) -> usize {
+        self.right_sizes.iter().sum()
+    }
+
+    pub fn probability(&self, left_block: usize, right_block: usize) -> f64 {
+        self.probabilities[left_block * self.right_sizes.len() + right_block]
+    }
+
+    /// Block of left vertex `i`, or `None` past the last one.
+    pub fn left_block(&self, i: usize) -> Option<usize> {
+        block_of(&self.left_sizes, i)
+    }
+
+    pub fn right_block(&self, j: usize) -> Option<usize> {
+        block_of(&self.right_sizes, j)
+    }
+
+    pub fn expected_edges(&self) -> f64 {
+        let mut total = 0.0;
+        for (a, &left) in self.left_sizes.iter().enumerate() {
+            for (b, &right) in self.right_sizes.iter().enumerate() {
+                total += self.probability(a, b) * (left * right) as f64;
+            }
+        }
+        total
+    }
+
+    /// Draws every block pair with geometric skips, so the cost is linear in the edges drawn
+    /// rather than in the number of vertex pairs.
+    pub fn graph<T: Default>(&self, rng: &mut StdRng) -> BipartiteGraph<T> {
+        let starts = |sizes: &[usize]| -> Vec<usize> {
+            sizes
+                .iter()
+                .scan(0, |start, &size| {
+                    *start += size;
+                    Some(*start - size)
+                })
+                .collect()
+        };
+        let (left_starts, right_starts) = (starts(&self.left_sizes), starts(&self.right_sizes));
+
+        let mut edges = Vec::with_capacity(self.expected_edges() as usize);
+        for (a, &left) in self.left_sizes.iter().enumerate() {
+            for (b, &right) in self.right_sizes.iter().enumerate() {
+                let len = left.checked_mul(right).expect("block pair fits in usize");
+                for code in binomial_codes(rng, len, self.probability(a, b)) {
+                    edges.push((left_starts[a] + code / right, right_starts[b] + code % right));
+                }
+            }
+        }
+        edges.sort_unstable();
+        BipartiteGraph::from_edges(self.left_len(), self.right_len(), edges)
+            .expect("block edges are in range")
+    }
+}
+
+fn block_of(sizes: &[usize], mut v: usize) -> Option<usize> {
+    for (block, &size) in sizes.iter().enumerate() {
+        if v < size {
+            return Some(block);
+        }
+        v -= size;
+    }
+    None
+}
+
+fn edge_counts(edges: &[(usize, usize)]) -> HashMap<(usize, usize), usize> {
+    let mut counts = HashMap::new();
+    for &edge in edges {
+        *counts.entry(edge).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Replaces edges `(a, b)` and `(c, d)` by `(a, d)` and `(c, b)`, keeping every degree, when
+/// neither new edge exists yet.
+fn switch(
+    edges: &mut [(usize, usize)],
+    counts: &mut HashMap<(usize, usize), usize>,
+    e: usize,
+    f: usize,
+) -> bool {
+    let ((a, b), (c, d)) = (edges[e], edges[f]);
+    if a == c || b == d || counts.contains_key(&(a, d)) || counts.contains_key(&(c, b)) {
+        return false;
+    }
+    for old in [(a, b), (c, d)] {
+        let count = counts.get_mut(&old).expect("edge is counted");
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(&old);
+        }
+    }
+    counts.insert((a, d), 1);
+    counts.insert((c, b), 1);
+    edges[e] = (a, d);
+    edges[f] = (c, b);
+    true
+}
+
+/// Moves one vertex in a degree histogram from bucket `from` to bucket `to`.
+fn bump(histogram: &mut Vec<usize>, from: Option<usize>, to: usize) {
+    if let Some(from) = from {
+        histogram[from] -= 1;
+    }
+    if histogram.len() <= to {
+        histogram.resize(to + 1, 0);
+    }
+    histogram[to] += 1;
+}
+
+/// Increasing codes in `0..len`, each kept with probability `p`, via geometric skips.
+fn binomial_codes(rng: &mut StdRng, len: usize, p: f64) -> impl Iterator<Item = usize> + '_ {
+    let log_q = (1.0 - p).ln();
+    let mut next: usize = 0;
+    std::iter::from_fn(move || {
+        if p <= 0.0 {
+            return None;
+        }
+        if p < 1.0 {
+            let u: f64 = 1.0 - rng.gen::<f64>();
+            let skip = (u.ln() / log_q).floor();
+            next = next.saturating_add(if skip < len as f64 {
+                skip as usize
+            } else {
+                len
+            });
+        }
+        if next >= len {
+            return None;
+        }
+        next += 1;
+        Some(next - 1)
+    })
+}
@@ -0,0 +1,516 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use rand::prelude::*;
+use rand::seq::index::sample;
+
+use crate::csr::{read_csv_edges, read_csv_fields, BIPARTITE_COMMENT};
+use crate::error::{Error, Result};
+use crate::input::open_input;
+
+/// Largest side the edge CSV loaders build from the indices in their input, so one huge index
+/// in an untrusted file is an error rather than an unbounded allocation. A `# bipartite L R`
+/// comment gives the sizes instead and is not capped.
+pub const MAX_INFERRED_VERTICES: usize = 1 << 26;
+
+pub trait Adjacency {
+    fn left_len(&self) -> usize;
+
+    fn right_len(&self) -> usize;
+
+    fn left_neighbours(&self, v: usize) -> impl Iterator<Item = usize> + '_;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Duplicates {
+    #[default]
+    Keep,
+    Merge,
+    Reject,
+}
+
+pub struct BipartiteGraph<T> {
+    pub(crate) left_nodes: Vec<Node<T>>,
+    pub(crate) right_nodes: Vec<Node<T>>,
+}
+
+impl<T> BipartiteGraph<T> {
+    pub fn random(
+        rng: &mut StdRng,
+        l: usize,
+        r: usize,
+        num_edges: usize,
+    ) -> Result<BipartiteGraph<T>>
+    where
+        T: Default,
+    {
+        let max_edges = l.checked_mul(r).ok_or(Error::Overflow)?;
+        if num_edges > max_edges {
+            return Err(Error::TooManyEdges {
+                requested: num_edges,
+                max: max_edges,
+            });
+        }
+
+        let mut left_nodes = Vec::new();
+        for _ in 0..l {
+            left_nodes.push(Node {
+                data: T::default(),
+                neighbours: RefCell::new(Vec::new()),
+            });
+        }
+
+        let mut right_nodes = Vec::new();
+        for _ in 0..r {
+            right_nodes.push(Node {
+                data: T::default(),
+                neighbours: RefCell::new(Vec::new()),
+            });
+        }
+
+        let edges_codes = sample(rng, max_edges, num_edges).into_vec();
+        for code in edges_codes {
+            let i = code / r;
+            let j = code % r;
+            left_nodes[i].neighbours.borrow_mut().push(j);
+            right_nodes[j].neighbours.borrow_mut().push(i);
+        }
+
+        for node in &left_nodes {
+            node.neighbours.borrow_mut().sort();
+        }
+
+        for node in &right_nodes {
+            node.neighbours.borrow_mut().sort();
+        }
+
+        Ok(BipartiteGraph {
+            left_nodes,
+            right_nodes,
+        })
+    }
+
+    pub fn from_edges<I>(l: usize, r: usize, edges: I) -> Result<BipartiteGraph<T>>
+    where
+        T: Default,
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        BipartiteGraph::from_edges_checked(l, r, edges, Duplicates::Keep)
+    }
+
+    pub fn from_edges_checked<I>(
+        l: usize,
+        r: usize,
+        edges: I,
+        duplicates: Duplicates,
+    ) -> Result<BipartiteGraph<T>>
+    where
+        T: Default,
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        let left_data = (0..l).map(|_| T::default()).collect();
+        let right_data = (0..r).map(|_| T::default()).collect();
+        BipartiteGraph::from_edges_with_data_checked(left_data, right_data, edges, duplicates)
+    }
+
+    pub fn from_edges_with_data<I>(
+        left_data: Vec<T>,
+        right_data: Vec<T>,
+        edges: I,
+    ) -> Result<BipartiteGraph<T>>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        BipartiteGraph::from_edges_with_data_checked(left_data, right_data, edges, Duplicates::Keep)
+    }
+
+    pub fn from_edges_with_data_checked<I>(
+        left_data: Vec<T>,
+        right_data: Vec<T>,
+        edges: I,
+        duplicates: Duplicates,
+    ) -> Result<BipartiteGraph<T>>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        let new_node = |data| Node {
+            data,
+            neighbours: RefCell::new(Vec::new()),
+        };
+        let left_nodes: Vec<Node<T>> = left_data.into_iter().map(new_node).collect();
+        let right_nodes: Vec<Node<T>> = right_data.into_iter().map(new_node).collect();
+
+        for (i, j) in edges {
+            if i >= left_nodes.len() {
+                return Err(Error::IndexOutOfRange {
+                    index: i,
+                    len: left_nodes.len(),
+                });
+            }
+            if j >= right_nodes.len() {
+                return Err(Error::IndexOutOfRange {
+                    index: j,
+                    len: right_nodes.len(),
+                });
+            }
+            left_nodes[i].neighbours.borrow_mut().push(j);
+            right_nodes[j].neighbours.borrow_mut().push(i);
+        }
+
+        let graph = BipartiteGraph {
+            left_nodes,
+            right_nodes,
+        };
+        graph.sort_neighbours();
+
+        match duplicates {
+            Duplicates::Keep => {}
+            Duplicates::Merge => {
+                for node in graph.left_nodes.iter().chain(&graph.right_nodes) {
+                    node.neighbours.borrow_mut().dedup();
+                }
+            }
+            Duplicates::Reject => {
+                for (i, node) in graph.left_nodes.iter().enumerate() {
+                    let neighbours = node.neighbours.borrow();
+                    if let Some(pair) = neighbours.windows(2).find(|pair| pair[0] == pair[1]) {
+                        return Err(Error::DuplicateEdge {
+                            left: i,
+                            right: pair[0],
+                        });
+                    }
+                }
+            }
+        }
+        Ok(graph)
+    }
+
+    pub fn load_csv(path: &Path) -> Result<BipartiteGraph<T>>
+    where
+        T: Default,
+    {
+        BipartiteGraph::read_csv(BufReader::new(open_input(path)?))
+    }
+
+    pub fn read_csv<R: BufRead>(reader: R) -> Result<BipartiteGraph<T>>
+    where
+        T: Default,
+    {
+        BipartiteGraph::read_csv_checked(reader, Duplicates::Keep)
+    }
+
+    /// Edges as `left,right` lines. The sizes come from a `# bipartite L R` comment ahead of
+    /// the edges, as [`write_csv`](BipartiteGraph::write_csv) emits, so trailing isolated
+    /// vertices survive; without one they are inferred from the largest indices.
+    pub fn read_csv_checked<R: BufRead>(
+        reader: R,
+        duplicates: Duplicates,
+    ) -> Result<BipartiteGraph<T>>
+    where
+        T: Default,
+    {
+        let mut edges = Vec::new();
+        let declared = read_csv_edges(reader, |i, j| edges.push((i, j)))?;
+        if let Some((l, r)) = declared {
+            return BipartiteGraph::from_edges_checked(l, r, edges, duplicates);
+        }
+
+        let mut sizes = [0, 0];
+        for &(i, j) in &edges {
+            for (len, index) in sizes.iter_mut().zip([i, j]) {
+                *len = (*len).max(index.checked_add(1).ok_or(Error::Overflow)?);
+            }
+        }
+        if let Some(&len) = sizes.iter().find(|&&len| len > MAX_INFERRED_VERTICES) {
+            return Err(Error::IndexOutOfRange {
+                index: len - 1,
+                len: MAX_INFERRED_VERTICES,
+            });
+        }
+        let [l, r] = sizes;
+        BipartiteGraph::from_edges_checked(l, r, edges, duplicates)
+    }
+
+    pub fn save_csv(&self, path: &Path) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        self.write_csv(&mut w)?;
+        w.flush()
+    }
+
+    /// Writes a `# bipartite L R` comment, a `left,right` header and one line per edge, so
+    /// that [`read_csv`](BipartiteGraph::read_csv) recovers the sizes.
+    pub fn write_csv<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(
+            w,
+            "# {BIPARTITE_COMMENT} {} {}",
+            self.left_nodes.len(),
+            self.right_nodes.len()
+        )?;
+        writeln!(w, "left,right")?;
+        for (i, j) in self.edges() {
+            writeln!(w, "{i},{j}")?;
+        }
+        Ok(())
+    }
+
+    pub fn left_data(&self, i: usize) -> Option<&T> {
+        self.left_nodes.get(i).map(|node| &node.data)
+    }
+
+    pub fn right_data(&self, j: usize) -> Option<&T> {
+        self.right_nodes.get(j).map(|node| &node.data)
+    }
+
+    pub fn left_data_mut(&mut self, i: usize) -> Option<&mut T> {
+        self.left_nodes.get_mut(i).map(|node| &mut node.data)
+    }
+
+    pub fn right_data_mut(&mut self, j: usize) -> Option<&mut T> {
+        self.right_nodes.get_mut(j).map(|node| &mut node.data)
+    }
+
+    pub fn map_data<U, F: FnMut(T) -> U>(self, mut f: F) -> BipartiteGraph<U> {
+        BipartiteGraph {
+            left_nodes: self
+                .left_nodes
+                .into_iter()
+                .map(|node| node.map(&mut f))
+                .collect(),
+            right_nodes: self
+                .right_nodes
+                .into_iter()
+                .map(|node| node.map(&mut f))
+                .collect(),
+        }
+    }
+
+    pub fn attach_data<U, L, R>(self, left: L, right: R) -> Result<BipartiteGraph<U>>
+    where
+        L: IntoIterator<Item = U>,
+        R: IntoIterator<Item = U>,
+    {
+        let left: Vec<U> = left.into_iter().collect();
+        let right: Vec<U> = right.into_iter().collect();
+        if left.len() != self.left_nodes.len() || right.len() != self.right_nodes.len() {
+            return Err(Error::InvalidParameter(format!(
+                "expected {} left and {} right labels, found {} and {}",
+                self.left_nodes.len(),
+                self.right_nodes.len(),
+                left.len(),
+                right.len()
+            )));
+        }
+
+        Ok(BipartiteGraph {
+            left_nodes: zip_data(self.left_nodes, left),
+            right_nodes: zip_data(self.right_nodes, right),
+        })
+    }
+
+    pub fn num_edges(&self) -> usize {
+        self.left_nodes
+            .iter()
+            .map(|node| node.neighbours.borrow().len())
+            .sum()
+    }
+
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        let mut edges = Vec::with_capacity(self.num_edges());
+        for (i, node) in self.left_nodes.iter().enumerate() {
+            edges.extend(node.neighbours.borrow().iter().map(|&j| (i, j)));
+        }
+        edges
+    }
+
+    pub fn has_edge(&self, left: usize, right: usize) -> bool {
+        self.left_nodes[left].neighbours.borrow().contains(&right)
+    }
+
+    /// Parallel edges are stored as repeated neighbours. A matching still uses each vertex at
+    /// most once, so copies of an edge are interchangeable: solvers may match any one copy and
+    /// the matching number is that of the simple graph.
+    pub fn multiplicity(&self, left: usize, right: usize) -> usize {
+        self.left_nodes[left]
+            .neighbours
+            .borrow()
+            .iter()
+            .filter(|&&j| j == right)
+            .count()
+    }
+
+    pub fn multiplicities(&self) -> Vec<((usize, usize), usize)> {
+        let mut edges = self.edges();
+        edges.sort_unstable();
+        let mut counts: Vec<((usize, usize), usize)> = Vec::new();
+        for edge in edges {
+            match counts.last_mut() {
+                Some((last, count)) if *last == edge => *count += 1,
+                _ => counts.push((edge, 1)),
+            }
+        }
+        counts
+    }
+
+    pub fn is_simple(&self) -> bool {
+        self.left_nodes.iter().all(|node| {
+            let mut neighbours = node.neighbours.borrow().clone();
+            neighbours.sort_unstable();
+            neighbours.windows(2).all(|pair| pair[0] != pair[1])
+        })
+    }
+
+    /// Collapses parallel edges to one copy; neighbour lists end up sorted.
+    pub fn merge_parallel_edges(&self) {
+        for node in self.left_nodes.iter().chain(&self.right_nodes) {
+            let mut neighbours = node.neighbours.borrow_mut();
+            neighbours.sort_unstable();
+            neighbours.dedup();
+        }
+    }
+
+    /// The same graph with the sides swapped, so that solvers drive from the right.
+    pub fn transpose(&self) -> BipartiteGraph<T>
+    where
+        T: Clone,
+    {
+        let copy = |nodes: &[Node<T>]| {
+            nodes
+                .iter()
+                .map(|node| Node {
+                    data: node.data.clone(),
+                    neighbours: node.neighbours.clone(),
+                })
+                .collect()
+        };
+        BipartiteGraph {
+            left_nodes: copy(&self.right_nodes),
+            right_nodes: copy(&self.left_nodes),
+        }
+    }
+
+    /// The side with fewer vertices, the left on a tie. Kuhn's algorithm runs one search per
+    /// vertex of the side it drives from, so this is usually the faster one to drive.
+    pub fn smaller_side(&self) -> Side {
+        if self.right_nodes.len() < self.left_nodes.len() {
+            Side::Right
+        } else {
+            Side::Left
+        }
+    }
+
+    pub fn sort_neighbours(&self) {
+        for node in self.left_nodes.iter().chain(&self.right_nodes) {
+            node.neighbours.borrow_mut().sort();
+        }
+    }
+
+    /// Randomly permutes the left neighbour lists, which set the scan order of every solver.
+    /// The right lists stay as they are, so lookups that rely on them being sorted still work.
+    pub fn shuffle_neighbours(&self, rng: &mut StdRng) {
+        for node in &self.left_nodes {
+            node.neighbours.borrow_mut().shuffle(rng);
+        }
+    }
+
+    /// Stably sorts every left list by right degree, so ties keep their current order.
+    pub fn sort_neighbours_by_degree(&self) {
+        for node in &self.left_nodes {
+            node.neighbours
+                .borrow_mut()
+                .sort_by_key(|&j| self.right_nodes[j].neighbours.borrow().len());
+        }
+    }
+}
+
+fn zip_data<T, U>(nodes: Vec<Node<T>>, data: Vec<U>) -> Vec<Node<U>> {
+    nodes
+        .into_iter()
+        .zip(data)
+        .map(|(node, data)| node.map(|_| data))
+        .collect()
+}
+
+impl BipartiteGraph<String> {
+    pub fn load_labeled_csv(path: &Path, duplicates: Duplicates) -> Result<BipartiteGraph<String>> {
+        BipartiteGraph::read_labeled_csv(BufReader::new(open_input(path)?), duplicates)
+    }
+
+    /// Edge list of arbitrary string IDs, numbered in order of first appearance on each side
+    /// and kept as node data. A first line reading `left,right` is a header.
+    pub fn read_labeled_csv<R: BufRead>(
+        reader: R,
+        duplicates: Duplicates,
+    ) -> Result<BipartiteGraph<String>> {
+        let mut left = Labels::default();
+        let mut right = Labels::default();
+        let mut edges = Vec::new();
+        read_csv_fields(reader, |line, i, j| {
+            if line == 1 && (i, j) == ("left", "right") {
+                return Ok(());
+            }
+            edges.push((left.index(i), right.index(j)));
+            Ok(())
+        })?;
+
+        BipartiteGraph::from_edges_with_data_checked(left.names, right.names, edges, duplicates)
+    }
+}
+
+#[derive(Default)]
+struct Labels {
+    names: Vec<String>,
+    index: HashMap<String, usize>,
+}
+
+impl Labels {
+    fn index(&mut self, name: &str) -> usize {
+        if let Some(&i) = self.index.get(name) {
+            return i;
+        }
+        self.names.push(name.to_string());
+        self.index.insert(name.to_string(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+}
+
+impl<T> Adjacency for BipartiteGraph<T> {
+    fn left_len(&self) -> usize {
+        self.left_nodes.len()
+    }
+
+    fn right_len(&self) -> usize {
+        self.right_nodes.len()
+    }
+
+    fn left_neighbours(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        let neighbours = &self.left_nodes[v].neighbours;
+        (0..neighbours.borrow().len()).map(move |k| neighbours.borrow()[k])
+    }
+}
+
+pub struct Node<T> {
+    pub(crate) data: T,
+    pub(crate) neighbours: RefCell<Vec<usize>>,
+}
+
+impl<T> Node<T> {
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Node<U> {
+        Node {
+            data: f(self.data),
+            neighbours: self.neighbours,
+        }
+    }
+}
@@ -0,0 +1,352 @@
+use std::collections::VecDeque;
+use std::io::{BufRead, Write};
+
+use crate::error::{Error, Result};
+use crate::graph::BipartiteGraph;
+
+const GRAPH6_HEADER: &str = ">>graph6<<";
+const SPARSE6_HEADER: &str = ">>sparse6<<";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SimpleGraph {
+    n: usize,
+    edges: Vec<(usize, usize)>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bipartition {
+    pub left: Vec<usize>,
+    pub right: Vec<usize>,
+}
+
+impl SimpleGraph {
+    pub fn new<I>(n: usize, edges: I) -> Result<SimpleGraph>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        let mut normalized = Vec::new();
+        for (u, v) in edges {
+            if let Some(index) = [u, v].into_iter().find(|&x| x >= n) {
+                return Err(Error::IndexOutOfRange { index, len: n });
+            }
+            normalized.push((u.min(v), u.max(v)));
+        }
+        Ok(SimpleGraph {
+            n,
+            edges: normalized,
+        })
+    }
+
+    pub fn from_bipartite<T>(graph: &BipartiteGraph<T>) -> SimpleGraph {
+        let l = graph.left_nodes.len();
+        SimpleGraph {
+            n: l + graph.right_nodes.len(),
+            edges: graph.edges().into_iter().map(|(i, j)| (i, l + j)).collect(),
+        }
+    }
+
+    pub fn num_vertices(&self) -> usize {
+        self.n
+    }
+
+    pub fn edges(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+
+    pub fn parse(line: &str) -> Result<SimpleGraph> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        if let Some(rest) = line.strip_prefix(SPARSE6_HEADER) {
+            SimpleGraph::from_sparse6(rest)
+        } else if let Some(rest) = line.strip_prefix(GRAPH6_HEADER) {
+            SimpleGraph::from_graph6(rest)
+        } else if line.starts_with(':') {
+            SimpleGraph::from_sparse6(line)
+        } else {
+            SimpleGraph::from_graph6(line)
+        }
+    }
+
+    pub fn read_all<R: BufRead>(reader: R) -> Result<Vec<SimpleGraph>> {
+        let mut graphs = Vec::new();
+        for (k, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let graph = SimpleGraph::parse(&line).map_err(|err| Error::ParseError {
+                line: k + 1,
+                message: err.to_string(),
+            })?;
+            graphs.push(graph);
+        }
+        Ok(graphs)
+    }
+
+    pub fn from_graph6(text: &str) -> Result<SimpleGraph> {
+        let bytes = decode_bytes(text)?;
+        let (n, rest) = decode_size(&bytes)?;
+
+        let pairs = n * n.saturating_sub(1) / 2;
+        if rest.len() != pairs.div_ceil(6) {
+            return Err(Error::InvalidFormat(format!(
+                "graph6 body has {} bytes, expected {} for {n} vertices",
+                rest.len(),
+                pairs.div_ceil(6)
+            )));
+        }
+
+        let mut bits = BitReader::new(rest);
+        let mut edges = Vec::new();
+        for j in 1..n {
+            for i in 0..j {
+                if bits.read(1) == Some(1) {
+                    edges.push((i, j));
+                }
+            }
+        }
+        Ok(SimpleGraph { n, edges })
+    }
+
+    pub fn to_graph6(&self) -> String {
+        let mut adjacent = vec![false; self.n * self.n];
+        for &(u, v) in &self.edges {
+            adjacent[u * self.n + v] = true;
+            adjacent[v * self.n + u] = true;
+        }
+
+        let mut bits = BitWriter::default();
+        for j in 1..self.n {
+            for i in 0..j {
+                bits.write(adjacent[i * self.n + j] as u64, 1);
+            }
+        }
+        bits.pad(0);
+
+        let mut out = encode_size(self.n);
+        out.extend(bits.bytes);
+        encode_bytes(&out)
+    }
+
+    pub fn from_sparse6(text: &str) -> Result<SimpleGraph> {
+        let text = text
+            .strip_prefix(':')
+            .ok_or_else(|| Error::InvalidFormat("sparse6 must start with `:`".to_string()))?;
+        let bytes = decode_bytes(text)?;
+        let (n, rest) = decode_size(&bytes)?;
+        let k = bits_for(n);
+
+        let mut bits = BitReader::new(rest);
+        let mut edges = Vec::new();
+        let mut v = 0;
+        while let (Some(b), Some(x)) = (bits.read(1), bits.read(k)) {
+            let x = x as usize;
+            if b == 1 {
+                v += 1;
+            }
+            if v >= n {
+                break;
+            }
+            if x > v {
+                v = x;
+            } else {
+                edges.push((x, v));
+            }
+        }
+        Ok(SimpleGraph { n, edges })
+    }
+
+    pub fn to_sparse6(&self) -> String {
+        let k = bits_for(self.n);
+        let mut edges = self.edges.clone();
+        edges.sort_by_key(|&(i, j)| (j, i));
+
+        let mut bits = BitWriter::default();
+        let mut last = 0;
+        for &(i, j) in &edges {
+            if j == last {
+                bits.write(0, 1);
+            } else {
+                bits.write(1, 1);
+                if j > last + 1 {
+                    bits.write(j as u64, k);
+                    bits.write(0, 1);
+                }
+                last = j;
+            }
+            bits.write(i as u64, k);
+        }
+
+        let padding = (6 - bits.len % 6) % 6;
+        if k < 6 && self.n == 1 << k && padding > k && last + 2 == self.n && !edges.is_empty() {
+            bits.write(0, 1);
+        }
+        bits.pad(1);
+
+        let mut out = encode_size(self.n);
+        out.extend(bits.bytes);
+        format!(":{}", encode_bytes(&out))
+    }
+
+    pub fn bipartition(&self) -> Option<Vec<bool>> {
+        let mut adjacency = vec![Vec::new(); self.n];
+        for &(u, v) in &self.edges {
+            adjacency[u].push(v);
+            adjacency[v].push(u);
+        }
+
+        let mut side = vec![None; self.n];
+        let mut queue = VecDeque::new();
+        for start in 0..self.n {
+            if side[start].is_some() {
+                continue;
+            }
+            side[start] = Some(false);
+            queue.push_back(start);
+            while let Some(u) = queue.pop_front() {
+                let here = side[u]?;
+                for &v in &adjacency[u] {
+                    match side[v] {
+                        None => {
+                            side[v] = Some(!here);
+                            queue.push_back(v);
+                        }
+                        Some(there) if there == here => return None,
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+        side.into_iter().collect()
+    }
+
+    pub fn to_bipartite<T: Default>(&self) -> Result<(BipartiteGraph<T>, Bipartition)> {
+        let side = self
+            .bipartition()
+            .ok_or_else(|| Error::InvalidFormat("graph is not bipartite".to_string()))?;
+
+        let mut partition = Bipartition::default();
+        let mut index = vec![0; self.n];
+        for (v, &is_right) in side.iter().enumerate() {
+            let part = if is_right {
+                &mut partition.right
+            } else {
+                &mut partition.left
+            };
+            index[v] = part.len();
+            part.push(v);
+        }
+
+        let edges = self.edges.iter().map(|&(u, v)| {
+            if side[u] {
+                (index[v], index[u])
+            } else {
+                (index[u], index[v])
+            }
+        });
+        let graph = BipartiteGraph::from_edges(partition.left.len(), partition.right.len(), edges)?;
+        Ok((graph, partition))
+    }
+}
+
+impl<T> BipartiteGraph<T> {
+    pub fn write_graph6<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        writeln!(w, "{}", SimpleGraph::from_bipartite(self).to_graph6())
+    }
+
+    pub fn write_sparse6<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        writeln!(w, "{}", SimpleGraph::from_bipartite(self).to_sparse6())
+    }
+}
+
+fn bits_for(n: usize) -> usize {
+    (usize::BITS - n.saturating_sub(1).leading_zeros()) as usize
+}
+
+fn decode_bytes(text: &str) -> Result<Vec<u8>> {
+    text.bytes()
+        .map(|b| match b {
+            63..=126 => Ok(b - 63),
+            _ => Err(Error::InvalidFormat(format!(
+                "byte {b} is outside the graph6 range"
+            ))),
+        })
+        .collect()
+}
+
+fn encode_bytes(values: &[u8]) -> String {
+    values.iter().map(|&v| (v + 63) as char).collect()
+}
+
+fn decode_size(bytes: &[u8]) -> Result<(usize, &[u8])> {
+    let truncated = || Error::InvalidFormat("truncated vertex count".to_string());
+    let (digits, rest) = match bytes {
+        [63, 63, rest @ ..] => (rest.get(..6).ok_or_else(truncated)?, &rest[6..]),
+        [63, rest @ ..] => (rest.get(..3).ok_or_else(truncated)?, &rest[3..]),
+        [n, rest @ ..] => return Ok((*n as usize, rest)),
+        [] => return Err(truncated()),
+    };
+    let n = digits.iter().fold(0, |n, &d| (n << 6) | d as usize);
+    Ok((n, rest))
+}
+
+fn encode_size(n: usize) -> Vec<u8> {
+    let digits = |count: usize| (0..count).rev().map(move |k| ((n >> (6 * k)) & 63) as u8);
+    if n <= 62 {
+        vec![n as u8]
+    } else if n <= 258047 {
+        std::iter::once(63).chain(digits(3)).collect()
+    } else {
+        [63, 63].into_iter().chain(digits(6)).collect()
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> BitReader<'a> {
+        BitReader { bytes, position: 0 }
+    }
+
+    fn read(&mut self, count: usize) -> Option<u64> {
+        if self.position + count > 6 * self.bytes.len() {
+            return None;
+        }
+        let mut value = 0;
+        for _ in 0..count {
+            let byte = self.bytes[self.position / 6];
+            let bit = (byte >> (5 - self.position % 6)) & 1;
+            value = (value << 1) | bit as u64;
+            self.position += 1;
+        }
+        Some(value)
+    }
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u64, count: usize) {
+        for k in (0..count).rev() {
+            if self.len.is_multiple_of(6) {
+                self.bytes.push(0);
+            }
+            let bit = ((value >> k) & 1) as u8;
+            let last = self.bytes.len() - 1;
+            self.bytes[last] |= bit << (5 - self.len % 6);
+            self.len += 1;
+        }
+    }
+
+    fn pad(&mut self, bit: u64) {
+        while !self.len.is_multiple_of(6) {
+            self.write(bit, 1);
+        }
+    }
+}
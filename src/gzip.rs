@@ -0,0 +1,420 @@
+//! In-process gzip (RFC 1952) decoding of the DEFLATE (RFC 1951) stream in each member, so
+//! reading a compressed input needs no external tool. Output is produced one DEFLATE block at
+//! a time and only the last 32 KiB, the largest distance a match may reach back, is kept
+//! between blocks. The CRC-32 and length in every member trailer are checked.
+
+use std::io::{self, BufRead, Read};
+
+const WINDOW: usize = 1 << 15;
+const MAX_BITS: usize = 15;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// The order in which a dynamic block lists the code lengths of the code-length alphabet.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 == 1 {
+                0xedb8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+fn corrupt(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("corrupt gzip data: {message}"))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    /// Expecting a member header; `first` is false once a member has ended, when the end of
+    /// input is a clean finish rather than an empty file.
+    Header { first: bool },
+    Blocks,
+    Done,
+}
+
+/// Decompresses a gzip stream, including several concatenated members, as it is read.
+pub struct GzipDecoder<R> {
+    bits: BitReader<R>,
+    state: State,
+    out: Vec<u8>,
+    pos: usize,
+    /// Start of the output not yet covered by `crc`.
+    checked: usize,
+    crc: u32,
+    size: u32,
+}
+
+impl<R: BufRead> GzipDecoder<R> {
+    pub fn new(reader: R) -> GzipDecoder<R> {
+        GzipDecoder {
+            bits: BitReader::new(reader),
+            state: State::Header { first: true },
+            out: Vec::new(),
+            pos: 0,
+            checked: 0,
+            crc: 0,
+            size: 0,
+        }
+    }
+
+    fn header(&mut self, first: bool) -> io::Result<()> {
+        if !first && self.bits.at_end()? {
+            self.state = State::Done;
+            return Ok(());
+        }
+        let mut fixed = [0; 10];
+        for byte in &mut fixed {
+            *byte = self.bits.byte()?;
+        }
+        if fixed[..2] != [0x1f, 0x8b] {
+            return Err(corrupt("missing member magic"));
+        }
+        if fixed[2] != 8 {
+            return Err(corrupt("compression method is not DEFLATE"));
+        }
+        let flags = fixed[3];
+        if flags & 0xe0 != 0 {
+            return Err(corrupt("reserved header flags are set"));
+        }
+        if flags & 0x04 != 0 {
+            let len = self.bits.bits(16)?;
+            for _ in 0..len {
+                self.bits.byte()?;
+            }
+        }
+        for flag in [0x08, 0x10] {
+            if flags & flag != 0 {
+                while self.bits.byte()? != 0 {}
+            }
+        }
+        if flags & 0x02 != 0 {
+            self.bits.bits(16)?;
+        }
+        self.crc = 0;
+        self.size = 0;
+        self.state = State::Blocks;
+        Ok(())
+    }
+
+    fn block(&mut self) -> io::Result<()> {
+        let last = self.bits.bits(1)? == 1;
+        match self.bits.bits(2)? {
+            0 => self.stored()?,
+            1 => {
+                let mut lengths = [0; 288 + 32];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..288].fill(8);
+                lengths[288..].fill(5);
+                let literals = Huffman::new(&lengths[..288])?;
+                let distances = Huffman::new(&lengths[288..])?;
+                self.codes(&literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = self.dynamic_tables()?;
+                self.codes(&literals, &distances)?;
+            }
+            _ => return Err(corrupt("reserved block type")),
+        }
+        self.update_crc();
+        if last {
+            self.trailer()?;
+        }
+        Ok(())
+    }
+
+    fn stored(&mut self) -> io::Result<()> {
+        self.bits.align();
+        let len = self.bits.bits(16)?;
+        if self.bits.bits(16)? != !len & 0xffff {
+            return Err(corrupt("stored block length check failed"));
+        }
+        for _ in 0..len {
+            let byte = self.bits.byte()?;
+            self.out.push(byte);
+        }
+        Ok(())
+    }
+
+    fn dynamic_tables(&mut self) -> io::Result<(Huffman, Huffman)> {
+        let literals = self.bits.bits(5)? as usize + 257;
+        let distances = self.bits.bits(5)? as usize + 1;
+        let code_lengths = self.bits.bits(4)? as usize + 4;
+        if literals > 286 || distances > 30 {
+            return Err(corrupt("too many codes in a dynamic block"));
+        }
+
+        let mut lengths = [0; 19];
+        for &symbol in &CODE_LENGTH_ORDER[..code_lengths] {
+            lengths[symbol] = self.bits.bits(3)? as u8;
+        }
+        let code_length_code = Huffman::new(&lengths)?;
+
+        let mut lengths = vec![0; literals + distances];
+        let mut k = 0;
+        while k < lengths.len() {
+            let symbol = code_length_code.decode(&mut self.bits)?;
+            let (value, repeat) = match symbol {
+                0..=15 => (symbol as u8, 1),
+                16 => {
+                    let previous = *lengths[..k]
+                        .last()
+                        .ok_or_else(|| corrupt("length repeat with no previous length"))?;
+                    (previous, 3 + self.bits.bits(2)? as usize)
+                }
+                17 => (0, 3 + self.bits.bits(3)? as usize),
+                _ => (0, 11 + self.bits.bits(7)? as usize),
+            };
+            if k + repeat > lengths.len() {
+                return Err(corrupt("code lengths overrun the alphabets"));
+            }
+            lengths[k..k + repeat].fill(value);
+            k += repeat;
+        }
+        if lengths[256] == 0 {
+            return Err(corrupt("no end-of-block code"));
+        }
+        Ok((
+            Huffman::new(&lengths[..literals])?,
+            Huffman::new(&lengths[literals..])?,
+        ))
+    }
+
+    fn codes(&mut self, literals: &Huffman, distances: &Huffman) -> io::Result<()> {
+        loop {
+            let symbol = literals.decode(&mut self.bits)?;
+            if symbol < 256 {
+                self.out.push(symbol as u8);
+                continue;
+            }
+            if symbol == 256 {
+                return Ok(());
+            }
+
+            let code = symbol as usize - 257;
+            if code >= LENGTH_BASE.len() {
+                return Err(corrupt("invalid length code"));
+            }
+            let len = LENGTH_BASE[code] as usize + self.bits.bits(LENGTH_EXTRA[code])? as usize;
+            let code = distances.decode(&mut self.bits)? as usize;
+            if code >= DIST_BASE.len() {
+                return Err(corrupt("invalid distance code"));
+            }
+            let dist = DIST_BASE[code] as usize + self.bits.bits(DIST_EXTRA[code])? as usize;
+            if dist > self.out.len() {
+                return Err(corrupt("distance reaches before the start of the output"));
+            }
+            let start = self.out.len() - dist;
+            for k in 0..len {
+                let byte = self.out[start + k];
+                self.out.push(byte);
+            }
+        }
+    }
+
+    fn update_crc(&mut self) {
+        let mut crc = !self.crc;
+        for &byte in &self.out[self.checked..] {
+            crc = CRC_TABLE[((crc ^ u32::from(byte)) & 0xff) as usize] ^ (crc >> 8);
+        }
+        self.crc = !crc;
+        self.size = self
+            .size
+            .wrapping_add((self.out.len() - self.checked) as u32);
+        self.checked = self.out.len();
+    }
+
+    fn trailer(&mut self) -> io::Result<()> {
+        self.bits.align();
+        let crc = self.bits.bits(16)? | self.bits.bits(16)? << 16;
+        let size = self.bits.bits(16)? | self.bits.bits(16)? << 16;
+        if crc != self.crc {
+            return Err(corrupt("CRC-32 mismatch"));
+        }
+        if size != self.size {
+            return Err(corrupt("length mismatch"));
+        }
+        self.state = State::Header { first: false };
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for GzipDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.out.len() {
+            if self.out.len() > 2 * WINDOW {
+                self.out.drain(..self.out.len() - WINDOW);
+                self.pos = self.out.len();
+                self.checked = self.out.len();
+            }
+            match self.state {
+                State::Header { first } => self.header(first)?,
+                State::Blocks => self.block()?,
+                State::Done => return Ok(0),
+            }
+        }
+        let n = buf.len().min(self.out.len() - self.pos);
+        buf[..n].copy_from_slice(&self.out[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Reads the input least significant bit first, as DEFLATE packs it.
+struct BitReader<R> {
+    inner: R,
+    buffer: u64,
+    count: u8,
+}
+
+impl<R: BufRead> BitReader<R> {
+    fn new(inner: R) -> BitReader<R> {
+        BitReader {
+            inner,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    /// Tops the buffer up to at least 57 bits, or to whatever the input has left.
+    fn refill(&mut self) -> io::Result<()> {
+        while self.count <= 56 {
+            let available = self.inner.fill_buf()?;
+            if available.is_empty() {
+                break;
+            }
+            let take = available.len().min(usize::from((64 - self.count) / 8));
+            for &byte in &available[..take] {
+                self.buffer |= u64::from(byte) << self.count;
+                self.count += 8;
+            }
+            self.inner.consume(take);
+        }
+        Ok(())
+    }
+
+    fn peek(&mut self, n: u8) -> io::Result<u32> {
+        if self.count < n {
+            self.refill()?;
+        }
+        Ok((self.buffer & ((1 << n) - 1)) as u32)
+    }
+
+    fn consume(&mut self, n: u8) -> io::Result<()> {
+        if self.count < n {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        self.buffer >>= n;
+        self.count -= n;
+        Ok(())
+    }
+
+    fn bits(&mut self, n: u8) -> io::Result<u32> {
+        let value = self.peek(n)?;
+        self.consume(n)?;
+        Ok(value)
+    }
+
+    fn byte(&mut self) -> io::Result<u8> {
+        Ok(self.bits(8)? as u8)
+    }
+
+    fn align(&mut self) {
+        let partial = self.count % 8;
+        self.buffer >>= partial;
+        self.count -= partial;
+    }
+
+    fn at_end(&mut self) -> io::Result<bool> {
+        self.align();
+        self.refill()?;
+        Ok(self.count == 0)
+    }
+}
+
+/// A canonical Huffman code decoded by one lookup of its longest code length: entry `k` holds
+/// the symbol and length of the code that `k`'s low bits start with, or 0 if none does.
+struct Huffman {
+    table: Vec<u16>,
+    bits: u8,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> io::Result<Huffman> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &len in lengths {
+            counts[usize::from(len)] += 1;
+        }
+        counts[0] = 0;
+        let bits = (1..=MAX_BITS).rev().find(|&len| counts[len] > 0).unwrap_or(0);
+
+        let mut next = [0u32; MAX_BITS + 1];
+        let mut code = 0;
+        for len in 1..=MAX_BITS {
+            code = (code + u32::from(counts[len - 1])) << 1;
+            next[len] = code;
+        }
+
+        let mut table = vec![0; 1 << bits];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len == 0 {
+                continue;
+            }
+            let len = usize::from(len);
+            let code = next[len];
+            next[len] += 1;
+            if code >= 1 << len {
+                return Err(corrupt("over-subscribed Huffman code"));
+            }
+            let reversed = (code.reverse_bits() >> (32 - len)) as usize;
+            for entry in table.iter_mut().skip(reversed).step_by(1 << len) {
+                *entry = (symbol as u16) << 4 | len as u16;
+            }
+        }
+        Ok(Huffman {
+            table,
+            bits: bits as u8,
+        })
+    }
+
+    fn decode<R: BufRead>(&self, reader: &mut BitReader<R>) -> io::Result<u16> {
+        let entry = self.table[reader.peek(self.bits)? as usize];
+        if entry == 0 {
+            return Err(corrupt("invalid Huffman code"));
+        }
+        reader.consume((entry & 0xf) as u8)?;
+        Ok(entry >> 4)
+    }
+}
@@ -0,0 +1,97 @@
+use crate::error::{Error, Result};
+use crate::graph::{Adjacency, BipartiteGraph};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A left subset `S` with `|N(S)| < |S|`; `subset` is its position in the queried list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HallViolation {
+    pub subset: usize,
+    pub size: usize,
+    pub neighbourhood: usize,
+}
+
+impl HallViolation {
+    pub fn deficiency(&self) -> usize {
+        self.size - self.neighbourhood
+    }
+}
+
+/// Evaluates Hall's condition on each subset of left vertices. Repeated vertices count once.
+/// Two bitsets are reused across subsets and only the touched words are cleared.
+pub fn check_hall<G: Adjacency>(graph: &G, subsets: &[Vec<usize>]) -> Result<Vec<HallViolation>> {
+    let mut members = BitSet::new(graph.left_len());
+    let mut neighbourhood = BitSet::new(graph.right_len());
+    let mut violations = Vec::new();
+
+    for (k, subset) in subsets.iter().enumerate() {
+        let mut size = 0;
+        let mut neighbours = 0;
+        for &v in subset {
+            if v >= graph.left_len() {
+                return Err(Error::IndexOutOfRange {
+                    index: v,
+                    len: graph.left_len(),
+                });
+            }
+            if !members.insert(v) {
+                continue;
+            }
+            size += 1;
+            for to in graph.left_neighbours(v) {
+                if neighbourhood.insert(to) {
+                    neighbours += 1;
+                }
+            }
+        }
+        members.clear();
+        neighbourhood.clear();
+
+        if neighbours < size {
+            violations.push(HallViolation {
+                subset: k,
+                size,
+                neighbourhood: neighbours,
+            });
+        }
+    }
+    Ok(violations)
+}
+
+impl<T> BipartiteGraph<T> {
+    pub fn check_hall(&self, subsets: &[Vec<usize>]) -> Result<Vec<HallViolation>> {
+        check_hall(self, subsets)
+    }
+}
+
+struct BitSet {
+    words: Vec<u64>,
+    touched: Vec<usize>,
+}
+
+impl BitSet {
+    fn new(len: usize) -> BitSet {
+        BitSet {
+            words: vec![0; len.div_ceil(WORD_BITS)],
+            touched: Vec::new(),
+        }
+    }
+
+    fn insert(&mut self, x: usize) -> bool {
+        let (word, bit) = (x / WORD_BITS, 1 << (x % WORD_BITS));
+        if self.words[word] & bit != 0 {
+            return false;
+        }
+        if self.words[word] == 0 {
+            self.touched.push(word);
+        }
+        self.words[word] |= bit;
+        true
+    }
+
+    fn clear(&mut self) {
+        for word in self.touched.drain(..) {
+            self.words[word] = 0;
+        }
+    }
+}
@@ -0,0 +1,380 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use rand::prelude::*;
+
+use crate::assignment::CostMatrix;
+use crate::error::{Error, Result};
+use crate::matching::Matching;
+use crate::weights::EdgeWeights;
+
+type HeapEdge = (i64, Reverse<usize>, Reverse<usize>);
+
+/// A min-cost assignment method: matches `min(rows, cols)` pairs and reports their total cost,
+/// or [`Error::CostOverflow`] when that total does not fit in an `i64`.
+pub trait AssignmentSolver {
+    fn name(&self) -> &'static str;
+
+    fn solve(&self, costs: &CostMatrix) -> Result<(Matching, i64)>;
+}
+
+/// A maximum-weight matching method over sparse edge weights. Only positive weights are
+/// worth matching, so the others are ignored.
+pub trait WeightedMatchingSolver {
+    fn name(&self) -> &'static str;
+
+    fn solve(
+        &self,
+        left_len: usize,
+        right_len: usize,
+        weights: &EdgeWeights,
+    ) -> Result<(Matching, i64)>;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Hungarian;
+
+impl AssignmentSolver for Hungarian {
+    fn name(&self) -> &'static str {
+        "hungarian"
+    }
+
+    fn solve(&self, costs: &CostMatrix) -> Result<(Matching, i64)> {
+        costs.min_cost_assignment()
+    }
+}
+
+/// Exact, on the dense `left_len x right_len` matrix of negated weights.
+impl WeightedMatchingSolver for Hungarian {
+    fn name(&self) -> &'static str {
+        "hungarian"
+    }
+
+    fn solve(
+        &self,
+        left_len: usize,
+        right_len: usize,
+        weights: &EdgeWeights,
+    ) -> Result<(Matching, i64)> {
+        let edges = positive_edges(left_len, right_len, weights)?;
+        let len = left_len.checked_mul(right_len).ok_or(Error::Overflow)?;
+        let mut costs = vec![0; len];
+        for &(w, Reverse(i), Reverse(j)) in &edges {
+            costs[i * right_len + j] = -w;
+        }
+        let (matching, cost) = CostMatrix::new(left_len, right_len, costs)?.min_cost_assignment()?;
+
+        let mut matched_right = vec![None; right_len];
+        for (i, j) in matching.pairs() {
+            if weights.get(i, j).is_some_and(|w| w > 0) {
+                matched_right[j] = Some(i);
+            }
+        }
+        let weight = cost
+            .checked_neg()
+            .ok_or_else(|| Error::CostOverflow("total weight".to_string()))?;
+        Ok((Matching::from_matched_right(left_len, matched_right), weight))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Greedy;
+
+impl AssignmentSolver for Greedy {
+    fn name(&self) -> &'static str {
+        "greedy"
+    }
+
+    fn solve(&self, costs: &CostMatrix) -> Result<(Matching, i64)> {
+        View::new(costs).greedy().finish()
+    }
+}
+
+/// Takes edges from heaviest to lightest while both ends are free, popping a binary heap in
+/// `O(E log E)`. Every edge of an optimum is blocked by a heavier taken edge at one of its
+/// ends, and each taken edge blocks at most two, so the weight is at least half the optimum.
+///
+/// ```
+/// use kuhn::{EdgeWeights, WeightedGreedy, WeightedMatchingSolver};
+///
+/// let mut weights = EdgeWeights::new();
+/// weights.set(0, 0, 5);
+/// weights.set(0, 1, 4);
+/// weights.set(1, 0, 4);
+/// let (matching, weight) = WeightedGreedy.solve(2, 2, &weights).unwrap();
+/// assert_eq!((matching.len(), weight), (1, 5));
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WeightedGreedy;
+
+impl WeightedMatchingSolver for WeightedGreedy {
+    fn name(&self) -> &'static str {
+        "greedy"
+    }
+
+    fn solve(
+        &self,
+        left_len: usize,
+        right_len: usize,
+        weights: &EdgeWeights,
+    ) -> Result<(Matching, i64)> {
+        let mut heap = BinaryHeap::from(positive_edges(left_len, right_len, weights)?);
+        let mut matched_left = vec![None; left_len];
+        let mut matched_right = vec![None; right_len];
+        let mut remaining = left_len.min(right_len);
+        let mut total: i64 = 0;
+        while remaining > 0 {
+            let Some((w, Reverse(i), Reverse(j))) = heap.pop() else {
+                break;
+            };
+            if matched_left[i].is_none() && matched_right[j].is_none() {
+                matched_left[i] = Some(j);
+                matched_right[j] = Some(i);
+                total = total
+                    .checked_add(w)
+                    .ok_or_else(|| Error::CostOverflow("total weight".to_string()))?;
+                remaining -= 1;
+            }
+        }
+        Ok((Matching::from_parts(matched_left, matched_right), total))
+    }
+}
+
+/// Positive-weight edges, ordered so that the heaviest, then lowest-indexed, is greatest.
+fn positive_edges(
+    left_len: usize,
+    right_len: usize,
+    weights: &EdgeWeights,
+) -> Result<Vec<HeapEdge>> {
+    let mut edges = Vec::new();
+    for ((i, j), w) in weights.iter() {
+        if i >= left_len {
+            return Err(Error::IndexOutOfRange {
+                index: i,
+                len: left_len,
+            });
+        }
+        if j >= right_len {
+            return Err(Error::IndexOutOfRange {
+                index: j,
+                len: right_len,
+            });
+        }
+        if w > 0 {
+            edges.push((w, Reverse(i), Reverse(j)));
+        }
+    }
+    Ok(edges)
+}
+
+/// Greedy start improved by 2-swaps and moves to free columns until no move helps.
+#[derive(Clone, Copy, Debug)]
+pub struct LocalSearch {
+    pub max_rounds: usize,
+}
+
+impl Default for LocalSearch {
+    fn default() -> LocalSearch {
+        LocalSearch { max_rounds: 100 }
+    }
+}
+
+impl AssignmentSolver for LocalSearch {
+    fn name(&self) -> &'static str {
+        "local-search"
+    }
+
+    fn solve(&self, costs: &CostMatrix) -> Result<(Matching, i64)> {
+        let mut state = View::new(costs).greedy();
+        for _ in 0..self.max_rounds {
+            if !state.improve() {
+                break;
+            }
+        }
+        state.finish()
+    }
+}
+
+/// Simulated annealing over the same moves as [`LocalSearch`], from the greedy start.
+#[derive(Clone, Copy, Debug)]
+pub struct Annealing {
+    pub seed: u64,
+    pub iterations: usize,
+    pub initial_temperature: f64,
+    pub cooling: f64,
+}
+
+impl Default for Annealing {
+    fn default() -> Annealing {
+        Annealing {
+            seed: 0,
+            iterations: 200_000,
+            initial_temperature: 10.0,
+            cooling: 0.9999,
+        }
+    }
+}
+
+impl AssignmentSolver for Annealing {
+    fn name(&self) -> &'static str {
+        "annealing"
+    }
+
+    fn solve(&self, costs: &CostMatrix) -> Result<(Matching, i64)> {
+        let mut state = View::new(costs).greedy();
+        if state.view.rows == 0 {
+            return state.finish();
+        }
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut best = (state.col_of.clone(), state.cost);
+        let mut temperature = self.initial_temperature;
+        for _ in 0..self.iterations {
+            let a = rng.gen_range(0..state.view.rows);
+            let j = rng.gen_range(0..state.view.cols);
+            let delta = state.move_delta(a, j);
+            if delta <= 0 || rng.gen::<f64>() < (-(delta as f64) / temperature).exp() {
+                state.apply_move(a, j, delta);
+                if state.cost < best.1 {
+                    best = (state.col_of.clone(), state.cost);
+                }
+            }
+            temperature = (temperature * self.cooling).max(f64::MIN_POSITIVE);
+        }
+
+        state.reset(best.0, best.1);
+        state.finish()
+    }
+}
+
+/// The matrix oriented so that rows are the smaller side and every row gets a column.
+#[derive(Clone, Copy)]
+struct View<'a> {
+    costs: &'a CostMatrix,
+    transposed: bool,
+    rows: usize,
+    cols: usize,
+}
+
+impl<'a> View<'a> {
+    fn new(costs: &'a CostMatrix) -> View<'a> {
+        let transposed = costs.rows() > costs.cols();
+        let (rows, cols) = if transposed {
+            (costs.cols(), costs.rows())
+        } else {
+            (costs.rows(), costs.cols())
+        };
+        View {
+            costs,
+            transposed,
+            rows,
+            cols,
+        }
+    }
+
+    fn get(&self, i: usize, j: usize) -> i64 {
+        if self.transposed {
+            self.costs.get(j, i)
+        } else {
+            self.costs.get(i, j)
+        }
+    }
+
+    fn greedy(self) -> State<'a> {
+        let mut cells: Vec<(i64, usize, usize)> = (0..self.rows)
+            .flat_map(|i| (0..self.cols).map(move |j| (self.get(i, j), i, j)))
+            .collect();
+        cells.sort_unstable();
+
+        let mut col_of = vec![usize::MAX; self.rows];
+        let mut row_of = vec![None; self.cols];
+        let mut cost = 0;
+        for (c, i, j) in cells {
+            if col_of[i] == usize::MAX && row_of[j].is_none() {
+                col_of[i] = j;
+                row_of[j] = Some(i);
+                cost += i128::from(c);
+            }
+        }
+        State {
+            view: self,
+            col_of,
+            row_of,
+            cost,
+        }
+    }
+}
+
+struct State<'a> {
+    view: View<'a>,
+    col_of: Vec<usize>,
+    row_of: Vec<Option<usize>>,
+    /// Kept in `i128` so that moves between large costs cannot wrap; checked in `finish`.
+    cost: i128,
+}
+
+impl State<'_> {
+    /// Cost change of giving row `a` column `j`, swapping with `j`'s current row if any.
+    fn move_delta(&self, a: usize, j: usize) -> i128 {
+        let ja = self.col_of[a];
+        if ja == j {
+            return 0;
+        }
+        let cost = |i, j| i128::from(self.view.get(i, j));
+        let own = cost(a, j) - cost(a, ja);
+        match self.row_of[j] {
+            None => own,
+            Some(b) => own + cost(b, ja) - cost(b, j),
+        }
+    }
+
+    fn apply_move(&mut self, a: usize, j: usize, delta: i128) {
+        let ja = self.col_of[a];
+        if ja == j {
+            return;
+        }
+        if let Some(b) = self.row_of[j] {
+            self.col_of[b] = ja;
+        }
+        self.row_of[ja] = self.row_of[j];
+        self.row_of[j] = Some(a);
+        self.col_of[a] = j;
+        self.cost += delta;
+    }
+
+    /// One pass over all moves, taking each improving one; returns whether any was taken.
+    fn improve(&mut self) -> bool {
+        let mut improved = false;
+        for a in 0..self.view.rows {
+            for j in 0..self.view.cols {
+                let delta = self.move_delta(a, j);
+                if delta < 0 {
+                    self.apply_move(a, j, delta);
+                    improved = true;
+                }
+            }
+        }
+        improved
+    }
+
+    fn reset(&mut self, col_of: Vec<usize>, cost: i128) {
+        self.row_of.fill(None);
+        for (i, &j) in col_of.iter().enumerate() {
+            self.row_of[j] = Some(i);
+        }
+        self.col_of = col_of;
+        self.cost = cost;
+    }
+
+    fn finish(self) -> Result<(Matching, i64)> {
+        let costs = self.view.costs;
+        let mut matched_right = vec![None; costs.cols()];
+        for (i, &j) in self.col_of.iter().enumerate() {
+            let (row, col) = if self.view.transposed { (j, i) } else { (i, j) };
+            matched_right[col] = Some(row);
+        }
+        let cost = i64::try_from(self.cost)
+            .map_err(|_| Error::CostOverflow(format!("total cost {}", self.cost)))?;
+        Ok((Matching::from_matched_right(costs.rows(), matched_right), cost))
+    }
+}
@@ -0,0 +1,464 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+
+use crate::graph::Adjacency;
+use crate::kuhn::{Observer, Phase};
+use crate::matching::Matching;
+
+const NONE: usize = usize::MAX;
+
+/// A root and its augmenting path as `(left, new partner)` pairs.
+type RootPath = (usize, Vec<(usize, usize)>);
+
+pub fn hopcroft_karp<G: Adjacency>(graph: &G) -> Matching {
+    hopcroft_karp_observed(graph, &mut ())
+}
+
+pub fn hopcroft_karp_observed<G: Adjacency, O: Observer>(graph: &G, observer: &mut O) -> Matching {
+    sequential(graph, observer, true)
+}
+
+/// [`hopcroft_karp_observed`] as it was before layers were versioned: every phase resets the
+/// whole distance array and walks all left vertices for roots. Kept as the `hopcroft-karp-reset`
+/// bench variant so that sweeps show what versioning saves.
+pub(crate) fn hopcroft_karp_full_reset_observed<G: Adjacency, O: Observer>(
+    graph: &G,
+    observer: &mut O,
+) -> Matching {
+    sequential(graph, observer, false)
+}
+
+/// BFS layers of the left vertices, valid for one phase. Starting a phase bumps `phase`
+/// instead of clearing `dist`, and only the free left vertices, which only ever shrink, are
+/// walked for roots, so a phase costs what it explores rather than `O(V)`.
+struct Layers {
+    dist: Vec<usize>,
+    stamp: Vec<usize>,
+    phase: usize,
+    /// Free left vertices in index order; may still hold some matched since the last phase.
+    free: Vec<usize>,
+    versioned: bool,
+}
+
+impl Layers {
+    fn new(left_len: usize, versioned: bool) -> Layers {
+        Layers {
+            dist: vec![NONE; left_len],
+            stamp: vec![0; left_len],
+            phase: 0,
+            free: (0..left_len).collect(),
+            versioned,
+        }
+    }
+
+    fn get(&self, v: usize) -> usize {
+        if self.stamp[v] == self.phase {
+            self.dist[v]
+        } else {
+            NONE
+        }
+    }
+
+    fn set(&mut self, v: usize, dist: usize) {
+        self.dist[v] = dist;
+        self.stamp[v] = self.phase;
+    }
+
+    /// Starts a phase and returns its roots.
+    fn start_phase(&mut self, match_left: &[Option<usize>]) -> &[usize] {
+        self.phase += 1;
+        if self.versioned {
+            self.free.retain(|&v| match_left[v].is_none());
+        } else {
+            self.dist.fill(NONE);
+            self.stamp.fill(self.phase);
+            self.free.clear();
+            self.free
+                .extend((0..match_left.len()).filter(|&v| match_left[v].is_none()));
+        }
+        &self.free
+    }
+}
+
+fn sequential<G: Adjacency, O: Observer>(graph: &G, observer: &mut O, versioned: bool) -> Matching {
+    observer.phase_started(Phase::Bookkeeping);
+    let mut match_left = vec![None; graph.left_len()];
+    let mut match_right = vec![None; graph.right_len()];
+    let mut layers = Layers::new(graph.left_len(), versioned);
+    let mut roots = Vec::new();
+    let mut stack = Vec::new();
+    observer.phase_finished(Phase::Bookkeeping);
+
+    let mut aborted = false;
+    while !aborted {
+        observer.phase_started(Phase::Bfs);
+        roots.clear();
+        roots.extend_from_slice(layers.start_phase(&match_left));
+        let limit = bfs(graph, &roots, &match_right, &mut layers);
+        observer.phase_finished(Phase::Bfs);
+        let Some(limit) = limit else {
+            break;
+        };
+
+        observer.phase_started(Phase::Dfs);
+        let mut augmented = false;
+        for &root in &roots {
+            if observer.should_abort() {
+                aborted = true;
+                break;
+            }
+
+            observer.search_started(root);
+            stack.push((root, graph.left_neighbours(root)));
+            while let Some((v, neighbours)) = stack.last_mut() {
+                let v = *v;
+                let Some(to) = neighbours.next() else {
+                    layers.set(v, NONE);
+                    stack.pop();
+                    continue;
+                };
+
+                observer.edge_scan(v, to);
+                let here = layers.get(v);
+                match match_right[to] {
+                    None if here == limit => {
+                        let mut right = to;
+                        while let Some((u, _)) = stack.pop() {
+                            let next = match_left[u];
+                            observer.match_flip(u, right);
+                            match_left[u] = Some(right);
+                            match_right[right] = Some(u);
+                            if let Some(next) = next {
+                                right = next;
+                            }
+                        }
+                        augmented = true;
+                        observer.augmented(root, &match_right);
+                    }
+                    Some(u) if here < limit && layers.get(u) == here + 1 => {
+                        stack.push((u, graph.left_neighbours(u)));
+                    }
+                    _ => {}
+                }
+            }
+            observer.search_finished(root);
+        }
+        observer.phase_finished(Phase::Dfs);
+        // A phase reached a free right vertex, so it augments at least once; a phase that
+        // did not was cut short and the next one could only repeat it.
+        if !augmented {
+            break;
+        }
+    }
+
+    Matching::from_parts(match_left, match_right)
+}
+
+/// Layers the graph from `roots`, returning the length of the shortest augmenting paths.
+fn bfs<G: Adjacency>(
+    graph: &G,
+    roots: &[usize],
+    match_right: &[Option<usize>],
+    layers: &mut Layers,
+) -> Option<usize> {
+    let mut queue = VecDeque::with_capacity(roots.len());
+    for &v in roots {
+        layers.set(v, 0);
+        queue.push_back(v);
+    }
+
+    let mut limit = None;
+    while let Some(v) = queue.pop_front() {
+        let here = layers.get(v);
+        // Layers leave the queue in order, so nothing after this can shorten a path.
+        if limit.is_some_and(|limit| here >= limit) {
+            break;
+        }
+
+        for to in graph.left_neighbours(v) {
+            match match_right[to] {
+                None => limit = Some(here),
+                Some(u) if layers.get(u) == NONE => {
+                    layers.set(u, here + 1);
+                    queue.push_back(u);
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    limit
+}
+
+pub fn hopcroft_karp_parallel<G: Adjacency + Sync>(graph: &G, threads: usize) -> Matching {
+    parallel(graph, threads, false)
+}
+
+/// Like [`hopcroft_karp_parallel`], but the matching depends only on the graph, not on the
+/// thread count or scheduling. Each phase runs in rounds: every pending root searches the
+/// layered graph without claiming anything, then the paths found are committed in root order,
+/// skipping any that meets one committed before it; those roots search again next round.
+pub fn hopcroft_karp_parallel_deterministic<G: Adjacency + Sync>(
+    graph: &G,
+    threads: usize,
+) -> Matching {
+    parallel(graph, threads, true)
+}
+
+fn parallel<G: Adjacency + Sync>(graph: &G, threads: usize, deterministic: bool) -> Matching {
+    let threads = threads.max(1);
+    let state = ParallelState {
+        match_left: (0..graph.left_len())
+            .map(|_| AtomicUsize::new(NONE))
+            .collect(),
+        match_right: (0..graph.right_len())
+            .map(|_| AtomicUsize::new(NONE))
+            .collect(),
+        dist: (0..graph.left_len())
+            .map(|_| AtomicUsize::new(NONE))
+            .collect(),
+        claimed: (0..graph.right_len())
+            .map(|_| AtomicBool::new(false))
+            .collect(),
+    };
+
+    let mut phase_threads = threads;
+    while let Some(limit) = state.parallel_bfs(graph, threads) {
+        for claimed in &state.claimed {
+            claimed.store(false, Ordering::Relaxed);
+        }
+
+        if deterministic {
+            state.deterministic_augment(graph, threads, limit);
+            continue;
+        }
+        let augmented = state.parallel_augment(graph, phase_threads, limit);
+        phase_threads = if augmented == 0 { 1 } else { threads };
+    }
+
+    let match_right = state
+        .match_right
+        .iter()
+        .map(|m| Some(m.load(Ordering::Relaxed)).filter(|&u| u != NONE))
+        .collect();
+    Matching::from_matched_right(graph.left_len(), match_right)
+}
+
+struct ParallelState {
+    match_left: Vec<AtomicUsize>,
+    match_right: Vec<AtomicUsize>,
+    dist: Vec<AtomicUsize>,
+    claimed: Vec<AtomicBool>,
+}
+
+impl ParallelState {
+    fn parallel_bfs<G: Adjacency + Sync>(&self, graph: &G, threads: usize) -> Option<usize> {
+        let mut frontier = Vec::new();
+        for v in 0..graph.left_len() {
+            if self.match_left[v].load(Ordering::Relaxed) == NONE {
+                self.dist[v].store(0, Ordering::Relaxed);
+                frontier.push(v);
+            } else {
+                self.dist[v].store(NONE, Ordering::Relaxed);
+            }
+        }
+
+        let mut level = 0;
+        while !frontier.is_empty() {
+            let chunk = frontier.len().div_ceil(threads);
+            let found = AtomicBool::new(false);
+            let next: Vec<Vec<usize>> = thread::scope(|s| {
+                let workers: Vec<_> = frontier
+                    .chunks(chunk)
+                    .map(|part| {
+                        let found = &found;
+                        s.spawn(move || {
+                            let mut next = Vec::new();
+                            for &v in part {
+                                for to in graph.left_neighbours(v) {
+                                    let u = self.match_right[to].load(Ordering::Relaxed);
+                                    if u == NONE {
+                                        found.store(true, Ordering::Relaxed);
+                                    } else if self.dist[u]
+                                        .compare_exchange(
+                                            NONE,
+                                            level + 1,
+                                            Ordering::Relaxed,
+                                            Ordering::Relaxed,
+                                        )
+                                        .is_ok()
+                                    {
+                                        next.push(u);
+                                    }
+                                }
+                            }
+                            next
+                        })
+                    })
+                    .collect();
+                workers.into_iter().map(|w| w.join().unwrap()).collect()
+            });
+
+            if found.load(Ordering::Relaxed) {
+                return Some(level);
+            }
+            frontier = next.concat();
+            level += 1;
+        }
+
+        None
+    }
+
+    fn parallel_augment<G: Adjacency + Sync>(
+        &self,
+        graph: &G,
+        threads: usize,
+        limit: usize,
+    ) -> usize {
+        let roots: Vec<usize> = (0..graph.left_len())
+            .filter(|&v| self.match_left[v].load(Ordering::Relaxed) == NONE)
+            .collect();
+        let next_root = AtomicUsize::new(0);
+        let augmented = AtomicUsize::new(0);
+
+        thread::scope(|s| {
+            for _ in 0..threads {
+                s.spawn(|| loop {
+                    let index = next_root.fetch_add(1, Ordering::Relaxed);
+                    let Some(&root) = roots.get(index) else {
+                        break;
+                    };
+                    if self.augment_from(graph, root, limit) {
+                        augmented.fetch_add(1, Ordering::Relaxed);
+                    }
+                });
+            }
+        });
+
+        augmented.into_inner()
+    }
+
+    fn deterministic_augment<G: Adjacency + Sync>(&self, graph: &G, threads: usize, limit: usize) {
+        let mut pending: Vec<usize> = (0..graph.left_len())
+            .filter(|&v| self.match_left[v].load(Ordering::Relaxed) == NONE)
+            .collect();
+
+        while !pending.is_empty() {
+            let chunk = pending.len().div_ceil(threads);
+            let found: Vec<Vec<RootPath>> = thread::scope(|s| {
+                let workers: Vec<_> = pending
+                    .chunks(chunk)
+                    .map(|part| {
+                        s.spawn(move || {
+                            part.iter()
+                                .filter_map(|&root| {
+                                    self.layered_path(graph, root, limit).map(|path| (root, path))
+                                })
+                                .collect()
+                        })
+                    })
+                    .collect();
+                workers.into_iter().map(|w| w.join().unwrap()).collect()
+            });
+
+            // Chunks are in root order, so this commits by increasing root.
+            pending.clear();
+            for (root, path) in found.into_iter().flatten() {
+                if path
+                    .iter()
+                    .any(|&(_, right)| self.claimed[right].load(Ordering::Relaxed))
+                {
+                    pending.push(root);
+                    continue;
+                }
+                for (left, right) in path {
+                    self.claimed[right].store(true, Ordering::Relaxed);
+                    self.match_left[left].store(right, Ordering::Relaxed);
+                    self.match_right[right].store(left, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// The first shortest augmenting path from `root` in search order that avoids claimed
+    /// right vertices, as `(left, new partner)` pairs; claims nothing. Vertices with no path
+    /// are pruned for the rest of the phase, which cannot change what another search finds
+    /// since claims between rounds only remove paths.
+    fn layered_path<G: Adjacency>(
+        &self,
+        graph: &G,
+        root: usize,
+        limit: usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        let mut stack = vec![(root, graph.left_neighbours(root))];
+        let mut via = Vec::new();
+        while let Some((v, neighbours)) = stack.last_mut() {
+            let v = *v;
+            let Some(to) = neighbours.next() else {
+                self.dist[v].store(NONE, Ordering::Relaxed);
+                stack.pop();
+                via.pop();
+                continue;
+            };
+            if self.claimed[to].load(Ordering::Relaxed) {
+                continue;
+            }
+
+            let level = self.dist[v].load(Ordering::Relaxed);
+            let u = self.match_right[to].load(Ordering::Relaxed);
+            if u == NONE {
+                if level == limit {
+                    via.push(to);
+                    return Some(stack.iter().map(|&(w, _)| w).zip(via).collect());
+                }
+            } else if level < limit && self.dist[u].load(Ordering::Relaxed) == level + 1 {
+                via.push(to);
+                stack.push((u, graph.left_neighbours(u)));
+            }
+        }
+        None
+    }
+
+    fn augment_from<G: Adjacency>(&self, graph: &G, root: usize, limit: usize) -> bool {
+        let mut stack = vec![(root, graph.left_neighbours(root))];
+        while let Some((v, neighbours)) = stack.last_mut() {
+            let v = *v;
+            let Some(to) = neighbours.next() else {
+                self.dist[v].store(NONE, Ordering::Relaxed);
+                stack.pop();
+                continue;
+            };
+
+            let level = self.dist[v].load(Ordering::Relaxed);
+            let usable = |u: usize| {
+                if u == NONE {
+                    level == limit
+                } else {
+                    level < limit && self.dist[u].load(Ordering::Relaxed) == level + 1
+                }
+            };
+            if !usable(self.match_right[to].load(Ordering::Relaxed))
+                || self.claimed[to].swap(true, Ordering::Relaxed)
+            {
+                continue;
+            }
+
+            let u = self.match_right[to].load(Ordering::Relaxed);
+            if u == NONE {
+                let mut right = to;
+                while let Some((w, _)) = stack.pop() {
+                    let next = self.match_left[w].load(Ordering::Relaxed);
+                    self.match_left[w].store(right, Ordering::Relaxed);
+                    self.match_right[right].store(w, Ordering::Relaxed);
+                    right = next;
+                }
+                return true;
+            } else if usable(u) {
+                stack.push((u, graph.left_neighbours(u)));
+            }
+        }
+
+        false
+    }
+}
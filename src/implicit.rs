@@ -0,0 +1,161 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use crate::graph::Adjacency;
+use crate::kuhn::maximum_matching;
+use crate::matching::Matching;
+
+/// A bipartite graph given by a neighbour callback, never materialized. Every solver generic
+/// over [`Adjacency`] runs on it and calls `neighbours(v)` each time it scans `v`, consuming
+/// the iterator lazily.
+pub struct ImplicitGraph<F, I> {
+    left_len: usize,
+    right_len: usize,
+    neighbours: F,
+    iterator: PhantomData<fn(usize) -> I>,
+}
+
+impl<F, I> ImplicitGraph<F, I>
+where
+    F: Fn(usize) -> I,
+    I: Iterator<Item = usize>,
+{
+    /// `neighbours(v)` must only yield right vertices below `right_len`.
+    pub fn new(left_len: usize, right_len: usize, neighbours: F) -> ImplicitGraph<F, I> {
+        ImplicitGraph {
+            left_len,
+            right_len,
+            neighbours,
+            iterator: PhantomData,
+        }
+    }
+}
+
+impl<F, I> Adjacency for ImplicitGraph<F, I>
+where
+    F: Fn(usize) -> I,
+    I: Iterator<Item = usize>,
+{
+    fn left_len(&self) -> usize {
+        self.left_len
+    }
+
+    fn right_len(&self) -> usize {
+        self.right_len
+    }
+
+    fn left_neighbours(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        (self.neighbours)(v)
+    }
+}
+
+/// Compatibility given by a predicate, tested against every right vertex on each scan.
+pub struct PredicateGraph<P> {
+    left_len: usize,
+    right_len: usize,
+    compatible: P,
+}
+
+impl<P: Fn(usize, usize) -> bool> PredicateGraph<P> {
+    pub fn new(left_len: usize, right_len: usize, compatible: P) -> PredicateGraph<P> {
+        PredicateGraph {
+            left_len,
+            right_len,
+            compatible,
+        }
+    }
+}
+
+impl<P: Fn(usize, usize) -> bool> Adjacency for PredicateGraph<P> {
+    fn left_len(&self) -> usize {
+        self.left_len
+    }
+
+    fn right_len(&self) -> usize {
+        self.right_len
+    }
+
+    fn left_neighbours(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.right_len).filter(move |&j| (self.compatible)(v, j))
+    }
+}
+
+/// Like [`PredicateGraph`], but each pair is tested at most once and the outcome remembered,
+/// for checks expensive enough that repeated scans of the same vertex would dominate. Pairs
+/// are only tested when a search reaches them, and the memo is a hash map, so memory grows
+/// with the pairs explored rather than with `left_len * right_len`.
+pub struct MemoizedPredicateGraph<P> {
+    left_len: usize,
+    right_len: usize,
+    compatible: P,
+    memo: RefCell<HashMap<(usize, usize), bool>>,
+}
+
+impl<P: Fn(usize, usize) -> bool> MemoizedPredicateGraph<P> {
+    pub fn new(left_len: usize, right_len: usize, compatible: P) -> MemoizedPredicateGraph<P> {
+        MemoizedPredicateGraph {
+            left_len,
+            right_len,
+            compatible,
+            memo: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_compatible(&self, left: usize, right: usize) -> bool {
+        if let Some(&known) = self.memo.borrow().get(&(left, right)) {
+            return known;
+        }
+        // Not borrowed while the predicate runs, so it may itself query the graph.
+        let outcome = (self.compatible)(left, right);
+        self.memo.borrow_mut().insert((left, right), outcome);
+        outcome
+    }
+
+    /// The remembered outcome, without evaluating the predicate.
+    pub fn known(&self, left: usize, right: usize) -> Option<bool> {
+        self.memo.borrow().get(&(left, right)).copied()
+    }
+
+    /// How many times the predicate has run, one per distinct pair.
+    pub fn evaluations(&self) -> usize {
+        self.memo.borrow().len()
+    }
+
+    pub fn clear_memo(&self) {
+        self.memo.borrow_mut().clear();
+    }
+}
+
+impl<P: Fn(usize, usize) -> bool> Adjacency for MemoizedPredicateGraph<P> {
+    fn left_len(&self) -> usize {
+        self.left_len
+    }
+
+    fn right_len(&self) -> usize {
+        self.right_len
+    }
+
+    fn left_neighbours(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.right_len).filter(move |&j| self.is_compatible(v, j))
+    }
+}
+
+/// Kuhn's algorithm on the graph with left vertex `v` adjacent to `neighbours(v)`.
+pub fn kuhn_implicit<F, I>(left_len: usize, right_len: usize, neighbours: F) -> Matching
+where
+    F: Fn(usize) -> I,
+    I: Iterator<Item = usize>,
+{
+    maximum_matching(&ImplicitGraph::new(left_len, right_len, neighbours))
+}
+
+/// Kuhn's algorithm with left `i` and right `j` adjacent when `compatible(i, j)`, testing each
+/// pair at most once and only when a search reaches it. See [`MemoizedPredicateGraph`].
+pub fn kuhn_memoized<P: Fn(usize, usize) -> bool>(
+    left_len: usize,
+    right_len: usize,
+    compatible: P,
+) -> Matching {
+    maximum_matching(&MemoizedPredicateGraph::new(left_len, right_len, compatible))
+}
@@ -0,0 +1,27 @@
+pub trait Index: Copy + Eq + Send + Sync + 'static {
+    const NONE: Self;
+
+    fn from_usize(value: usize) -> Option<Self>;
+
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! impl_index {
+    ($($ty:ty),*) => {
+        $(
+            impl Index for $ty {
+                const NONE: $ty = <$ty>::MAX;
+
+                fn from_usize(value: usize) -> Option<$ty> {
+                    <$ty>::try_from(value).ok().filter(|&v| v != <$ty>::MAX)
+                }
+
+                fn to_usize(self) -> usize {
+                    self as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_index!(u32, u64, usize);
@@ -0,0 +1,124 @@
+//! Input files that may be gzip or zstd compressed. Compression is recognised from the magic
+//! bytes, not the file name. With the `compression` feature both formats are decoded in
+//! process by the crate's own decoders, so no external tool is needed. Without the feature,
+//! opening a compressed file fails with a hint to enable it.
+
+use std::ffi::OsStr;
+use std::fs::File;
+#[cfg(feature = "compression")]
+use std::io::BufReader;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+#[cfg(feature = "compression")]
+use crate::gzip::GzipDecoder;
+#[cfg(feature = "compression")]
+use crate::zstd::ZstdDecoder;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub fn detect(header: &[u8]) -> Compression {
+        if header.starts_with(&GZIP_MAGIC) {
+            Compression::Gzip
+        } else if header.starts_with(&ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Compression::None => "none",
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        }
+    }
+}
+
+/// A readable input file, decompressed on the fly when needed.
+pub struct Input {
+    inner: Inner,
+}
+
+enum Inner {
+    Plain(File),
+    #[cfg(feature = "compression")]
+    Gzip(Box<GzipDecoder<BufReader<File>>>),
+    #[cfg(feature = "compression")]
+    Zstd(Box<ZstdDecoder<BufReader<File>>>),
+}
+
+/// Opens `path` for reading, decompressing gzip and zstd files.
+pub fn open_input(path: &Path) -> io::Result<Input> {
+    let mut file = File::open(path)?;
+    let mut header = [0; 4];
+    let mut read = 0;
+    while read < header.len() {
+        match file.read(&mut header[read..])? {
+            0 => break,
+            n => read += n,
+        }
+    }
+    file.seek(SeekFrom::Start(0))?;
+
+    match Compression::detect(&header[..read]) {
+        Compression::None => Ok(Input {
+            inner: Inner::Plain(file),
+        }),
+        compression => decompress(path, file, compression),
+    }
+}
+
+#[cfg(feature = "compression")]
+fn decompress(_path: &Path, file: File, compression: Compression) -> io::Result<Input> {
+    let reader = BufReader::new(file);
+    let inner = match compression {
+        Compression::Gzip => Inner::Gzip(Box::new(GzipDecoder::new(reader))),
+        Compression::Zstd => Inner::Zstd(Box::new(ZstdDecoder::new(reader))),
+        Compression::None => Inner::Plain(reader.into_inner()),
+    };
+    Ok(Input { inner })
+}
+
+#[cfg(not(feature = "compression"))]
+fn decompress(path: &Path, _file: File, compression: Compression) -> io::Result<Input> {
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "`{}` is {}-compressed; enable the `compression` feature to read it",
+            path.display(),
+            compression.name()
+        ),
+    ))
+}
+
+impl Read for Input {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.inner {
+            Inner::Plain(file) => file.read(buf),
+            #[cfg(feature = "compression")]
+            Inner::Gzip(decoder) => decoder.read(buf),
+            #[cfg(feature = "compression")]
+            Inner::Zstd(decoder) => decoder.read(buf),
+        }
+    }
+}
+
+/// The extension that names the format, looking past a trailing `.gz` or `.zst`:
+/// `npy` for both `costs.npy` and `costs.npy.gz`.
+pub fn input_extension(path: &Path) -> Option<&OsStr> {
+    match path.extension() {
+        Some(ext) if ext == "gz" || ext == "zst" => Path::new(path.file_stem()?).extension(),
+        ext => ext,
+    }
+}
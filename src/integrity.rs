@@ -0,0 +1,309 @@
+use std::fmt;
+
+use crate::graph::{BipartiteGraph, Side};
+
+/// A structural defect found by [`BipartiteGraph::validate`]. Vertices are indices on `side`
+/// and neighbours indices on the other side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntegrityIssue {
+    OutOfRange {
+        side: Side,
+        vertex: usize,
+        neighbour: usize,
+        len: usize,
+    },
+    /// The edge is listed a different number of times by its two endpoints.
+    Asymmetric {
+        left: usize,
+        right: usize,
+        in_left: usize,
+        in_right: usize,
+    },
+    Duplicate {
+        side: Side,
+        vertex: usize,
+        neighbour: usize,
+        count: usize,
+    },
+}
+
+impl fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = |side: Side| match side {
+            Side::Left => ("left", "right"),
+            Side::Right => ("right", "left"),
+        };
+        match *self {
+            IntegrityIssue::OutOfRange {
+                side,
+                vertex,
+                neighbour,
+                len,
+            } => {
+                let (this, other) = names(side);
+                write!(
+                    f,
+                    "{this} {vertex} lists {other} {neighbour}, out of range for {len} vertices"
+                )
+            }
+            IntegrityIssue::Asymmetric {
+                left,
+                right,
+                in_left,
+                in_right,
+            } => write!(
+                f,
+                "edge ({left}, {right}) appears {in_left} times on the left and {in_right} on the right"
+            ),
+            IntegrityIssue::Duplicate {
+                side,
+                vertex,
+                neighbour,
+                count,
+            } => {
+                let (this, other) = names(side);
+                write!(f, "{this} {vertex} lists {other} {neighbour} {count} times")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    pub issues: Vec<IntegrityIssue>,
+    /// Vertices whose neighbour list is not in increasing order. Lists are only sorted after
+    /// [`BipartiteGraph::sort_neighbours`] and friends, so this is not an issue by itself.
+    pub unsorted: Vec<(Side, usize)>,
+}
+
+impl IntegrityReport {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Whether every neighbour list on both sides is sorted, as a caller may claim.
+    pub fn is_sorted(&self) -> bool {
+        self.unsorted.is_empty()
+    }
+}
+
+impl fmt::Display for IntegrityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            write!(f, "no integrity issues")?;
+        } else {
+            write!(f, "{} integrity issues", self.issues.len())?;
+        }
+        if !self.is_sorted() {
+            write!(f, ", {} unsorted neighbour lists", self.unsorted.len())?;
+        }
+        for issue in &self.issues {
+            write!(f, "\n  {issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> BipartiteGraph<T> {
+    /// Checks that both sides' lists stay in range and describe the same edge multiset, and
+    /// reports parallel edges and unsorted lists. Costs `O(E log E)`.
+    pub fn validate(&self) -> IntegrityReport {
+        let mut report = IntegrityReport::default();
+        let (l, r) = (self.left_nodes.len(), self.right_nodes.len());
+        let mut listed = [Vec::new(), Vec::new()];
+
+        for (side, nodes, other_len) in [
+            (Side::Left, &self.left_nodes, r),
+            (Side::Right, &self.right_nodes, l),
+        ] {
+            for (vertex, node) in nodes.iter().enumerate() {
+                let neighbours = node.neighbours.borrow();
+                if neighbours.windows(2).any(|pair| pair[0] > pair[1]) {
+                    report.unsorted.push((side, vertex));
+                }
+
+                let mut sorted = neighbours.clone();
+                sorted.sort_unstable();
+                for run in sorted.chunk_by(|a, b| a == b) {
+                    let neighbour = run[0];
+                    if neighbour >= other_len {
+                        report.issues.push(IntegrityIssue::OutOfRange {
+                            side,
+                            vertex,
+                            neighbour,
+                            len: other_len,
+                        });
+                        continue;
+                    }
+                    if run.len() > 1 {
+                        report.issues.push(IntegrityIssue::Duplicate {
+                            side,
+                            vertex,
+                            neighbour,
+                            count: run.len(),
+                        });
+                    }
+                    let edge = match side {
+                        Side::Left => (vertex, neighbour),
+                        Side::Right => (neighbour, vertex),
+                    };
+                    listed[side as usize].push((edge, run.len()));
+                }
+            }
+        }
+
+        let [mut from_left, mut from_right] = listed;
+        from_left.sort_unstable();
+        from_right.sort_unstable();
+        let (mut a, mut b) = (0, 0);
+        while a < from_left.len() || b < from_right.len() {
+            let left = from_left.get(a).copied();
+            let right = from_right.get(b).copied();
+            let ((i, j), in_left, in_right) = match (left, right) {
+                (Some((e, x)), Some((f, y))) if e == f => {
+                    a += 1;
+                    b += 1;
+                    (e, x, y)
+                }
+                (Some((e, x)), Some((f, _))) if e < f => {
+                    a += 1;
+                    (e, x, 0)
+                }
+                (Some((e, x)), None) => {
+                    a += 1;
+                    (e, x, 0)
+                }
+                (_, Some((f, y))) => {
+                    b += 1;
+                    (f, 0, y)
+                }
+                (None, None) => unreachable!(),
+            };
+            if in_left != in_right {
+                report.issues.push(IntegrityIssue::Asymmetric {
+                    left: i,
+                    right: j,
+                    in_left,
+                    in_right,
+                });
+            }
+        }
+        report
+    }
+}
+
+/// What [`BipartiteGraph::sanitize`] found and changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SanitizeReport {
+    /// Everything [`BipartiteGraph::validate`] reported before the repair.
+    pub issues: Vec<IntegrityIssue>,
+    /// Extra copies of edges that were kept once.
+    pub removed_duplicates: usize,
+    /// List entries naming a vertex that does not exist.
+    pub removed_out_of_range: usize,
+    /// Edges listed by only one endpoint.
+    pub removed_one_sided: usize,
+    pub sorted_lists: usize,
+}
+
+impl SanitizeReport {
+    /// Whether the graph was already simple, consistent and sorted, so nothing changed.
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty() && self.sorted_lists == 0
+    }
+}
+
+impl fmt::Display for SanitizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return write!(f, "nothing to sanitize");
+        }
+        write!(
+            f,
+            "removed {} duplicate copies, {} one-sided edges and {} out-of-range entries; sorted {} neighbour lists",
+            self.removed_duplicates,
+            self.removed_one_sided,
+            self.removed_out_of_range,
+            self.sorted_lists
+        )
+    }
+}
+
+impl<T> BipartiteGraph<T> {
+    /// Repairs what [`validate`](BipartiteGraph::validate) reports: drops out-of-range entries
+    /// and edges that only one endpoint lists, keeps one copy of each parallel edge, and
+    /// leaves every list sorted. Degrees and shuffles then see each edge exactly once.
+    pub fn sanitize(&self) -> SanitizeReport {
+        let before = self.validate();
+        let (l, r) = (self.left_nodes.len(), self.right_nodes.len());
+        let mut report = SanitizeReport {
+            sorted_lists: before.unsorted.len(),
+            issues: before.issues,
+            ..SanitizeReport::default()
+        };
+
+        let mut listed = [Vec::new(), Vec::new()];
+        for (side, nodes, other_len) in [
+            (Side::Left, &self.left_nodes, r),
+            (Side::Right, &self.right_nodes, l),
+        ] {
+            for (vertex, node) in nodes.iter().enumerate() {
+                for &neighbour in &*node.neighbours.borrow() {
+                    if neighbour >= other_len {
+                        report.removed_out_of_range += 1;
+                        continue;
+                    }
+                    listed[side as usize].push(match side {
+                        Side::Left => (vertex, neighbour),
+                        Side::Right => (neighbour, vertex),
+                    });
+                }
+            }
+        }
+        let [mut from_left, mut from_right] = listed;
+        from_left.sort_unstable();
+        from_right.sort_unstable();
+
+        let counted = |edges: &[(usize, usize)]| -> Vec<((usize, usize), usize)> {
+            edges
+                .chunk_by(|a, b| a == b)
+                .map(|run| (run[0], run.len()))
+                .collect()
+        };
+        let (from_left, from_right) = (counted(&from_left), counted(&from_right));
+        let mut kept = Vec::with_capacity(from_left.len().min(from_right.len()));
+        let (mut a, mut b) = (0, 0);
+        while a < from_left.len() || b < from_right.len() {
+            match (from_left.get(a), from_right.get(b)) {
+                (Some(&(e, x)), Some(&(f, y))) if e == f => {
+                    kept.push(e);
+                    report.removed_duplicates += x.max(y) - 1;
+                    a += 1;
+                    b += 1;
+                }
+                (Some(&(e, _)), Some(&(f, _))) if e < f => {
+                    report.removed_one_sided += 1;
+                    a += 1;
+                }
+                (Some(_), None) => {
+                    report.removed_one_sided += 1;
+                    a += 1;
+                }
+                _ => {
+                    report.removed_one_sided += 1;
+                    b += 1;
+                }
+            }
+        }
+
+        for node in self.left_nodes.iter().chain(&self.right_nodes) {
+            node.neighbours.borrow_mut().clear();
+        }
+        // `kept` is sorted by left then right vertex, so both sides' lists come out sorted.
+        for &(i, j) in &kept {
+            self.left_nodes[i].neighbours.borrow_mut().push(j);
+            self.right_nodes[j].neighbours.borrow_mut().push(i);
+        }
+        report
+    }
+}
@@ -0,0 +1,117 @@
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::graph::BipartiteGraph;
+use crate::hopcroft_karp::hopcroft_karp;
+use crate::input::open_input;
+use crate::matching::Matching;
+
+/// A closed time range `[start, end]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeWindow {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl TimeWindow {
+    pub fn new(start: i64, end: i64) -> Result<TimeWindow> {
+        if end < start {
+            return Err(Error::InvalidParameter(format!(
+                "window [{start}, {end}] ends before it starts"
+            )));
+        }
+        Ok(TimeWindow { start, end })
+    }
+
+    /// Whether `other` lies entirely within this window.
+    pub fn contains(&self, other: &TimeWindow) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn load(path: &Path) -> Result<Vec<TimeWindow>> {
+        TimeWindow::read(BufReader::new(open_input(path)?))
+    }
+
+    /// Reads one `start,end` window per line. A header on the first line and `#` comments
+    /// are skipped.
+    pub fn read<R: BufRead>(reader: R) -> Result<Vec<TimeWindow>> {
+        let mut windows = Vec::new();
+        for (k, line) in reader.lines().enumerate() {
+            let line = line?;
+            let text = line.trim();
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+            let invalid = |message: String| Error::ParseError {
+                line: k + 1,
+                message,
+            };
+
+            let mut fields = text.split(',').map(str::trim);
+            let (Some(start), Some(end), None) = (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(invalid(format!("expected `start,end`, got `{text}`")));
+            };
+            let (Ok(start), Ok(end)) = (start.parse(), end.parse()) else {
+                if k == 0 {
+                    continue;
+                }
+                return Err(invalid(format!("invalid window `{text}`")));
+            };
+            windows.push(TimeWindow::new(start, end).map_err(|err| invalid(err.to_string()))?);
+        }
+        Ok(windows)
+    }
+}
+
+/// Tasks assigned to slots by [`schedule_intervals`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Schedule {
+    /// Left vertices are tasks and right vertices slots.
+    pub matching: Matching,
+    /// Tasks no slot could be found for, in index order.
+    pub unscheduled: Vec<usize>,
+}
+
+impl Schedule {
+    pub fn slot_of(&self, task: usize) -> Option<usize> {
+        self.matching.match_of_left(task)
+    }
+
+    pub fn task_of(&self, slot: usize) -> Option<usize> {
+        self.matching.match_of_right(slot)
+    }
+}
+
+/// The compatibility graph of [`schedule_intervals`], with each vertex carrying its window:
+/// task `i` is adjacent to slot `j` when the slot fits inside the task's window.
+pub fn interval_graph(tasks: &[TimeWindow], slots: &[TimeWindow]) -> BipartiteGraph<TimeWindow> {
+    let mut by_start: Vec<usize> = (0..slots.len()).collect();
+    by_start.sort_by_key(|&j| (slots[j].start, j));
+
+    let mut edges = Vec::new();
+    for (i, task) in tasks.iter().enumerate() {
+        let first = by_start.partition_point(|&j| slots[j].start < task.start);
+        let fitting = by_start[first..]
+            .iter()
+            .take_while(|&&j| slots[j].start <= task.end)
+            .filter(|&&j| task.contains(&slots[j]));
+        edges.extend(fitting.map(|&j| (i, j)));
+    }
+    BipartiteGraph::from_edges_with_data(tasks.to_vec(), slots.to_vec(), edges)
+        .expect("edges join listed tasks and slots")
+}
+
+/// Gives each task its own slot inside the task's window, scheduling as many tasks as
+/// possible, and lists the ones left over.
+pub fn schedule_intervals(tasks: &[TimeWindow], slots: &[TimeWindow]) -> Schedule {
+    let matching = hopcroft_karp(&interval_graph(tasks, slots));
+    let unscheduled = (0..tasks.len())
+        .filter(|&i| matching.match_of_left(i).is_none())
+        .collect();
+    Schedule {
+        matching,
+        unscheduled,
+    }
+}
@@ -0,0 +1,1155 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rand::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::arrival::ArrivalOrder;
+use crate::error::{Error, Result};
+use crate::graph::{Adjacency, BipartiteGraph, Side};
+use crate::hopcroft_karp::hopcroft_karp_observed;
+use crate::index::Index;
+use crate::matching::Matching;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Bfs,
+    Dfs,
+    Bookkeeping,
+}
+
+pub trait Observer {
+    fn edge_scan(&mut self, _left: usize, _right: usize) {}
+
+    fn match_flip(&mut self, _left: usize, _right: usize) {}
+
+    fn augmented(&mut self, _root: usize, _matched_right: &[Option<usize>]) {}
+
+    fn phase_started(&mut self, _phase: Phase) {}
+
+    fn phase_finished(&mut self, _phase: Phase) {}
+
+    fn search_started(&mut self, _root: usize) {}
+
+    fn search_finished(&mut self, _root: usize) {}
+
+    fn should_abort(&mut self) -> bool {
+        false
+    }
+}
+
+impl Observer for () {}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OpCounts {
+    pub edge_scans: u64,
+    pub match_flips: u64,
+    pub augmentations: u64,
+}
+
+impl Observer for OpCounts {
+    fn edge_scan(&mut self, _left: usize, _right: usize) {
+        self.edge_scans += 1;
+    }
+
+    fn match_flip(&mut self, _left: usize, _right: usize) {
+        self.match_flips += 1;
+    }
+
+    fn augmented(&mut self, _root: usize, _matched_right: &[Option<usize>]) {
+        self.augmentations += 1;
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PhaseTimings {
+    pub ops: OpCounts,
+    pub bfs: Duration,
+    pub dfs: Duration,
+    pub bookkeeping: Duration,
+    pub searches: Vec<Duration>,
+    phase_start: Option<Instant>,
+    search_start: Option<Instant>,
+}
+
+impl PhaseTimings {
+    pub fn search_quantile(&self, q: f64) -> Option<Duration> {
+        let mut sorted = self.searches.clone();
+        sorted.sort();
+        let last = sorted.len().checked_sub(1)?;
+        let rank = (q.clamp(0.0, 1.0) * last as f64).round() as usize;
+        Some(sorted[rank])
+    }
+}
+
+impl Observer for PhaseTimings {
+    fn edge_scan(&mut self, left: usize, right: usize) {
+        self.ops.edge_scan(left, right);
+    }
+
+    fn match_flip(&mut self, left: usize, right: usize) {
+        self.ops.match_flip(left, right);
+    }
+
+    fn augmented(&mut self, root: usize, matched_right: &[Option<usize>]) {
+        self.ops.augmented(root, matched_right);
+    }
+
+    fn phase_started(&mut self, _phase: Phase) {
+        self.phase_start = Some(Instant::now());
+    }
+
+    fn phase_finished(&mut self, phase: Phase) {
+        let Some(start) = self.phase_start.take() else {
+            return;
+        };
+        let total = match phase {
+            Phase::Bfs => &mut self.bfs,
+            Phase::Dfs => &mut self.dfs,
+            Phase::Bookkeeping => &mut self.bookkeeping,
+        };
+        *total += start.elapsed();
+    }
+
+    fn search_started(&mut self, _root: usize) {
+        self.search_start = Some(Instant::now());
+    }
+
+    fn search_finished(&mut self, _root: usize) {
+        if let Some(start) = self.search_start.take() {
+            self.searches.push(start.elapsed());
+        }
+    }
+}
+
+/// Histogram of augmenting-path lengths in edges, keyed by length. A path that flips `k`
+/// matched pairs has `2k - 1` edges.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PathLengths {
+    pub counts: BTreeMap<usize, u64>,
+    flips: usize,
+}
+
+impl PathLengths {
+    pub fn augmentations(&self) -> u64 {
+        self.counts.values().sum()
+    }
+}
+
+impl Observer for PathLengths {
+    fn match_flip(&mut self, _left: usize, _right: usize) {
+        self.flips += 1;
+    }
+
+    fn augmented(&mut self, _root: usize, _matched_right: &[Option<usize>]) {
+        let length = (2 * std::mem::take(&mut self.flips)).saturating_sub(1);
+        *self.counts.entry(length).or_default() += 1;
+    }
+}
+
+pub struct Deadline<'a, O> {
+    inner: &'a mut O,
+    at: Instant,
+    expired: bool,
+}
+
+impl<'a, O: Observer> Deadline<'a, O> {
+    pub fn new(inner: &'a mut O, timeout: Duration) -> Deadline<'a, O> {
+        Deadline {
+            inner,
+            at: Instant::now() + timeout,
+            expired: false,
+        }
+    }
+
+    pub fn expired(&self) -> bool {
+        self.expired
+    }
+}
+
+impl<O: Observer> Observer for Deadline<'_, O> {
+    fn edge_scan(&mut self, left: usize, right: usize) {
+        self.inner.edge_scan(left, right);
+    }
+
+    fn match_flip(&mut self, left: usize, right: usize) {
+        self.inner.match_flip(left, right);
+    }
+
+    fn augmented(&mut self, root: usize, matched_right: &[Option<usize>]) {
+        self.inner.augmented(root, matched_right);
+    }
+
+    fn phase_started(&mut self, phase: Phase) {
+        self.inner.phase_started(phase);
+    }
+
+    fn phase_finished(&mut self, phase: Phase) {
+        self.inner.phase_finished(phase);
+    }
+
+    fn search_started(&mut self, root: usize) {
+        self.inner.search_started(root);
+    }
+
+    fn search_finished(&mut self, root: usize) {
+        self.inner.search_finished(root);
+    }
+
+    fn should_abort(&mut self) -> bool {
+        if !self.expired && Instant::now() >= self.at {
+            self.expired = true;
+        }
+        self.expired || self.inner.should_abort()
+    }
+}
+
+/// Shared flag for aborting a solve from another thread; clones observe the same flag.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+pub struct Cancellable<'a, O> {
+    inner: &'a mut O,
+    token: &'a CancellationToken,
+    cancelled: bool,
+}
+
+impl<'a, O: Observer> Cancellable<'a, O> {
+    pub fn new(inner: &'a mut O, token: &'a CancellationToken) -> Cancellable<'a, O> {
+        Cancellable {
+            inner,
+            token,
+            cancelled: false,
+        }
+    }
+
+    /// Whether the solve stopped early because of the token.
+    pub fn cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    fn finish(self, matching: Matching) -> Result<Matching> {
+        if self.cancelled {
+            Err(Error::Cancelled)
+        } else {
+            Ok(matching)
+        }
+    }
+}
+
+impl<O: Observer> Observer for Cancellable<'_, O> {
+    fn edge_scan(&mut self, left: usize, right: usize) {
+        self.inner.edge_scan(left, right);
+    }
+
+    fn match_flip(&mut self, left: usize, right: usize) {
+        self.inner.match_flip(left, right);
+    }
+
+    fn augmented(&mut self, root: usize, matched_right: &[Option<usize>]) {
+        self.inner.augmented(root, matched_right);
+    }
+
+    fn phase_started(&mut self, phase: Phase) {
+        self.inner.phase_started(phase);
+    }
+
+    fn phase_finished(&mut self, phase: Phase) {
+        self.inner.phase_finished(phase);
+    }
+
+    fn search_started(&mut self, root: usize) {
+        self.inner.search_started(root);
+    }
+
+    fn search_finished(&mut self, root: usize) {
+        self.inner.search_finished(root);
+    }
+
+    fn should_abort(&mut self) -> bool {
+        if !self.cancelled && self.token.is_cancelled() {
+            self.cancelled = true;
+        }
+        self.cancelled || self.inner.should_abort()
+    }
+}
+
+pub fn hopcroft_karp_cancellable<G: Adjacency>(
+    graph: &G,
+    token: &CancellationToken,
+) -> Result<Matching> {
+    let mut unobserved = ();
+    let mut observer = Cancellable::new(&mut unobserved, token);
+    let matching = hopcroft_karp_observed(graph, &mut observer);
+    observer.finish(matching)
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PathRecorder {
+    flips: Vec<(usize, usize)>,
+}
+
+impl PathRecorder {
+    pub fn match_flip(&mut self, left: usize, right: usize) {
+        self.flips.push((left, right));
+    }
+
+    pub fn len(&self) -> usize {
+        (2 * self.flips.len()).saturating_sub(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.flips.is_empty()
+    }
+
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        let mut edges = self.flips.clone();
+        for pair in self.flips.windows(2) {
+            edges.push((pair[0].0, pair[1].1));
+        }
+        edges
+    }
+
+    pub fn clear(&mut self) {
+        self.flips.clear();
+    }
+}
+
+/// Result of [`BipartiteGraph::kuhn_capped`]: the length in edges of every augmenting path
+/// taken, and how many roots had an augmenting path that only existed above the cap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CappedMatching {
+    pub matching: Matching,
+    pub path_lengths: Vec<usize>,
+    pub blocked: usize,
+}
+
+pub fn maximum_matching<G: Adjacency>(graph: &G) -> Matching {
+    maximum_matching_indexed::<usize, G>(graph).expect("usize indices always fit")
+}
+
+/// Fails with [`Error::Overflow`] when a left vertex index does not fit `I`.
+pub fn maximum_matching_indexed<I: Index, G: Adjacency>(graph: &G) -> Result<Matching> {
+    Ok(augment_roots::<I, G>(graph, 0..graph.left_len(), false)?.0)
+}
+
+/// Size of a maximum matching without building the [`Matching`]: only the right side's
+/// partners and the visit stamps are kept, and [`maximum_matching_size_indexed`] narrows both
+/// to a smaller index type.
+pub fn maximum_matching_size<G: Adjacency>(graph: &G) -> usize {
+    maximum_matching_size_indexed::<usize, G>(graph).expect("usize indices always fit")
+}
+
+/// Fails with [`Error::Overflow`] when a left vertex index does not fit `I`.
+pub fn maximum_matching_size_indexed<I: Index, G: Adjacency>(graph: &G) -> Result<usize> {
+    Ok(search_roots::<I, G>(graph, 0..graph.left_len(), false)?.1)
+}
+
+/// Maximum matching that lexicographically maximises the number of matched left vertices of
+/// class 0, then class 1, and so on. Augmenting never unmatches a left vertex, so searching
+/// from the roots in class order is enough. Also returns the matched count of every class.
+pub fn prioritized_matching<G: Adjacency>(
+    graph: &G,
+    classes: &[usize],
+) -> Result<(Matching, Vec<usize>)> {
+    if classes.len() != graph.left_len() {
+        return Err(Error::InvalidParameter(format!(
+            "{} priority classes for {} left vertices",
+            classes.len(),
+            graph.left_len()
+        )));
+    }
+
+    let mut roots: Vec<usize> = (0..graph.left_len()).collect();
+    roots.sort_by_key(|&v| classes[v]);
+    let (matching, _) = augment_roots::<usize, G>(graph, roots, false)?;
+
+    let mut matched = vec![0; classes.iter().max().map_or(0, |&c| c + 1)];
+    for (&class, m) in classes.iter().zip(matching.matched_left()) {
+        if m.is_some() {
+            matched[class] += 1;
+        }
+    }
+    Ok((matching, matched))
+}
+
+pub fn left_perfect_matching<G: Adjacency>(graph: &G) -> std::result::Result<Matching, usize> {
+    if let Some(v) = (0..graph.left_len()).find(|&v| graph.left_neighbours(v).next().is_none()) {
+        return Err(v);
+    }
+    match augment_roots::<usize, G>(graph, 0..graph.left_len(), true)
+        .expect("usize indices always fit")
+    {
+        (matching, None) => Ok(matching),
+        (_, Some(root)) => Err(root),
+    }
+}
+
+fn augment_roots<I: Index, G: Adjacency>(
+    graph: &G,
+    roots: impl IntoIterator<Item = usize>,
+    stop_on_failure: bool,
+) -> Result<(Matching, Option<usize>)> {
+    let (matched_right, _, failed) = search_roots::<I, G>(graph, roots, stop_on_failure)?;
+    let matched_right = matched_right
+        .into_iter()
+        .map(|u| (u != I::NONE).then(|| u.to_usize()))
+        .collect();
+    Ok((
+        Matching::from_matched_right(graph.left_len(), matched_right),
+        failed,
+    ))
+}
+
+/// The augmenting searches behind [`augment_roots`], returning the raw partner array, the
+/// number of augmentations and the first root without a path if `stop_on_failure` is set.
+/// Only left vertex indices are stored as `I`, so they are checked to fit up front.
+fn search_roots<I: Index, G: Adjacency>(
+    graph: &G,
+    roots: impl IntoIterator<Item = usize>,
+    stop_on_failure: bool,
+) -> Result<(Vec<I>, usize, Option<usize>)> {
+    if graph.left_len() > 0 && I::from_usize(graph.left_len() - 1).is_none() {
+        return Err(Error::Overflow);
+    }
+    let index = |v: usize| I::from_usize(v).unwrap_or(I::NONE);
+
+    let mut matched_right = vec![I::NONE; graph.right_len()];
+    let mut visited = vec![I::NONE; graph.left_len()];
+    let mut stack = Vec::new();
+
+    let mut size = 0;
+    let mut failed = None;
+    for root in roots {
+        let stamp = index(root);
+        visited[root] = stamp;
+        stack.push((root, None, graph.left_neighbours(root)));
+
+        let mut augmented = false;
+        while let Some((v, _, neighbours)) = stack.last_mut() {
+            let v = *v;
+            let Some(to) = neighbours.next() else {
+                stack.pop();
+                continue;
+            };
+
+            let u = matched_right[to];
+            if u == I::NONE {
+                matched_right[to] = index(v);
+                while let Some((_, via, _)) = stack.pop() {
+                    if let (Some(via), Some((u, _, _))) = (via, stack.last()) {
+                        matched_right[via] = index(*u);
+                    }
+                }
+                augmented = true;
+            } else if visited[u.to_usize()] != stamp {
+                visited[u.to_usize()] = stamp;
+                stack.push((u.to_usize(), Some(to), graph.left_neighbours(u.to_usize())));
+            }
+        }
+
+        size += usize::from(augmented);
+        if stop_on_failure && !augmented {
+            failed = Some(root);
+            break;
+        }
+    }
+    Ok((matched_right, size, failed))
+}
+
+impl<T> BipartiteGraph<T> {
+    pub fn kuhn<const R: bool>(&self, rng: &mut StdRng) -> Matching {
+        self.kuhn_observed::<R, _>(rng, &mut ())
+    }
+
+    pub fn kuhn_observed<const R: bool, O: Observer>(
+        &self,
+        rng: &mut StdRng,
+        observer: &mut O,
+    ) -> Matching {
+        let matched_right = vec![None; self.right_nodes.len()];
+        self.augment_all(ScanOrder::shuffled_if(R, rng), matched_right, observer)
+    }
+
+    /// Randomizes like the shuffling variant, but each scan of a neighbour list starts at a
+    /// random position and wraps around instead of permuting the whole list.
+    pub fn kuhn_random_offset(&self, rng: &mut StdRng) -> Matching {
+        self.kuhn_random_offset_observed(rng, &mut ())
+    }
+
+    pub fn kuhn_random_offset_observed<O: Observer>(
+        &self,
+        rng: &mut StdRng,
+        observer: &mut O,
+    ) -> Matching {
+        let matched_right = vec![None; self.right_nodes.len()];
+        self.augment_all(ScanOrder::Offset(rng), matched_right, observer)
+    }
+
+    /// Kuhn's algorithm augmenting from `side`; driving from the right solves the transpose.
+    pub fn kuhn_driven<const R: bool>(&self, rng: &mut StdRng, side: Side) -> Matching
+    where
+        T: Clone,
+    {
+        match side {
+            Side::Left => self.kuhn::<R>(rng),
+            Side::Right => self.transpose().kuhn::<R>(rng).transpose(),
+        }
+    }
+
+    /// Polls `token` between augmentations and returns [`Error::Cancelled`] once it is set.
+    pub fn kuhn_cancellable<const R: bool>(
+        &self,
+        rng: &mut StdRng,
+        token: &CancellationToken,
+    ) -> Result<Matching> {
+        let mut unobserved = ();
+        let mut observer = Cancellable::new(&mut unobserved, token);
+        let matching = self.kuhn_observed::<R, _>(rng, &mut observer);
+        observer.finish(matching)
+    }
+
+    pub fn kuhn_bfs_cancellable(&self, token: &CancellationToken) -> Result<Matching> {
+        let mut unobserved = ();
+        let mut observer = Cancellable::new(&mut unobserved, token);
+        let matching = self.kuhn_bfs_observed(&mut observer);
+        observer.finish(matching)
+    }
+
+    pub fn kuhn_deterministic(&self) -> Matching {
+        self.kuhn_deterministic_observed(&mut ())
+    }
+
+    pub fn kuhn_deterministic_observed<O: Observer>(&self, observer: &mut O) -> Matching {
+        self.sort_neighbours();
+        let matched_right = vec![None; self.right_nodes.len()];
+        self.augment_all(ScanOrder::Stored, matched_right, observer)
+    }
+
+    pub fn kuhn_from<const R: bool>(
+        &self,
+        rng: &mut StdRng,
+        initial: &Matching,
+    ) -> Result<Matching> {
+        self.kuhn_from_observed::<R, _>(rng, initial, &mut ())
+    }
+
+    pub fn kuhn_from_observed<const R: bool, O: Observer>(
+        &self,
+        rng: &mut StdRng,
+        initial: &Matching,
+        observer: &mut O,
+    ) -> Result<Matching> {
+        if !self.is_valid_matching(initial) {
+            return Err(Error::InvalidMatching);
+        }
+
+        let matched_right = initial.matched_right().to_vec();
+        Ok(self.augment_all(ScanOrder::shuffled_if(R, rng), matched_right, observer))
+    }
+
+    /// Kuhn's algorithm trying the roots in `arrivals` order, as a replay of an online run
+    /// that keeps every arrival's augmenting path. Unlisted left vertices stay unmatched.
+    pub fn kuhn_in_order(&self, arrivals: &ArrivalOrder) -> Matching {
+        self.kuhn_in_order_observed(arrivals, &mut ())
+    }
+
+    pub fn kuhn_in_order_observed<O: Observer>(
+        &self,
+        arrivals: &ArrivalOrder,
+        observer: &mut O,
+    ) -> Matching {
+        let matched_right = vec![None; self.right_nodes.len()];
+        let roots = arrivals.as_slice().iter().copied();
+        self.augment_in_order(ScanOrder::Stored, matched_right, roots, observer)
+    }
+
+    pub fn has_left_perfect_matching(&self) -> std::result::Result<Matching, usize> {
+        left_perfect_matching(self)
+    }
+
+    pub fn kuhn_bfs(&self) -> Matching {
+        self.kuhn_bfs_observed(&mut ())
+    }
+
+    pub fn kuhn_bfs_observed<O: Observer>(&self, observer: &mut O) -> Matching {
+        let mut matched_right = vec![None; self.right_nodes.len()];
+        let mut matched_left = vec![None; self.left_nodes.len()];
+        let mut via = vec![0; self.right_nodes.len()];
+        let mut visited = vec![usize::MAX; self.left_nodes.len()];
+        let mut queue = VecDeque::new();
+
+        for root in 0..self.left_nodes.len() {
+            if observer.should_abort() {
+                break;
+            }
+            observer.search_started(root);
+            observer.phase_started(Phase::Bfs);
+            visited[root] = root;
+            queue.clear();
+            queue.push_back(root);
+
+            let mut free = None;
+            'search: while let Some(v) = queue.pop_front() {
+                for to in self.left_neighbours(v) {
+                    observer.edge_scan(v, to);
+                    match matched_right[to] {
+                        None => {
+                            free = Some((v, to));
+                            break 'search;
+                        }
+                        Some(u) if visited[u] != root => {
+                            visited[u] = root;
+                            via[to] = v;
+                            queue.push_back(u);
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+
+            observer.phase_finished(Phase::Bfs);
+
+            let Some((mut v, mut to)) = free else {
+                observer.search_finished(root);
+                continue;
+            };
+            loop {
+                let previous = matched_left[v];
+                observer.match_flip(v, to);
+                matched_right[to] = Some(v);
+                matched_left[v] = Some(to);
+                match previous {
+                    Some(j) => {
+                        to = j;
+                        v = via[j];
+                    }
+                    None => break,
+                }
+            }
+            observer.search_finished(root);
+            observer.augmented(root, &matched_right);
+        }
+
+        Matching::from_parts(matched_left, matched_right)
+    }
+
+    pub fn kuhn_capped(&self, max_path_len: usize) -> CappedMatching {
+        self.kuhn_capped_observed(max_path_len, &mut ())
+    }
+
+    pub fn kuhn_capped_observed<O: Observer>(
+        &self,
+        max_path_len: usize,
+        observer: &mut O,
+    ) -> CappedMatching {
+        let mut matched_right = vec![None; self.right_nodes.len()];
+        let mut reached_at = vec![usize::MAX; self.left_nodes.len()];
+        let mut path_lengths = Vec::new();
+        let mut blocked = 0;
+
+        for root in 0..self.left_nodes.len() {
+            if observer.should_abort() {
+                break;
+            }
+
+            observer.search_started(root);
+            observer.phase_started(Phase::Dfs);
+            reached_at.fill(usize::MAX);
+            let length = self.try_kuhn_capped(
+                root,
+                0,
+                max_path_len,
+                &mut matched_right,
+                &mut reached_at,
+                observer,
+            );
+            observer.phase_finished(Phase::Dfs);
+            observer.search_finished(root);
+
+            match length {
+                Some(length) => {
+                    path_lengths.push(length);
+                    observer.augmented(root, &matched_right);
+                }
+                None if self.has_augmenting_path(root, &matched_right) => blocked += 1,
+                None => {}
+            }
+        }
+
+        CappedMatching {
+            matching: Matching::from_matched_right(self.left_nodes.len(), matched_right),
+            path_lengths,
+            blocked,
+        }
+    }
+
+    /// Depth-limited DFS; a vertex is re-entered only when reached by a shorter prefix, so
+    /// every path within the cap is found.
+    fn try_kuhn_capped<O: Observer>(
+        &self,
+        v: usize,
+        depth: usize,
+        max_path_len: usize,
+        matched_right: &mut Vec<Option<usize>>,
+        reached_at: &mut Vec<usize>,
+        observer: &mut O,
+    ) -> Option<usize> {
+        if reached_at[v] <= depth || depth + 1 > max_path_len {
+            return None;
+        }
+        reached_at[v] = depth;
+
+        let neighbours = self.left_nodes[v].neighbours.borrow();
+        for &to in &*neighbours {
+            observer.edge_scan(v, to);
+            let length = match matched_right[to] {
+                None => Some(depth + 1),
+                Some(u) => self.try_kuhn_capped(
+                    u,
+                    depth + 2,
+                    max_path_len,
+                    matched_right,
+                    reached_at,
+                    observer,
+                ),
+            };
+            if length.is_some() {
+                observer.match_flip(v, to);
+                matched_right[to] = Some(v);
+                return length;
+            }
+        }
+
+        None
+    }
+
+    fn has_augmenting_path(&self, root: usize, matched_right: &[Option<usize>]) -> bool {
+        let mut visited = vec![false; self.right_nodes.len()];
+        let mut stack = vec![root];
+        while let Some(v) = stack.pop() {
+            for &to in &*self.left_nodes[v].neighbours.borrow() {
+                if visited[to] {
+                    continue;
+                }
+                visited[to] = true;
+                match matched_right[to] {
+                    None => return true,
+                    Some(u) => stack.push(u),
+                }
+            }
+        }
+        false
+    }
+
+    fn augment_all<O: Observer>(
+        &self,
+        order: ScanOrder<'_>,
+        matched_right: Vec<Option<usize>>,
+        observer: &mut O,
+    ) -> Matching {
+        self.augment_in_order(order, matched_right, 0..self.left_nodes.len(), observer)
+    }
+
+    fn augment_in_order<O: Observer>(
+        &self,
+        mut order: ScanOrder<'_>,
+        matched_right: Vec<Option<usize>>,
+        roots: impl IntoIterator<Item = usize>,
+        observer: &mut O,
+    ) -> Matching {
+        observer.phase_started(Phase::Bookkeeping);
+        let mut state = KuhnState::with_matched_right(self, matched_right);
+        observer.phase_finished(Phase::Bookkeeping);
+
+        for v in roots {
+            if state.matched_left[v] {
+                continue;
+            }
+            if observer.should_abort() {
+                break;
+            }
+            state.augment_with(order.reborrow(), v, observer);
+        }
+
+        state.into_matching()
+    }
+
+    fn try_kuhn<O: Observer>(
+        &self,
+        order: &mut ScanOrder<'_>,
+        v: usize,
+        matched_right: &mut Vec<Option<usize>>,
+        used_left: &mut Vec<bool>,
+        observer: &mut O,
+    ) -> bool {
+        if used_left[v] {
+            return false;
+        }
+
+        used_left[v] = true;
+
+        let start = match order {
+            ScanOrder::Stored => 0,
+            ScanOrder::Shuffled(rng) => {
+                let mut neighbours = self.left_nodes[v].neighbours.borrow_mut();
+                (*neighbours).shuffle(*rng);
+                0
+            }
+            ScanOrder::Offset(rng) => {
+                let len = self.left_nodes[v].neighbours.borrow().len();
+                if len == 0 {
+                    0
+                } else {
+                    rng.gen_range(0..len)
+                }
+            }
+        };
+
+        let neighbours = self.left_nodes[v].neighbours.borrow();
+        let (before, after) = neighbours.split_at(start);
+        for &to in after.iter().chain(before) {
+            observer.edge_scan(v, to);
+            if matched_right[to].is_none()
+                || self.try_kuhn(
+                    order,
+                    matched_right[to].unwrap(),
+                    matched_right,
+                    used_left,
+                    observer,
+                )
+            {
+                observer.match_flip(v, to);
+                matched_right[to] = Some(v);
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// How a depth-first search walks each neighbour list.
+enum ScanOrder<'r> {
+    Stored,
+    Shuffled(&'r mut StdRng),
+    /// From a random position, wrapping around.
+    Offset(&'r mut StdRng),
+}
+
+impl<'r> ScanOrder<'r> {
+    fn shuffled_if(shuffle: bool, rng: &'r mut StdRng) -> ScanOrder<'r> {
+        if shuffle {
+            ScanOrder::Shuffled(rng)
+        } else {
+            ScanOrder::Stored
+        }
+    }
+
+    fn reborrow(&mut self) -> ScanOrder<'_> {
+        match self {
+            ScanOrder::Stored => ScanOrder::Stored,
+            ScanOrder::Shuffled(rng) => ScanOrder::Shuffled(rng),
+            ScanOrder::Offset(rng) => ScanOrder::Offset(rng),
+        }
+    }
+}
+
+/// Kuhn's algorithm one root at a time, for custom augmentation orders or online arrival.
+/// The searches are the same depth-first ones the batch solvers run.
+pub struct KuhnState<'g, T> {
+    graph: &'g BipartiteGraph<T>,
+    matched_right: Vec<Option<usize>>,
+    matched_left: Vec<bool>,
+    used_left: Vec<bool>,
+}
+
+impl<'g, T> KuhnState<'g, T> {
+    pub fn new(graph: &'g BipartiteGraph<T>) -> KuhnState<'g, T> {
+        KuhnState::with_matched_right(graph, vec![None; graph.right_nodes.len()])
+    }
+
+    pub fn from_matching(
+        graph: &'g BipartiteGraph<T>,
+        initial: &Matching,
+    ) -> Result<KuhnState<'g, T>> {
+        if !graph.is_valid_matching(initial) {
+            return Err(Error::InvalidMatching);
+        }
+        Ok(KuhnState::with_matched_right(
+            graph,
+            initial.matched_right().to_vec(),
+        ))
+    }
+
+    fn with_matched_right(
+        graph: &'g BipartiteGraph<T>,
+        matched_right: Vec<Option<usize>>,
+    ) -> KuhnState<'g, T> {
+        let mut matched_left = vec![false; graph.left_nodes.len()];
+        for &i in matched_right.iter().flatten() {
+            matched_left[i] = true;
+        }
+        KuhnState {
+            graph,
+            matched_right,
+            matched_left,
+            used_left: vec![false; graph.left_nodes.len()],
+        }
+    }
+
+    /// Searches for an augmenting path from left vertex `v` and applies it. Returns false,
+    /// changing nothing, when `v` is already matched or no path exists. Panics if `v` is out
+    /// of range.
+    pub fn augment(&mut self, v: usize) -> bool {
+        self.augment_with(ScanOrder::Stored, v, &mut ())
+    }
+
+    pub fn augment_observed<O: Observer>(&mut self, v: usize, observer: &mut O) -> bool {
+        self.augment_with(ScanOrder::Stored, v, observer)
+    }
+
+    fn augment_with<O: Observer>(
+        &mut self,
+        mut order: ScanOrder<'_>,
+        v: usize,
+        observer: &mut O,
+    ) -> bool {
+        if self.matched_left[v] {
+            return false;
+        }
+
+        observer.search_started(v);
+        observer.phase_started(Phase::Bookkeeping);
+        self.used_left.fill(false);
+        observer.phase_finished(Phase::Bookkeeping);
+
+        observer.phase_started(Phase::Dfs);
+        let found = self.graph.try_kuhn(
+            &mut order,
+            v,
+            &mut self.matched_right,
+            &mut self.used_left,
+            observer,
+        );
+        observer.phase_finished(Phase::Dfs);
+        observer.search_finished(v);
+
+        if found {
+            self.matched_left[v] = true;
+            observer.augmented(v, &self.matched_right);
+        }
+        found
+    }
+
+    /// Augments from every vertex of `arrivals` in turn and returns how many were matched.
+    /// Panics if the order was built for more left vertices than the graph has.
+    pub fn replay(&mut self, arrivals: &ArrivalOrder) -> usize {
+        self.replay_observed(arrivals, &mut ())
+    }
+
+    pub fn replay_observed<O: Observer>(
+        &mut self,
+        arrivals: &ArrivalOrder,
+        observer: &mut O,
+    ) -> usize {
+        let mut matched = 0;
+        for &v in arrivals.as_slice() {
+            if observer.should_abort() {
+                break;
+            }
+            matched += usize::from(self.augment_observed(v, observer));
+        }
+        matched
+    }
+
+    pub fn is_left_matched(&self, v: usize) -> bool {
+        self.matched_left[v]
+    }
+
+    pub fn match_of_right(&self, j: usize) -> Option<usize> {
+        self.matched_right[j]
+    }
+
+    pub fn len(&self) -> usize {
+        self.matched_right.iter().flatten().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn matching(&self) -> Matching {
+        Matching::from_matched_right(self.graph.left_nodes.len(), self.matched_right.clone())
+    }
+
+    pub fn into_matching(self) -> Matching {
+        Matching::from_matched_right(self.graph.left_nodes.len(), self.matched_right)
+    }
+}
+
+/// Kuhn's algorithm over any [`Adjacency`] with buffers that outlive a solve: the matching,
+/// visit stamps, the search stack, the neighbour lists being scanned and the root order.
+/// Solving graphs of similar size again allocates nothing but the returned [`Matching`], and
+/// not even that with [`KuhnSolver::solve_in_place`].
+#[derive(Clone, Debug, Default)]
+pub struct KuhnSolver {
+    left_len: usize,
+    matched_right: Vec<Option<usize>>,
+    matched_left: Vec<bool>,
+    visited: Vec<u64>,
+    stamp: u64,
+    /// `(vertex, right vertex it was reached through, cursor, end)`, the range indexing `scans`.
+    stack: Vec<(usize, Option<usize>, usize, usize)>,
+    scans: Vec<usize>,
+    roots: Vec<usize>,
+}
+
+impl KuhnSolver {
+    pub fn new() -> KuhnSolver {
+        KuhnSolver::default()
+    }
+
+    pub fn solve<G: Adjacency>(&mut self, graph: &G) -> Matching {
+        self.solve_in_place(graph, None, &mut ());
+        self.matching()
+    }
+
+    /// Like [`BipartiteGraph::kuhn`] with shuffling, but tries the roots in random order and
+    /// permutes copies of the neighbour lists, so the graph is left untouched.
+    pub fn solve_shuffled<G: Adjacency>(&mut self, graph: &G, rng: &mut StdRng) -> Matching {
+        self.solve_in_place(graph, Some(rng), &mut ());
+        self.matching()
+    }
+
+    /// Solves into the solver's own buffers and returns the matching size; read the result
+    /// back with [`KuhnSolver::matched_right`] or [`KuhnSolver::matching`].
+    pub fn solve_in_place<G: Adjacency, O: Observer>(
+        &mut self,
+        graph: &G,
+        mut rng: Option<&mut StdRng>,
+        observer: &mut O,
+    ) -> usize {
+        observer.phase_started(Phase::Bookkeeping);
+        let (l, r) = (graph.left_len(), graph.right_len());
+        self.left_len = l;
+        self.matched_right.clear();
+        self.matched_right.resize(r, None);
+        self.matched_left.clear();
+        self.matched_left.resize(l, false);
+        // Stamps only grow, so entries left over from earlier solves never match.
+        self.visited.resize(l, 0);
+        self.roots.clear();
+        self.roots.extend(0..l);
+        if let Some(rng) = rng.as_deref_mut() {
+            self.roots.shuffle(rng);
+        }
+        observer.phase_finished(Phase::Bookkeeping);
+
+        let mut size = 0;
+        for k in 0..l {
+            if observer.should_abort() {
+                break;
+            }
+            let root = self.roots[k];
+            observer.search_started(root);
+            observer.phase_started(Phase::Dfs);
+            self.stamp += 1;
+            self.push(graph, root, None, rng.as_deref_mut());
+            let found = self.search(graph, rng.as_deref_mut(), observer);
+            observer.phase_finished(Phase::Dfs);
+            observer.search_finished(root);
+            if found {
+                self.matched_left[root] = true;
+                size += 1;
+                observer.augmented(root, &self.matched_right);
+            }
+        }
+        size
+    }
+
+    fn push<G: Adjacency>(
+        &mut self,
+        graph: &G,
+        v: usize,
+        via: Option<usize>,
+        rng: Option<&mut StdRng>,
+    ) {
+        self.visited[v] = self.stamp;
+        let start = self.scans.len();
+        self.scans.extend(graph.left_neighbours(v));
+        if let Some(rng) = rng {
+            self.scans[start..].shuffle(rng);
+        }
+        self.stack.push((v, via, start, self.scans.len()));
+    }
+
+    fn search<G: Adjacency, O: Observer>(
+        &mut self,
+        graph: &G,
+        mut rng: Option<&mut StdRng>,
+        observer: &mut O,
+    ) -> bool {
+        while let Some(&mut (v, _, ref mut cursor, end)) = self.stack.last_mut() {
+            if *cursor == end {
+                let (_, _, start, _) = self.stack.pop().expect("stack is not empty");
+                self.scans.truncate(start);
+                continue;
+            }
+            let to = self.scans[*cursor];
+            *cursor += 1;
+            observer.edge_scan(v, to);
+
+            match self.matched_right[to] {
+                None => {
+                    let mut right = to;
+                    while let Some((u, via, _, _)) = self.stack.pop() {
+                        observer.match_flip(u, right);
+                        self.matched_right[right] = Some(u);
+                        if let Some(via) = via {
+                            right = via;
+                        }
+                    }
+                    self.scans.clear();
+                    return true;
+                }
+                Some(u) if self.visited[u] != self.stamp => {
+                    self.push(graph, u, Some(to), rng.as_deref_mut());
+                }
+                Some(_) => {}
+            }
+        }
+        false
+    }
+
+    pub fn matched_right(&self) -> &[Option<usize>] {
+        &self.matched_right
+    }
+
+    pub fn is_left_matched(&self, v: usize) -> bool {
+        self.matched_left[v]
+    }
+
+    /// The matching of the last solve.
+    pub fn matching(&self) -> Matching {
+        Matching::from_matched_right(self.left_len, self.matched_right.clone())
+    }
+}
@@ -0,0 +1,132 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod core;
+mod index;
+
+pub use index::Index;
+
+/// Marks every item as needing the `std` feature.
+macro_rules! with_std {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "std")]
+            $item
+        )*
+    };
+}
+
+with_std! {
+    mod arrival;
+    mod assignment;
+    mod batch;
+    pub mod bench;
+    mod bounds;
+    mod coloring;
+    mod components;
+    mod csr;
+    mod dense;
+    mod dimacs;
+    mod dot;
+    mod double_cover;
+    mod edit;
+    mod error;
+    mod feasibility;
+    #[cfg(feature = "ffi")]
+    pub mod ffi;
+    mod flow;
+    mod fractional;
+    mod generate;
+    mod graph;
+    mod graph6;
+    #[cfg(feature = "compression")]
+    mod gzip;
+    mod hall;
+    mod heuristic;
+    mod hopcroft_karp;
+    mod implicit;
+    mod input;
+    mod integrity;
+    mod interval;
+    mod kuhn;
+    mod matching;
+    mod matrix_market;
+    mod matroid;
+    mod mcmc;
+    mod metadata;
+    mod metis;
+    #[cfg(all(unix, feature = "mmap"))]
+    mod mmap;
+    mod orientation;
+    mod pareto;
+    #[cfg(feature = "parquet")]
+    pub mod parquet;
+    mod relabel;
+    pub mod report;
+    mod robustness;
+    mod sample;
+    #[cfg(feature = "server")]
+    pub mod server;
+    pub mod stable;
+    mod threshold;
+    #[cfg(all(unix, feature = "tui"))]
+    pub mod tui;
+    mod typed;
+    mod weights;
+    #[cfg(feature = "compression")]
+    mod zstd;
+
+    pub use arrival::ArrivalOrder;
+    pub use assignment::{AssignmentDuals, CostMatrix, IncrementalHungarian};
+    pub use batch::{solve_batch, BatchAlgorithm};
+    pub use bounds::{estimate_matching_bounds, MatchingBounds};
+    pub use coloring::EdgeColoring;
+    pub use components::{connected_components, solve_components_parallel, Component};
+    pub use csr::CsrGraph;
+    pub use dense::{DenseBipartiteGraph, GeneratedGraph};
+    pub use dot::{DotLayout, DotOrder, NodeLink};
+    pub use edit::GraphDiff;
+    pub use error::{Error, Result};
+    pub use feasibility::{perfect_matching, perfect_matching_obstruction, Obstruction};
+    pub use flow::{
+        degree_constrained_matching, push_relabel_matching, DegreeConstrainedMatching, FlowNetwork,
+    };
+    pub use fractional::FractionalMatching;
+    pub use generate::{BlockModel, DegreeSequence, GenConfig, GenConfigBuilder, GenModel, GenStats};
+    pub use graph::{Adjacency, BipartiteGraph, Duplicates, Node, Side, MAX_INFERRED_VERTICES};
+    pub use graph6::{Bipartition, SimpleGraph};
+    pub use hall::{check_hall, HallViolation};
+    pub use heuristic::{
+        Annealing, AssignmentSolver, Greedy, Hungarian, LocalSearch, WeightedGreedy,
+        WeightedMatchingSolver,
+    };
+    pub use hopcroft_karp::{
+        hopcroft_karp, hopcroft_karp_observed, hopcroft_karp_parallel,
+        hopcroft_karp_parallel_deterministic,
+    };
+    pub use implicit::{
+        kuhn_implicit, kuhn_memoized, ImplicitGraph, MemoizedPredicateGraph, PredicateGraph,
+    };
+    pub use input::{input_extension, open_input, Compression, Input};
+    pub use integrity::{IntegrityIssue, IntegrityReport, SanitizeReport};
+    pub use interval::{interval_graph, schedule_intervals, Schedule, TimeWindow};
+    pub use kuhn::{
+        hopcroft_karp_cancellable, left_perfect_matching, maximum_matching, maximum_matching_indexed,
+        maximum_matching_size, maximum_matching_size_indexed,
+        prioritized_matching, Cancellable, CancellationToken, CappedMatching, Deadline, KuhnState,
+        KuhnSolver, Observer, OpCounts, PathLengths, PathRecorder, Phase, PhaseTimings,
+    };
+    pub use matching::{Matching, MatchingSimilarity};
+    pub use matroid::TransversalMatroid;
+    pub use mcmc::MatchingChain;
+    pub use metadata::RunMetadata;
+    #[cfg(all(unix, feature = "mmap"))]
+    pub use mmap::MmapCsrGraph;
+    pub use orientation::AlternatingOrientation;
+    pub use pareto::{BiObjective, ParetoPoint};
+    pub use relabel::Relabeling;
+    pub use robustness::{EdgeCriticality, RobustnessReport, VertexFailure};
+    pub use sample::SampledSubgraph;
+    pub use threshold::{threshold_curve, Sweep, Threshold, ThresholdPoint};
+    pub use typed::{EdgeType, EdgeTypes, StagedMatching};
+    pub use weights::{EdgeWeights, WeightDistribution};
+}
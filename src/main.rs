@@ -1,164 +1,1283 @@
-use std::cell::RefCell;
-use std::time::Instant;
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, BufReader};
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use rand::prelude::*;
-use rand::seq::index::sample;
-use rand::seq::SliceRandom;
 
-struct BipartiteGraph<T> {
-    left_nodes: Vec<Node<T>>,
-    right_nodes: Vec<Node<T>>,
+use kuhn::bench::{
+    self, cell_seed, compare_layouts, compare_orientations, fit_complexity, mad_outliers,
+    run_ordered, test_graph_with_options, variance_study, ComplexityFit, LocalityRow, Metric,
+    OrientationRow, PathLengthRow, RunOptions, TimingPoint, Trace, TransitionRow, Variant,
+};
+use kuhn::report::{run_report, write_report, ReportFormat};
+use kuhn::{
+    estimate_matching_bounds, hopcroft_karp, input_extension, maximum_matching_size, open_input,
+    perfect_matching, schedule_intervals, solve_components_parallel, Adjacency, Annealing,
+    ArrivalOrder, AssignmentSolver, BiObjective, BipartiteGraph, CostMatrix, CsrGraph, DotLayout,
+    Duplicates, EdgeType, EdgeTypes, GenConfig, GenModel, GenStats, Greedy, Hungarian, LocalSearch,
+    Matching, MatchingChain, ParetoPoint, RunMetadata, Side, SimpleGraph, TimeWindow,
+};
+
+const SEED: u64 = 131254153212;
+
+fn usage() -> ! {
+    eprintln!("usage: kuhn [bench] [--metric time|ops|both|phases] [--variant NAME] [--timeout-per-instance SECS] [--warmup K] [--reject-outliers] [--drive left|right|auto] [--relabel-every PUSHES] [--deterministic] [--layout sorted|shuffled] [--trace] [--jobs N] [--metadata FILE|-] [--format csv|parquet]");
+    eprintln!(
+        "       kuhn bench variance [--runs K] [--size L R M] [--seed S] [--metadata FILE|-]"
+    );
+    eprintln!("       kuhn bench phase-transition [--size N] [--degrees MIN MAX STEP] [--trials K] [--seed S] [--jobs N] [--metadata FILE|-]");
+    eprintln!(
+        "       kuhn solve <edges.csv|graphs.g6|graphs.s6|graph.metis|problem.dimacs|matrix.mtx> [--parallel-components] [--threads N] [--labeled [--format csv|json]] [--backup edges.csv] [--arrivals FILE] [--size-only] [--perfect]"
+    );
+    eprintln!("       kuhn bench rerun --size L R M --seed S [--metric M] [--variant NAME] [--warmup K] [--drive left|right|auto] [--relabel-every PUSHES] [--deterministic] [--layout sorted|shuffled]");
+    eprintln!("       kuhn bench fit <timings.csv> [--vertices V] [--residuals]");
+    eprintln!("       kuhn bench paths [--variant NAME] [--size L R] [--edges MIN MAX STEP] [--repetitions K] [--seed S] [--jobs N] [--metadata FILE|-]");
+    eprintln!("       kuhn bench orientation [--variant NAME] [--instance L R M]... [--seed S] [--metadata FILE|-]");
+    eprintln!("       kuhn bench locality [--variant NAME] [--instance L R M]... [--seed S] [--metadata FILE|-]");
+    eprintln!("       kuhn generate [--size L R] [--edges M|--density P] [--model gnm|gnp|multigraph] [--seed S] [--csr FILE] [--stats-every K]");
+    eprintln!("       kuhn bounds <edges.csv>...");
+    eprintln!("       kuhn assign <costs.txt|costs.npy> [--duals FILE] [--compare] [--fairness FILE [--weights A B]]");
+    eprintln!("       kuhn render <edges.csv> [--deterministic] [--seed S] [--frames DIR] [--save-matching FILE] [--same-rank] [--order free|index|degree]");
+    eprintln!("       kuhn robustness <edges.csv> [--sample K] [--seed S] [--edges]");
+    eprintln!("       kuhn orient <edges.csv> [--matching FILE] [--dot]");
+    eprintln!("       kuhn relabel <edges.csv> [--seed S] [--mapping FILE]");
+    eprintln!("       kuhn schedule <tasks.csv> <slots.csv>");
+    eprintln!("       kuhn sample <edges.csv> [--count K] [--iterations N] [--seed S]");
+    eprintln!("       kuhn inspect <edges.csv>");
+    eprintln!("       kuhn serve [--bind ADDR] [--port P]");
+    eprintln!("       kuhn report [--format markdown|html] [--instance L R M]... [--seed S] [edges.csv...]");
+    process::exit(2);
+}
+
+fn fail(err: impl Display) -> ! {
+    eprintln!("error: {err}");
+    process::exit(1);
 }
 
-impl<T> BipartiteGraph<T> {
-    fn random(rng: &mut StdRng, l: usize, r: usize, num_edges: usize) -> BipartiteGraph<T>
-    where
-        T: Default,
-    {
-        let mut left_nodes = Vec::new();
-        for _ in 0..l {
-            left_nodes.push(Node {
-                data: T::default(),
-                neighbours: RefCell::new(Vec::new()),
-            });
+fn parse_value<V>(args: &mut impl Iterator<Item = String>) -> V
+where
+    V: FromStr,
+    V::Err: Display,
+{
+    let value = args.next().unwrap_or_else(|| usage());
+    value.parse().unwrap_or_else(|err| {
+        eprintln!("{err}");
+        usage()
+    })
+}
+
+fn main() {
+    let mut args = env::args().skip(1).peekable();
+    match args.peek().map(String::as_str) {
+        Some("serve") => {
+            args.next();
+            serve(args)
+        }
+        Some("solve") => {
+            args.next();
+            solve(args)
+        }
+        Some("generate") => {
+            args.next();
+            generate(args)
+        }
+        Some("bounds") => {
+            args.next();
+            bounds(args)
+        }
+        Some("assign") => {
+            args.next();
+            assign(args)
+        }
+        Some("render") => {
+            args.next();
+            render(args)
+        }
+        Some("robustness") => {
+            args.next();
+            robustness(args)
+        }
+        Some("inspect") => {
+            args.next();
+            inspect(args)
+        }
+        Some("orient") => {
+            args.next();
+            orient(args)
+        }
+        Some("relabel") => {
+            args.next();
+            relabel(args)
         }
+        Some("schedule") => {
+            args.next();
+            schedule(args)
+        }
+        Some("sample") => {
+            args.next();
+            sample(args)
+        }
+        Some("report") => {
+            args.next();
+            report(args)
+        }
+        Some("bench") => {
+            args.next();
+            bench(args)
+        }
+        _ => bench(args),
+    }
+}
 
-        let mut right_nodes = Vec::new();
-        for _ in 0..r {
-            right_nodes.push(Node {
-                data: T::default(),
-                neighbours: RefCell::new(Vec::new()),
-            });
+fn bench(mut args: Peekable<impl Iterator<Item = String>>) {
+    match args.peek().map(String::as_str) {
+        Some("variance") => {
+            args.next();
+            variance(args)
+        }
+        Some("sweep") => {
+            args.next();
+            sweep(args)
+        }
+        Some("phase-transition") => {
+            args.next();
+            phase_transition(args)
         }
+        Some("orientation") => {
+            args.next();
+            orientation(args)
+        }
+        Some("locality") => {
+            args.next();
+            locality(args)
+        }
+        Some("rerun") => {
+            args.next();
+            rerun(args)
+        }
+        Some("fit") => {
+            args.next();
+            fit(args)
+        }
+        Some("paths") => {
+            args.next();
+            paths(args)
+        }
+        _ => sweep(args),
+    }
+}
 
-        let edges_codes = sample(rng, l * r, num_edges).into_vec();
-        for code in edges_codes {
-            let i = code / r;
-            let j = code % r;
-            left_nodes[i].neighbours.borrow_mut().push(j);
-            right_nodes[j].neighbours.borrow_mut().push(i);
+fn sweep(mut args: impl Iterator<Item = String>) {
+    let mut metric = Metric::Time;
+    let mut variant = Variant::Plain;
+    let mut options = RunOptions::default();
+    let mut reject_outliers = false;
+    let mut jobs = 1;
+    let mut metadata: Option<String> = None;
+    let mut format = String::from("csv");
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--metric" => metric = parse_value(&mut args),
+            "--variant" => variant = parse_value(&mut args),
+            "--timeout-per-instance" => {
+                let seconds: f64 = parse_value(&mut args);
+                options.timeout =
+                    Some(Duration::try_from_secs_f64(seconds).unwrap_or_else(|err| fail(err)));
+            }
+            "--warmup" => options.warmup = parse_value(&mut args),
+            "--reject-outliers" => reject_outliers = true,
+            "--drive" => options.drive = parse_value(&mut args),
+            "--relabel-every" => options.relabel_every = Some(parse_value(&mut args)),
+            "--deterministic" => options.deterministic = true,
+            "--layout" => options.layout = parse_value(&mut args),
+            "--trace" => options.trace = true,
+            "--jobs" => jobs = parse_value(&mut args),
+            "--metadata" => metadata = Some(parse_value(&mut args)),
+            "--format" => format = parse_value(&mut args),
+            _ => usage(),
+        }
+    }
+    if options.timeout.is_some() && !variant.is_observable() {
+        fail(format!(
+            "--timeout-per-instance cannot abort the {} variant",
+            variant.name()
+        ));
+    }
+    let parquet = match format.as_str() {
+        "csv" => false,
+        "parquet" if cfg!(feature = "parquet") => true,
+        "parquet" => fail("kuhn was built without the `parquet` feature"),
+        _ => fail(format!(
+            "unknown format `{format}` (expected csv or parquet)"
+        )),
+    };
+    // Parquet goes to stdout whole, so `--metadata -` moves into the file footer.
+    let mut footer = None;
+    match &metadata {
+        Some(dest) if parquet && dest == "-" => {
+            let variants = vec![variant.name().to_string()];
+            footer =
+                Some(RunMetadata::collect(env::args().collect(), variants, Some(SEED)).to_json());
         }
+        Some(dest) => write_metadata(dest, vec![variant.name().to_string()], Some(SEED)),
+        None => {}
+    }
+
+    let l = 10000;
+    let r = 10000;
+    let repetitions = 10;
+    let densities: Vec<usize> = ((l * r / 200)..(l * r / 20)).step_by(l * r / 200).collect();
+
+    let censored_column = if options.timeout.is_some() {
+        ",censored"
+    } else {
+        ""
+    };
+    let trace_columns = if options.trace {
+        format!(",{}", Trace::CSV_COLUMNS)
+    } else {
+        String::new()
+    };
+    let header = format!("{}{censored_column}{trace_columns}", metric.csv_header());
+    let mut rows = Vec::new();
+    if !parquet {
+        println!("{header}");
+    }
+    let mut group = Vec::with_capacity(repetitions);
+    let mut rejected = 0;
+    run_ordered(
+        densities.len() * repetitions,
+        jobs,
+        |cell| {
+            let n_edges = densities[cell / repetitions];
+            let config = GenConfig::builder()
+                .sizes(l, r)
+                .edges(n_edges)
+                .seed(cell_seed(SEED, cell))
+                .build()?;
+            test_graph_with_options(&mut config.solver_rng(), &config, variant, metric, &options)
+                .map(|sample| (n_edges, sample))
+        },
+        |cell, result| {
+            group.push(result.unwrap_or_else(|err| fail(err)));
+            if cell % repetitions != repetitions - 1 {
+                return;
+            }
 
-        for i in 0..left_nodes.len() {
-            left_nodes[i].neighbours.borrow_mut().sort();
+            let times: Vec<f64> = group
+                .iter()
+                .filter_map(|(_, sample)| sample.time.map(|t| t as f64))
+                .collect();
+            let outliers = if reject_outliers && times.len() == group.len() {
+                mad_outliers(&times, 3.5)
+            } else {
+                vec![false; group.len()]
+            };
+            for ((n_edges, sample), outlier) in group.drain(..).zip(outliers) {
+                let row = format!("{},{n_edges},{}", l * r, sample.csv_fields());
+                if outlier {
+                    rejected += 1;
+                } else if parquet {
+                    rows.push(row);
+                } else {
+                    println!("{row}");
+                }
+            }
+        },
+    );
+    if reject_outliers {
+        eprintln!("rejected {rejected} timing outliers");
+    }
+    if parquet {
+        write_parquet(&header, &rows, footer);
+    }
+}
+
+#[cfg(feature = "parquet")]
+fn write_parquet(header: &str, rows: &[String], metadata: Option<String>) {
+    use std::io::Write;
+
+    let mut table =
+        kuhn::parquet::Table::from_csv_rows(header, rows, &["rng", "instance"], &["seed"])
+            .unwrap_or_else(|err| fail(err));
+    if let Some(json) = metadata {
+        table.set_metadata("kuhn.run_metadata", &json);
+    }
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    table
+        .write(&mut out)
+        .and_then(|()| out.flush())
+        .unwrap_or_else(|err| fail(err));
+}
+
+#[cfg(not(feature = "parquet"))]
+fn write_parquet(_header: &str, _rows: &[String], _metadata: Option<String>) {
+    fail("kuhn was built without the `parquet` feature");
+}
+
+fn variance(mut args: impl Iterator<Item = String>) {
+    let mut runs = 30;
+    let (mut l, mut r, mut m) = (10000, 10000, 10000 * 10000 / 200);
+    let mut seed = SEED;
+    let mut metadata: Option<String> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--runs" => runs = parse_value(&mut args),
+            "--size" => {
+                l = parse_value(&mut args);
+                r = parse_value(&mut args);
+                m = parse_value(&mut args);
+            }
+            "--seed" => seed = parse_value(&mut args),
+            "--metadata" => metadata = Some(parse_value(&mut args)),
+            _ => usage(),
         }
+    }
+    if let Some(dest) = &metadata {
+        let variants = [Variant::Plain, Variant::Shuffle];
+        write_metadata(
+            dest,
+            variants.map(|v| v.name().to_string()).to_vec(),
+            Some(seed),
+        );
+    }
+
+    let config = GenConfig::builder()
+        .sizes(l, r)
+        .edges(m)
+        .seed(seed)
+        .build()
+        .unwrap_or_else(|err| fail(err));
+    let report =
+        variance_study(&mut config.solver_rng(), &config, runs).unwrap_or_else(|err| fail(err));
+    report.print_csv();
+}
 
-        for j in 0..right_nodes.len() {
-            right_nodes[j].neighbours.borrow_mut().sort();
+fn generate(mut args: impl Iterator<Item = String>) {
+    let mut builder = GenConfig::builder().sizes(10000, 10000).seed(SEED);
+    let mut sized = false;
+    let mut csr: Option<PathBuf> = None;
+    let mut every = 0;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => builder = builder.sizes(parse_value(&mut args), parse_value(&mut args)),
+            "--edges" => {
+                builder = builder.edges(parse_value(&mut args));
+                sized = true;
+            }
+            "--density" => {
+                builder = builder.density(parse_value(&mut args));
+                sized = true;
+            }
+            "--model" => builder = builder.model(parse_value::<GenModel>(&mut args)),
+            "--seed" => builder = builder.seed(parse_value(&mut args)),
+            "--csr" => csr = Some(parse_value(&mut args)),
+            "--stats-every" => every = parse_value(&mut args),
+            _ => usage(),
         }
+    }
+    if !sized {
+        builder = builder.density(0.005);
+    }
+    let config = builder.build().unwrap_or_else(|err| fail(err));
+
+    let progress = |stats: &GenStats| {
+        eprintln!(
+            "rows {}/{}, edges {}, isolated left {}, isolated right {}",
+            stats.rows,
+            config.left_len(),
+            stats.edges,
+            stats.isolated_left(),
+            stats.isolated_right()
+        )
+    };
+    let stats = match &csr {
+        Some(path) => config
+            .stream_csr(&mut config.rng(), path, every, progress)
+            .unwrap_or_else(|err| fail(err)),
+        None => config.stream_stats(&mut config.rng(), every, progress),
+    };
+    progress(&stats);
+
+    println!("degree,left,right");
+    for d in 0..stats.left_degrees.len().max(stats.right_degrees.len()) {
+        let count = |histogram: &[usize]| histogram.get(d).copied().unwrap_or(0);
+        println!(
+            "{d},{},{}",
+            count(&stats.left_degrees),
+            count(&stats.right_degrees)
+        );
+    }
+}
+
+/// Repeats one traced sweep cell in isolation, from the seed in its `seed` column.
+fn rerun(mut args: impl Iterator<Item = String>) {
+    let mut size = None;
+    let mut seed = None;
+    let mut metric = Metric::Time;
+    let mut variant = Variant::Plain;
+    let mut options = RunOptions {
+        trace: true,
+        ..RunOptions::default()
+    };
 
-        BipartiteGraph {
-            left_nodes,
-            right_nodes,
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => {
+                size = Some((
+                    parse_value(&mut args),
+                    parse_value(&mut args),
+                    parse_value::<usize>(&mut args),
+                ))
+            }
+            "--seed" => seed = Some(parse_value(&mut args)),
+            "--metric" => metric = parse_value(&mut args),
+            "--variant" => variant = parse_value(&mut args),
+            "--warmup" => options.warmup = parse_value(&mut args),
+            "--drive" => options.drive = parse_value(&mut args),
+            "--relabel-every" => options.relabel_every = Some(parse_value(&mut args)),
+            "--deterministic" => options.deterministic = true,
+            "--layout" => options.layout = parse_value(&mut args),
+            _ => usage(),
         }
     }
 
-    fn kuhn<const R: bool>(&self, rng: &mut StdRng) -> Vec<Option<usize>> {
-        let mut matched_right = vec![None; self.right_nodes.len()];
-        for v in 0..self.left_nodes.len() {
-            let mut used_left = vec![false; self.left_nodes.len()];
-            self.try_kuhn::<R>(rng, v, &mut matched_right, &mut used_left);
+    let ((l, r, m), seed) = size.zip(seed).unwrap_or_else(|| usage());
+    let config = GenConfig::builder()
+        .sizes(l, r)
+        .edges(m)
+        .seed(seed)
+        .build()
+        .unwrap_or_else(|err| fail(err));
+    let sample =
+        test_graph_with_options(&mut config.solver_rng(), &config, variant, metric, &options)
+            .unwrap_or_else(|err| fail(err));
+    println!("{},{}", metric.csv_header(), Trace::CSV_COLUMNS);
+    println!("{},{m},{}", l * r, sample.csv_fields());
+}
+
+fn fit(mut args: impl Iterator<Item = String>) {
+    let mut path = None;
+    let mut vertices = None;
+    let mut residuals = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--vertices" => vertices = Some(parse_value(&mut args)),
+            "--residuals" => residuals = true,
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(PathBuf::from(arg)),
+            _ => usage(),
         }
+    }
+
+    let path = path.unwrap_or_else(|| usage());
+    let file = open_input(&path).unwrap_or_else(|err| fail(err));
+    let points =
+        TimingPoint::read_csv(BufReader::new(file), vertices).unwrap_or_else(|err| fail(err));
+    let fits = fit_complexity(&points).unwrap_or_else(|err| fail(err));
 
-        matched_right
+    let best = &fits[0];
+    eprintln!(
+        "best fit over {} timings: {} * {}",
+        points.len(),
+        best.coefficient,
+        best.model.name()
+    );
+    if residuals {
+        println!("v,m,time,predicted,residual");
+        for (p, residual) in points.iter().zip(&best.residuals) {
+            let predicted = best.coefficient * best.model.eval(p.vertices, p.edges);
+            println!(
+                "{},{},{},{predicted},{residual}",
+                p.vertices, p.edges, p.time
+            );
+        }
+    } else {
+        println!("{}", ComplexityFit::CSV_HEADER);
+        for fit in &fits {
+            println!("{}", fit.csv_fields());
+        }
     }
+}
+
+fn orientation(mut args: impl Iterator<Item = String>) {
+    let mut variant = Variant::Plain;
+    let mut instances: Vec<(usize, usize, usize)> = Vec::new();
+    let mut seed = SEED;
+    let mut metadata: Option<String> = None;
 
-    fn try_kuhn<const R: bool>(
-        &self,
-        rng: &mut StdRng,
-        v: usize,
-        matched_right: &mut Vec<Option<usize>>,
-        used_left: &mut Vec<bool>,
-    ) -> bool {
-        if used_left[v] {
-            return false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--variant" => variant = parse_value(&mut args),
+            "--instance" => instances.push((
+                parse_value(&mut args),
+                parse_value(&mut args),
+                parse_value(&mut args),
+            )),
+            "--seed" => seed = parse_value(&mut args),
+            "--metadata" => metadata = Some(parse_value(&mut args)),
+            _ => usage(),
         }
+    }
+    if instances.is_empty() {
+        instances = vec![
+            (100, 100000, 500000),
+            (1000, 100000, 500000),
+            (10000, 10000, 500000),
+            (100000, 1000, 500000),
+            (100000, 100, 500000),
+        ];
+    }
+    if let Some(dest) = &metadata {
+        write_metadata(dest, vec![variant.name().to_string()], Some(seed));
+    }
 
-        used_left[v] = true;
+    println!("{}", OrientationRow::CSV_HEADER);
+    for (k, &(l, r, m)) in instances.iter().enumerate() {
+        let config = GenConfig::builder()
+            .sizes(l, r)
+            .edges(m)
+            .seed(cell_seed(seed, k))
+            .build()
+            .unwrap_or_else(|err| fail(err));
+        let row = compare_orientations(&mut config.solver_rng(), &config, variant)
+            .unwrap_or_else(|err| fail(err));
+        println!("{}", row.csv_fields());
+    }
+}
+
+fn locality(mut args: impl Iterator<Item = String>) {
+    let mut variant = Variant::Plain;
+    let mut instances: Vec<(usize, usize, usize)> = Vec::new();
+    let mut seed = SEED;
+    let mut metadata: Option<String> = None;
 
-        if R {
-            let mut neighbours = self.left_nodes[v].neighbours.borrow_mut();
-            (*neighbours).shuffle(rng);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--variant" => variant = parse_value(&mut args),
+            "--instance" => instances.push((
+                parse_value(&mut args),
+                parse_value(&mut args),
+                parse_value(&mut args),
+            )),
+            "--seed" => seed = parse_value(&mut args),
+            "--metadata" => metadata = Some(parse_value(&mut args)),
+            _ => usage(),
         }
+    }
+    if instances.is_empty() {
+        instances = vec![
+            (10000, 10000, 50000),
+            (100000, 100000, 500000),
+            (1000000, 1000000, 10000000),
+        ];
+    }
+    if let Some(dest) = &metadata {
+        write_metadata(dest, vec![variant.name().to_string()], Some(seed));
+    }
 
-        let neighbours = self.left_nodes[v].neighbours.borrow();
-        for &to in &*neighbours {
-            if matched_right[to].is_none()
-                || self.try_kuhn::<R>(rng, matched_right[to].unwrap(), matched_right, used_left)
-            {
-                matched_right[to] = Some(v);
-                return true;
+    println!("{}", LocalityRow::CSV_HEADER);
+    for (k, &(l, r, m)) in instances.iter().enumerate() {
+        let config = GenConfig::builder()
+            .sizes(l, r)
+            .edges(m)
+            .seed(cell_seed(seed, k))
+            .build()
+            .unwrap_or_else(|err| fail(err));
+        let row = compare_layouts(&mut config.solver_rng(), &config, variant)
+            .unwrap_or_else(|err| fail(err));
+        println!("{}", row.csv_fields());
+    }
+}
+
+fn phase_transition(mut args: impl Iterator<Item = String>) {
+    let mut n = 1000;
+    let (mut min, mut max, mut step): (f64, f64, f64) = (0.5, 5.0, 0.25);
+    let mut trials = 50;
+    let mut seed = SEED;
+    let mut jobs = 1;
+    let mut metadata: Option<String> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => n = parse_value(&mut args),
+            "--degrees" => {
+                min = parse_value(&mut args);
+                max = parse_value(&mut args);
+                step = parse_value(&mut args);
             }
+            "--trials" => trials = parse_value(&mut args),
+            "--seed" => seed = parse_value(&mut args),
+            "--jobs" => jobs = parse_value(&mut args),
+            "--metadata" => metadata = Some(parse_value(&mut args)),
+            _ => usage(),
         }
+    }
+    if let Some(dest) = &metadata {
+        let variants = vec![Variant::HopcroftKarp.name().to_string()];
+        write_metadata(dest, variants, Some(seed));
+    }
+    if step.is_nan() || step <= 0.0 || min > max {
+        fail("--degrees needs MIN <= MAX and a positive STEP");
+    }
+
+    let degrees: Vec<f64> = (0..)
+        .map(|k| min + k as f64 * step)
+        .take_while(|&degree| degree <= max + step * 1e-9)
+        .collect();
+    let rows =
+        bench::phase_transition(seed, n, &degrees, trials, jobs).unwrap_or_else(|err| fail(err));
 
-        false
+    println!("{}", TransitionRow::CSV_HEADER);
+    for row in rows {
+        println!("{}", row.csv_fields());
     }
+}
 
-    fn print_as_dot(&self, matched_right: &[Option<usize>]) {
-        println!("digraph A {{");
-        println!("\trankdir=LR");
-        println!("\tsplines=false");
+fn paths(mut args: impl Iterator<Item = String>) {
+    let mut variant = Variant::Plain;
+    let (mut l, mut r) = (1000, 1000);
+    let mut range: Option<(usize, usize, usize)> = None;
+    let mut repetitions = 10;
+    let mut seed = SEED;
+    let mut jobs = 1;
+    let mut metadata: Option<String> = None;
 
-        println!("\tsubgraph cluster1 {{");
-        println!("\t\tmargin=30");
-        println!("\t\tstyle=invis");
-        for i in 0..self.left_nodes.len() {
-            println!("\t\tA{i}");
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--variant" => variant = parse_value(&mut args),
+            "--size" => {
+                l = parse_value(&mut args);
+                r = parse_value(&mut args);
+            }
+            "--edges" => {
+                range = Some((
+                    parse_value(&mut args),
+                    parse_value(&mut args),
+                    parse_value(&mut args),
+                ))
+            }
+            "--repetitions" => repetitions = parse_value(&mut args),
+            "--seed" => seed = parse_value(&mut args),
+            "--jobs" => jobs = parse_value(&mut args),
+            "--metadata" => metadata = Some(parse_value(&mut args)),
+            _ => usage(),
         }
-        println!("\t}}");
-        println!("\tsubgraph cluster2 {{");
-        println!("\t\tmargin=30");
-        println!("\t\tstyle=invis");
-        for i in 0..self.right_nodes.len() {
-            println!("\t\tB{i}");
+    }
+    if let Some(dest) = &metadata {
+        write_metadata(dest, vec![variant.name().to_string()], Some(seed));
+    }
+
+    // The densities of the plain sweep, scaled to the chosen sides.
+    let (min, max, step) = range.unwrap_or((l * r / 200, l * r / 20, (l * r / 200).max(1)));
+    if step == 0 || min > max {
+        fail("--edges needs MIN <= MAX and a positive STEP");
+    }
+    let edges: Vec<usize> = (min..=max).step_by(step).collect();
+    let rows = bench::path_length_histograms(seed, (l, r), &edges, repetitions, variant, jobs)
+        .unwrap_or_else(|err| fail(err));
+
+    println!("{}", PathLengthRow::CSV_HEADER);
+    for row in rows {
+        println!("{}", row.csv_fields());
+    }
+}
+
+/// `-` prefixes stdout with the record as a `#` comment line; anything else is a sidecar path.
+fn write_metadata(dest: &str, variants: Vec<String>, seed: Option<u64>) {
+    let json = RunMetadata::collect(env::args().collect(), variants, seed).to_json();
+    if dest == "-" {
+        println!("# {json}");
+    } else {
+        fs::write(dest, json + "\n").unwrap_or_else(|err| fail(err));
+    }
+}
+
+fn solve(mut args: impl Iterator<Item = String>) {
+    let mut path = None;
+    let mut parallel_components = false;
+    let mut threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let mut labeled = false;
+    let mut format = String::from("csv");
+    let mut backup: Option<PathBuf> = None;
+    let mut arrivals: Option<PathBuf> = None;
+    let mut size_only = false;
+    let mut perfect = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size-only" => size_only = true,
+            "--perfect" => perfect = true,
+            "--parallel-components" => parallel_components = true,
+            "--backup" => backup = Some(parse_value(&mut args)),
+            "--arrivals" => arrivals = Some(parse_value(&mut args)),
+            "--threads" => threads = parse_value(&mut args),
+            "--labeled" => labeled = true,
+            "--format" => format = parse_value(&mut args),
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(PathBuf::from(arg)),
+            _ => usage(),
         }
-        println!("\t}}");
+    }
 
-        for i in 0..self.right_nodes.len() {
-            if let Some(val) = matched_right[i] {
-                let neighbours = self.right_nodes[i].neighbours.borrow();
-                for &j in &*neighbours {
-                    if j == val {
-                        println!("\t\tB{i} -> A{j} [arrowhead=none,color=red]")
-                    } else {
-                        println!("\t\tB{i} -> A{j} [arrowhead=none]");
-                    }
-                }
-            } else {
-                let neighbours = self.right_nodes[i].neighbours.borrow();
-                for j in &*neighbours {
-                    println!("\t\tB{i} -> A{j} [arrowhead=none]");
-                }
+    let path = path.unwrap_or_else(|| usage());
+    let extension = input_extension(&path);
+    if extension.is_some_and(|ext| ["g6", "s6", "graph6", "sparse6"].iter().any(|e| ext == *e)) {
+        return solve_graph6(&path);
+    }
+    if extension.is_some_and(|ext| ext == "metis" || ext == "graph") {
+        return solve_loaded(BipartiteGraph::load_metis(&path, None));
+    }
+    if extension.is_some_and(|ext| ext == "dimacs" || ext == "asn") {
+        return solve_loaded(BipartiteGraph::load_dimacs(&path));
+    }
+    if extension.is_some_and(|ext| ext == "mtx") {
+        return solve_loaded(BipartiteGraph::load_matrix_market(&path));
+    }
+    if labeled {
+        return solve_labeled(&path, parallel_components, threads, &format);
+    }
+    if let Some(backup) = backup {
+        return solve_staged(&path, &backup);
+    }
+    if let Some(arrivals) = arrivals {
+        return solve_arrivals(&path, &arrivals);
+    }
+
+    let graph = CsrGraph::load_csv(&path).unwrap_or_else(|err| fail(err));
+    if size_only {
+        println!("{}", maximum_matching_size(&graph));
+        return;
+    }
+    let matching = if perfect {
+        perfect_matching(&graph)
+            .unwrap_or_else(|reason| fail(format!("no perfect matching: {reason}")))
+    } else if parallel_components {
+        solve_components_parallel(&graph, threads)
+    } else {
+        hopcroft_karp(&graph)
+    };
+
+    println!("left,right");
+    for (i, j) in matching.as_coo() {
+        println!("{i},{j}");
+    }
+}
+
+fn solve_labeled(path: &Path, parallel_components: bool, threads: usize, format: &str) {
+    let graph = BipartiteGraph::<String>::load_labeled_csv(path, Duplicates::Keep)
+        .unwrap_or_else(|err| fail(err));
+    let matching = if parallel_components {
+        solve_components_parallel(&graph.to_csr(), threads)
+    } else {
+        hopcroft_karp(&graph)
+    };
+
+    match format {
+        "csv" => matching
+            .write_labeled_csv(&mut io::stdout().lock(), &graph)
+            .unwrap_or_else(|err| fail(err)),
+        "json" => println!(
+            "{}",
+            matching
+                .to_json_labeled(&graph)
+                .unwrap_or_else(|err| fail(err))
+        ),
+        _ => fail(format!("unknown format `{format}` (expected csv or json)")),
+    }
+}
+
+fn solve_loaded(graph: kuhn::Result<BipartiteGraph<()>>) {
+    let graph = graph.unwrap_or_else(|err| fail(err));
+    println!("left,right");
+    for (i, j) in hopcroft_karp(&graph).as_coo() {
+        println!("{i},{j}");
+    }
+}
+
+fn solve_arrivals(path: &Path, arrivals: &Path) {
+    let graph = BipartiteGraph::<()>::load_csv(path).unwrap_or_else(|err| fail(err));
+    let arrivals = ArrivalOrder::load(arrivals, graph.left_len()).unwrap_or_else(|err| fail(err));
+    let matching = graph.kuhn_in_order(&arrivals);
+
+    eprintln!("{} arrivals, matched {}", arrivals.len(), matching.len());
+    println!("left,right");
+    for (i, j) in matching.as_coo() {
+        println!("{i},{j}");
+    }
+}
+
+fn solve_staged(path: &Path, backup: &Path) {
+    let load = |path: &Path| BipartiteGraph::<()>::load_csv(path).unwrap_or_else(|err| fail(err));
+    let (preferred, backup) = (load(path), load(backup));
+
+    let mut types = EdgeTypes::new();
+    for (i, j) in backup.edges() {
+        types.set(i, j, EdgeType::Backup);
+    }
+    for (i, j) in preferred.edges() {
+        types.set(i, j, EdgeType::Preferred);
+    }
+    let graph = BipartiteGraph::<()>::from_edges(
+        preferred.left_len().max(backup.left_len()),
+        preferred.right_len().max(backup.right_len()),
+        preferred.edges().into_iter().chain(backup.edges()),
+    )
+    .unwrap_or_else(|err| fail(err));
+    let staged = graph.staged_matching(&types);
+
+    eprintln!(
+        "preferred-only {}, matched {}, backup edges used {}",
+        staged.preferred_only,
+        staged.matching.len(),
+        staged.backup_edges
+    );
+    println!("left,right,type");
+    for (i, j) in staged.matching.as_coo() {
+        let edge_type = match types.get(i, j) {
+            EdgeType::Preferred => "preferred",
+            EdgeType::Backup => "backup",
+        };
+        println!("{i},{j},{edge_type}");
+    }
+}
+
+fn bounds(args: impl Iterator<Item = String>) {
+    let paths: Vec<PathBuf> = args.map(PathBuf::from).collect();
+    if paths.is_empty() {
+        usage();
+    }
+
+    println!("instance,lower,upper");
+    for path in paths {
+        let graph = CsrGraph::load_csv(&path).unwrap_or_else(|err| fail(err));
+        let bounds = estimate_matching_bounds(&graph);
+        println!("{},{},{}", path.display(), bounds.lower, bounds.upper);
+    }
+}
+
+fn assign(mut args: impl Iterator<Item = String>) {
+    let mut path = None;
+    let mut duals_path = None;
+    let mut compare = false;
+    let mut fairness = None;
+    let mut weights = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--duals" => duals_path = Some(parse_value::<PathBuf>(&mut args)),
+            "--compare" => compare = true,
+            "--fairness" => fairness = Some(parse_value::<PathBuf>(&mut args)),
+            "--weights" => weights = Some((parse_value(&mut args), parse_value(&mut args))),
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(PathBuf::from(arg)),
+            _ => usage(),
+        }
+    }
+
+    let path = path.unwrap_or_else(|| usage());
+    let costs = CostMatrix::load(&path).unwrap_or_else(|err| fail(err));
+    if compare {
+        return compare_solvers(&costs);
+    }
+    if let Some(fairness) = fairness {
+        let fairness = CostMatrix::load(&fairness).unwrap_or_else(|err| fail(err));
+        let objectives = BiObjective::new(costs, fairness).unwrap_or_else(|err| fail(err));
+        return trade_off(&objectives, weights);
+    }
+    let (matching, cost, duals) = costs
+        .min_cost_assignment_with_duals()
+        .unwrap_or_else(|err| fail(err));
+
+    eprintln!("total cost {cost}");
+    if let Some(duals_path) = duals_path {
+        if !costs.satisfies_complementary_slackness(&matching, &duals) {
+            fail("dual prices violate complementary slackness");
+        }
+        let mut out = String::from("side,index,potential\n");
+        for (i, p) in duals.row_potentials.iter().enumerate() {
+            out.push_str(&format!("row,{i},{p}\n"));
+        }
+        for (j, p) in duals.col_potentials.iter().enumerate() {
+            out.push_str(&format!("col,{j},{p}\n"));
+        }
+        fs::write(&duals_path, out).unwrap_or_else(|err| fail(err));
+    }
+    println!("row,col");
+    for (i, j) in matching.as_coo() {
+        println!("{i},{j}");
+    }
+}
+
+fn trade_off(objectives: &BiObjective, weights: Option<(i64, i64)>) {
+    if let Some((cost_weight, fairness_weight)) = weights {
+        let point = objectives
+            .scalarized(cost_weight, fairness_weight)
+            .unwrap_or_else(|err| fail(err));
+        eprintln!(
+            "total cost {}, total fairness {}",
+            point.cost, point.fairness
+        );
+        println!("row,col");
+        for (i, j) in point.matching.as_coo() {
+            println!("{i},{j}");
+        }
+        return;
+    }
+    let frontier = objectives.pareto_frontier().unwrap_or_else(|err| fail(err));
+    eprintln!("{} points on the Pareto frontier", frontier.len());
+    println!("{}", ParetoPoint::CSV_HEADER);
+    for point in &frontier {
+        println!("{}", point.csv_fields());
+    }
+}
+
+fn compare_solvers(costs: &CostMatrix) {
+    let solvers: [&dyn AssignmentSolver; 4] = [
+        &Hungarian,
+        &Greedy,
+        &LocalSearch::default(),
+        &Annealing::default(),
+    ];
+
+    let mut optimum = None;
+    println!("solver,cost,gap,time");
+    for solver in solvers {
+        let start = Instant::now();
+        let (_matching, cost) = solver.solve(costs).unwrap_or_else(|err| fail(err));
+        let time = start.elapsed().as_nanos();
+        let optimum = *optimum.get_or_insert(cost);
+        let gap = i128::from(cost) - i128::from(optimum);
+        println!("{},{cost},{gap},{time}", solver.name());
+    }
+}
+
+fn render(mut args: impl Iterator<Item = String>) {
+    let mut path = None;
+    let mut deterministic = false;
+    let mut seed = SEED;
+    let mut frames = None;
+    let mut save_matching = None;
+    let mut layout = DotLayout::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--deterministic" => deterministic = true,
+            "--same-rank" => layout.same_rank = true,
+            "--order" => layout.order = parse_value(&mut args),
+            "--seed" => seed = parse_value(&mut args),
+            "--frames" => frames = Some(parse_value::<PathBuf>(&mut args)),
+            "--save-matching" => save_matching = Some(parse_value::<PathBuf>(&mut args)),
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(PathBuf::from(arg)),
+            _ => usage(),
+        }
+    }
+
+    let path = path.unwrap_or_else(|| usage());
+    let graph = BipartiteGraph::<()>::load_csv(&path).unwrap_or_else(|err| fail(err));
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let matching = match (&frames, deterministic) {
+        (Some(dir), true) => {
+            graph.sort_neighbours();
+            graph.write_dot_frames_with_layout::<false>(&mut rng, dir, layout)
+        }
+        (Some(dir), false) => graph.write_dot_frames_with_layout::<true>(&mut rng, dir, layout),
+        (None, true) => Ok(graph.kuhn_deterministic()),
+        (None, false) => Ok(graph.kuhn::<true>(&mut rng)),
+    }
+    .unwrap_or_else(|err| fail(err));
+
+    if frames.is_none() {
+        graph
+            .write_dot_with_layout(&mut io::stdout().lock(), &matching, layout)
+            .unwrap_or_else(|err| fail(err));
+    }
+    if let Some(out) = save_matching {
+        matching.save(&out).unwrap_or_else(|err| fail(err));
+    }
+}
+
+fn robustness(mut args: impl Iterator<Item = String>) {
+    let mut path = None;
+    let mut sample = None;
+    let mut seed = SEED;
+    let mut edges = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--sample" => sample = Some(parse_value(&mut args)),
+            "--seed" => seed = parse_value(&mut args),
+            "--edges" => edges = true,
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(PathBuf::from(arg)),
+            _ => usage(),
+        }
+    }
+
+    let path = path.unwrap_or_else(|| usage());
+    let graph = BipartiteGraph::<()>::load_csv(&path).unwrap_or_else(|err| fail(err));
+    if edges {
+        let criticality = graph.edge_criticality();
+        eprintln!(
+            "baseline {}, {} critical edges",
+            criticality.matching.len(),
+            criticality.critical.len()
+        );
+        println!("left,right");
+        for (i, j) in &criticality.critical {
+            println!("{i},{j}");
+        }
+        return;
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let report = graph.vertex_failures(&mut rng, sample);
+
+    eprintln!(
+        "baseline {}, mean drop {:.4}, drop histogram {:?}",
+        report.baseline,
+        report.mean_drop(),
+        report.drop_histogram()
+    );
+    println!("side,vertex,drop");
+    for failure in &report.failures {
+        let side = match failure.side {
+            Side::Left => "left",
+            Side::Right => "right",
+        };
+        println!("{side},{},{}", failure.vertex, failure.drop);
+    }
+}
+
+fn orient(mut args: impl Iterator<Item = String>) {
+    let mut path = None;
+    let mut matching = None;
+    let mut dot = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--matching" => matching = Some(parse_value::<PathBuf>(&mut args)),
+            "--dot" => dot = true,
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(PathBuf::from(arg)),
+            _ => usage(),
+        }
+    }
+
+    let path = path.unwrap_or_else(|| usage());
+    let graph = BipartiteGraph::<()>::load_csv(&path).unwrap_or_else(|err| fail(err));
+    let matching = match matching {
+        Some(path) => Matching::load(&path).unwrap_or_else(|err| fail(err)),
+        None => hopcroft_karp(&graph),
+    };
+    let orientation = graph
+        .alternating_orientation(&matching)
+        .unwrap_or_else(|err| fail(err));
+
+    let mut out = io::stdout().lock();
+    if dot {
+        orientation.write_dot(&mut out)
+    } else {
+        orientation.write_edge_list(&mut out)
+    }
+    .unwrap_or_else(|err| fail(err));
+}
+
+fn schedule(mut args: impl Iterator<Item = String>) {
+    let (Some(tasks), Some(slots), None) = (args.next(), args.next(), args.next()) else {
+        usage()
+    };
+    let load = |path: String| TimeWindow::load(Path::new(&path)).unwrap_or_else(|err| fail(err));
+    let (tasks, slots) = (load(tasks), load(slots));
+    let schedule = schedule_intervals(&tasks, &slots);
+
+    eprintln!(
+        "scheduled {} of {} tasks in {} slots",
+        schedule.matching.len(),
+        tasks.len(),
+        slots.len()
+    );
+    println!("task,slot");
+    for task in 0..tasks.len() {
+        match schedule.slot_of(task) {
+            Some(slot) => println!("{task},{slot}"),
+            None => println!("{task},"),
+        }
+    }
+}
+
+fn sample(mut args: impl Iterator<Item = String>) {
+    let mut path = None;
+    let mut count = 1;
+    let mut iterations = None;
+    let mut seed = SEED;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--count" => count = parse_value(&mut args),
+            "--iterations" => iterations = Some(parse_value(&mut args)),
+            "--seed" => seed = parse_value(&mut args),
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(PathBuf::from(arg)),
+            _ => usage(),
+        }
+    }
+
+    let path = path.unwrap_or_else(|| usage());
+    let graph = BipartiteGraph::<()>::load_csv(&path).unwrap_or_else(|err| fail(err));
+    let mut chain = MatchingChain::new(&graph);
+    let iterations = iterations.unwrap_or(10 * graph.num_edges());
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    eprintln!(
+        "maximum size {}, {iterations} steps between samples",
+        chain.maximum_size()
+    );
+    println!("sample,left,right");
+    for k in 0..count {
+        for (i, j) in chain.sample(&mut rng, iterations).as_coo() {
+            println!("{k},{i},{j}");
+        }
+    }
+}
+
+fn relabel(mut args: impl Iterator<Item = String>) {
+    let mut path = None;
+    let mut seed = SEED;
+    let mut mapping: Option<PathBuf> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => seed = parse_value(&mut args),
+            "--mapping" => mapping = Some(parse_value(&mut args)),
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(PathBuf::from(arg)),
+            _ => usage(),
+        }
+    }
+
+    let path = path.unwrap_or_else(|| usage());
+    let graph = BipartiteGraph::<()>::load_csv(&path).unwrap_or_else(|err| fail(err));
+    let (relabeled, relabeling) = graph.relabel_random(&mut StdRng::seed_from_u64(seed));
+
+    if let Some(mapping) = mapping {
+        let mut lines = vec!["side,old,new".to_string()];
+        for (side, permutation) in [("left", &relabeling.left), ("right", &relabeling.right)] {
+            for (old, new) in permutation.iter().enumerate() {
+                lines.push(format!("{side},{old},{new}"));
             }
         }
+        lines.push(String::new());
+        fs::write(mapping, lines.join("\n")).unwrap_or_else(|err| fail(err));
+    }
+    relabeled
+        .write_csv(&mut io::stdout().lock())
+        .unwrap_or_else(|err| fail(err));
+}
+
+fn solve_graph6(path: &Path) {
+    let file = open_input(path).unwrap_or_else(|err| fail(err));
+    let graphs = SimpleGraph::read_all(BufReader::new(file)).unwrap_or_else(|err| fail(err));
+
+    println!("graph,u,v");
+    for (k, graph) in graphs.iter().enumerate() {
+        let (bipartite, partition) = graph
+            .to_bipartite::<()>()
+            .unwrap_or_else(|err| fail(format!("graph {k}: {err}")));
+        for (i, j) in hopcroft_karp(&bipartite).as_coo() {
+            println!("{k},{},{}", partition.left[i], partition.right[j]);
+        }
+    }
+}
 
-        println!("}}");
+#[cfg(feature = "server")]
+fn serve(mut args: impl Iterator<Item = String>) {
+    let mut bind = "127.0.0.1".to_string();
+    let mut port = 8080;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bind" => bind = parse_value(&mut args),
+            "--port" => port = parse_value(&mut args),
+            _ => usage(),
+        }
     }
+
+    eprintln!("listening on {bind}:{port}");
+    kuhn::server::serve(&bind, port).unwrap_or_else(|err| fail(err));
 }
 
-struct Node<T> {
-    data: T,
-    neighbours: RefCell<Vec<usize>>,
+#[cfg(not(feature = "server"))]
+fn serve(_args: impl Iterator<Item = String>) {
+    fail("kuhn was built without the `server` feature");
 }
 
-fn test_graph<const R: bool>(rng: &mut StdRng, l: usize, r: usize, edges: usize) -> u128 {
-    let graph = BipartiteGraph::<()>::random(rng, l, r, edges);
+#[cfg(all(unix, feature = "tui"))]
+fn inspect(mut args: impl Iterator<Item = String>) {
+    let path = match (args.next(), args.next()) {
+        (Some(path), None) if !path.starts_with("--") => PathBuf::from(path),
+        _ => usage(),
+    };
+    let graph = BipartiteGraph::<()>::load_csv(&path).unwrap_or_else(|err| fail(err));
+    kuhn::tui::inspect(&graph).unwrap_or_else(|err| fail(err));
+}
 
-    let start = Instant::now();
-    let _matched = graph.kuhn::<R>(rng);
-    start.elapsed().as_nanos()
+#[cfg(not(all(unix, feature = "tui")))]
+fn inspect(_args: impl Iterator<Item = String>) {
+    fail("kuhn was built without the `tui` feature");
 }
 
-fn main() {
-    let mut rng = StdRng::seed_from_u64(131254153212);
+fn report(mut args: impl Iterator<Item = String>) {
+    let mut format = ReportFormat::Markdown;
+    let mut seed = SEED;
+    let mut sizes = Vec::new();
+    let mut files = Vec::new();
 
-    let l = 10000;
-    let r = 10000;
-    println!("n,m,time");
-    for n_edges in ((l * r / 200)..(l * r / 20)).step_by(l * r / 200) {
-        for _ in 0..10 {
-            let time = test_graph::<false>(&mut rng, l, r, n_edges);
-            println!("{},{n_edges},{time}", l * r);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => format = parse_value(&mut args),
+            "--seed" => seed = parse_value(&mut args),
+            "--instance" => sizes.push((
+                parse_value(&mut args),
+                parse_value(&mut args),
+                parse_value(&mut args),
+            )),
+            _ if !arg.starts_with("--") => files.push(PathBuf::from(arg)),
+            _ => usage(),
         }
     }
+    if sizes.is_empty() && files.is_empty() {
+        sizes = vec![(1000, 1000, 5000), (1000, 1000, 20000), (5000, 5000, 25000)];
+    }
+
+    // Each generated instance has its own cell seed, and its solves draw from that cell's
+    // solver stream, so adding a variant or an instance leaves the other graphs unchanged.
+    // Loaded files have no generator stream, so their solves take the cell seed directly.
+    let mut instances = Vec::new();
+    for (k, (l, r, m)) in sizes.into_iter().enumerate() {
+        let config = GenConfig::builder()
+            .sizes(l, r)
+            .edges(m)
+            .seed(cell_seed(seed, k))
+            .build()
+            .unwrap_or_else(|err| fail(err));
+        let graph = config
+            .graph::<()>(&mut config.rng())
+            .unwrap_or_else(|err| fail(err));
+        instances.push((format!("random {l}x{r} m={m}"), graph, config.solver_rng()));
+    }
+    for path in files {
+        let graph = BipartiteGraph::<()>::load_csv(&path).unwrap_or_else(|err| fail(err));
+        let rng = StdRng::seed_from_u64(cell_seed(seed, instances.len()));
+        instances.push((path.display().to_string(), graph, rng));
+    }
+
+    let mut rows = Vec::new();
+    for (name, graph, mut rng) in instances {
+        rows.extend(run_report(&[(name, graph)], &Variant::ALL, &mut rng));
+    }
+    write_report(&mut io::stdout().lock(), &rows, format).unwrap_or_else(|err| fail(err));
 }
@@ -0,0 +1,365 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::graph::BipartiteGraph;
+use crate::input::open_input;
+use crate::metadata::json_string;
+
+/// Overlap between two matchings of the same graph, from [`Matching::similarity`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MatchingSimilarity {
+    pub shared_edges: usize,
+    /// Edges in exactly one of the two matchings.
+    pub differing_edges: usize,
+    /// Vertices whose partner differs, counting matched against unmatched.
+    pub changed_left: usize,
+    pub changed_right: usize,
+}
+
+impl MatchingSimilarity {
+    /// Shared edges over the union of both edge sets; 1 when both matchings are empty.
+    pub fn jaccard(&self) -> f64 {
+        let union = self.shared_edges + self.differing_edges;
+        if union == 0 {
+            1.0
+        } else {
+            self.shared_edges as f64 / union as f64
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Matching {
+    matched_left: Vec<Option<usize>>,
+    matched_right: Vec<Option<usize>>,
+}
+
+impl Matching {
+    pub fn empty(left_len: usize, right_len: usize) -> Matching {
+        Matching {
+            matched_left: vec![None; left_len],
+            matched_right: vec![None; right_len],
+        }
+    }
+
+    /// Builds a matching from the partner of every right vertex, rejecting a left vertex out
+    /// of range or matched twice.
+    pub fn try_from_matched_right(
+        left_len: usize,
+        matched_right: Vec<Option<usize>>,
+    ) -> Result<Matching> {
+        let mut matched_left = vec![None; left_len];
+        for (j, i) in matched_right.iter().enumerate() {
+            let Some(i) = *i else { continue };
+            match matched_left.get_mut(i) {
+                None => {
+                    return Err(Error::IndexOutOfRange {
+                        index: i,
+                        len: left_len,
+                    })
+                }
+                Some(Some(k)) => {
+                    return Err(Error::InvalidParameter(format!(
+                        "left vertex {i} is matched to right vertices {k} and {j}"
+                    )))
+                }
+                Some(slot) => *slot = Some(j),
+            }
+        }
+        Ok(Matching {
+            matched_left,
+            matched_right,
+        })
+    }
+
+    /// Like [`Matching::try_from_matched_right`] for solvers whose output is valid by
+    /// construction; panics on a left vertex out of range.
+    pub(crate) fn from_matched_right(left_len: usize, matched_right: Vec<Option<usize>>) -> Matching {
+        let mut matched_left = vec![None; left_len];
+        for (j, i) in matched_right.iter().enumerate() {
+            if let Some(i) = *i {
+                matched_left[i] = Some(j);
+            }
+        }
+        Matching {
+            matched_left,
+            matched_right,
+        }
+    }
+
+    pub(crate) fn from_parts(
+        matched_left: Vec<Option<usize>>,
+        matched_right: Vec<Option<usize>>,
+    ) -> Matching {
+        Matching {
+            matched_left,
+            matched_right,
+        }
+    }
+
+    pub fn matched_left(&self) -> &[Option<usize>] {
+        &self.matched_left
+    }
+
+    pub fn matched_right(&self) -> &[Option<usize>] {
+        &self.matched_right
+    }
+
+    pub fn match_of_left(&self, i: usize) -> Option<usize> {
+        self.matched_left.get(i).copied().flatten()
+    }
+
+    pub fn match_of_right(&self, j: usize) -> Option<usize> {
+        self.matched_right.get(j).copied().flatten()
+    }
+
+    pub fn into_matched_right(self) -> Vec<Option<usize>> {
+        self.matched_right
+    }
+
+    pub fn len(&self) -> usize {
+        self.matched_right.iter().filter(|m| m.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matched_right.iter().all(|m| m.is_none())
+    }
+
+    pub fn pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.matched_right
+            .iter()
+            .enumerate()
+            .filter_map(|(j, m)| m.map(|i| (i, j)))
+    }
+
+    /// The matching of the transposed graph.
+    pub fn transpose(&self) -> Matching {
+        Matching::from_parts(self.matched_right.clone(), self.matched_left.clone())
+    }
+
+    /// Compares against `other` vertex by vertex; sides of different sizes behave as if the
+    /// shorter one were padded with unmatched vertices.
+    pub fn similarity(&self, other: &Matching) -> MatchingSimilarity {
+        let changed = |a: &[Option<usize>], b: &[Option<usize>]| {
+            (0..a.len().max(b.len()))
+                .filter(|&v| a.get(v).copied().flatten() != b.get(v).copied().flatten())
+                .count()
+        };
+        let shared_edges = self
+            .pairs()
+            .filter(|&(i, j)| other.match_of_right(j) == Some(i))
+            .count();
+        MatchingSimilarity {
+            shared_edges,
+            differing_edges: self.len() + other.len() - 2 * shared_edges,
+            changed_left: changed(&self.matched_left, &other.matched_left),
+            changed_right: changed(&self.matched_right, &other.matched_right),
+        }
+    }
+
+    pub fn as_permutation(&self) -> Option<Vec<usize>> {
+        let n = self.matched_right.len();
+        if self.matched_left.len() != n {
+            return None;
+        }
+        let mut permutation = vec![usize::MAX; n];
+        for (j, m) in self.matched_right.iter().enumerate() {
+            let i = (*m)?;
+            if i >= n || permutation[i] != usize::MAX {
+                return None;
+            }
+            permutation[i] = j;
+        }
+
+        Some(permutation)
+    }
+
+    pub fn as_coo(&self) -> Vec<(usize, usize)> {
+        let mut coo: Vec<(usize, usize)> = self.pairs().collect();
+        coo.sort_unstable();
+        coo
+    }
+
+    pub fn to_json(&self) -> String {
+        let pairs: Vec<String> = self
+            .as_coo()
+            .iter()
+            .map(|(i, j)| format!("[{i},{j}]"))
+            .collect();
+        format!(
+            "{{\"size\":{},\"pairs\":[{}]}}",
+            pairs.len(),
+            pairs.join(",")
+        )
+    }
+
+    /// JSON object mapping each matched left vertex's data to its partner's.
+    pub fn to_json_labeled<T: Display>(&self, graph: &BipartiteGraph<T>) -> Result<String> {
+        let pairs: Vec<String> = self
+            .labeled_pairs(graph)?
+            .iter()
+            .map(|(left, right)| format!("{}:{}", json_string(left), json_string(right)))
+            .collect();
+        Ok(format!(
+            "{{\"size\":{},\"pairs\":{{{}}}}}",
+            pairs.len(),
+            pairs.join(",")
+        ))
+    }
+
+    pub fn write_labeled_csv<W: Write, T: Display>(
+        &self,
+        w: &mut W,
+        graph: &BipartiteGraph<T>,
+    ) -> Result<()> {
+        writeln!(w, "left,right")?;
+        for (left, right) in self.labeled_pairs(graph)? {
+            writeln!(w, "{left},{right}")?;
+        }
+        Ok(())
+    }
+
+    fn labeled_pairs<T: Display>(
+        &self,
+        graph: &BipartiteGraph<T>,
+    ) -> Result<Vec<(String, String)>> {
+        self.as_coo()
+            .into_iter()
+            .map(|(i, j)| match (graph.left_data(i), graph.right_data(j)) {
+                (Some(left), Some(right)) => Ok((left.to_string(), right.to_string())),
+                _ => Err(Error::InvalidMatching),
+            })
+            .collect()
+    }
+
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(
+            w,
+            "{} {}",
+            self.matched_left.len(),
+            self.matched_right.len()
+        )?;
+        for (i, j) in self.pairs() {
+            writeln!(w, "{i} {j}")?;
+        }
+        Ok(())
+    }
+
+    pub fn read_from<R: BufRead>(r: R) -> Result<Matching> {
+        let mut lines = r.lines();
+        let header = lines.next().ok_or_else(|| Error::ParseError {
+            line: 1,
+            message: "missing matching header".to_string(),
+        })??;
+        let sizes: Vec<&str> = header.split_whitespace().collect();
+        let (left_len, right_len) = match sizes.as_slice() {
+            [right] => (None, parse_index(right, 1)?),
+            [left, right] => (Some(parse_index(left, 1)?), parse_index(right, 1)?),
+            _ => {
+                return Err(Error::ParseError {
+                    line: 1,
+                    message: format!("malformed matching header `{header}`"),
+                })
+            }
+        };
+
+        let mut matched_right = vec![None; right_len];
+        let mut seen_left = HashMap::new();
+        for (n, line) in lines.enumerate() {
+            let line = line?;
+            let line_number = n + 2;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let (Some(i), Some(j), None) = (fields.next(), fields.next(), fields.next()) else {
+                return Err(Error::ParseError {
+                    line: line_number,
+                    message: format!("malformed matching line `{line}`"),
+                });
+            };
+            let (i, j) = (parse_index(i, line_number)?, parse_index(j, line_number)?);
+            if j >= right_len {
+                return Err(Error::IndexOutOfRange {
+                    index: j,
+                    len: right_len,
+                });
+            }
+            if let Some(left_len) = left_len.filter(|&len| i >= len) {
+                return Err(Error::IndexOutOfRange {
+                    index: i,
+                    len: left_len,
+                });
+            }
+            if let Some(k) = matched_right[j] {
+                return Err(Error::ParseError {
+                    line: line_number,
+                    message: format!("right vertex {j} is already matched to left vertex {k}"),
+                });
+            }
+            if let Some(k) = seen_left.insert(i, j) {
+                return Err(Error::ParseError {
+                    line: line_number,
+                    message: format!("left vertex {i} is already matched to right vertex {k}"),
+                });
+            }
+            matched_right[j] = Some(i);
+        }
+
+        let inferred = matched_right
+            .iter()
+            .flatten()
+            .map(|&i| i + 1)
+            .max()
+            .unwrap_or(0);
+        Ok(Matching::from_matched_right(
+            left_len.unwrap_or(inferred),
+            matched_right,
+        ))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        self.write_to(&mut w)?;
+        w.flush()
+    }
+
+    pub fn load(path: &Path) -> Result<Matching> {
+        Matching::read_from(BufReader::new(open_input(path)?))
+    }
+}
+
+impl<T> BipartiteGraph<T> {
+    pub fn is_valid_matching(&self, matching: &Matching) -> bool {
+        if matching.matched_right.len() != self.right_nodes.len() {
+            return false;
+        }
+
+        let mut used_left = vec![false; self.left_nodes.len()];
+        for (i, j) in matching.pairs() {
+            if i >= self.left_nodes.len() || used_left[i] {
+                return false;
+            }
+            used_left[i] = true;
+
+            // Left lists may be in any order, e.g. after `shuffle_neighbours`.
+            if !self.left_nodes[i].neighbours.borrow().contains(&j) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+fn parse_index(s: &str, line: usize) -> Result<usize> {
+    s.trim().parse().map_err(|_| Error::ParseError {
+        line,
+        message: format!("invalid index `{}`", s.trim()),
+    })
+}
@@ -0,0 +1,114 @@
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::graph::BipartiteGraph;
+use crate::input::open_input;
+
+const BANNER: &str = "%%MatrixMarket";
+
+impl<T> BipartiteGraph<T> {
+    pub fn load_matrix_market(path: &Path) -> Result<BipartiteGraph<T>>
+    where
+        T: Default,
+    {
+        BipartiteGraph::read_matrix_market(BufReader::new(open_input(path)?))
+    }
+
+    /// A Matrix Market coordinate matrix with its rows on the left and its columns on the
+    /// right; every stored entry is an edge, whatever its value. Symmetric, skew-symmetric and
+    /// Hermitian matrices store one triangle, so their off-diagonal entries are mirrored.
+    pub fn read_matrix_market<R: BufRead>(reader: R) -> Result<BipartiteGraph<T>>
+    where
+        T: Default,
+    {
+        let parse_error = |line: usize, message: String| Error::ParseError { line, message };
+        let mut lines = reader.lines().enumerate().map(|(k, line)| (k + 1, line));
+
+        let banner = match lines.next() {
+            Some((_, line)) => line?,
+            None => return Err(Error::InvalidFormat("empty Matrix Market file".to_string())),
+        };
+        let banner: Vec<String> = banner
+            .split_whitespace()
+            .map(|field| field.to_ascii_lowercase())
+            .collect();
+        let [first, object, format, field, symmetry] = &banner[..] else {
+            return Err(parse_error(
+                1,
+                format!("banner is not `{BANNER} matrix coordinate FIELD SYMMETRY`"),
+            ));
+        };
+        if *first != BANNER.to_ascii_lowercase() || object != "matrix" {
+            return Err(parse_error(1, format!("missing `{BANNER} matrix` banner")));
+        }
+        if format != "coordinate" {
+            return Err(parse_error(
+                1,
+                format!("`{format}` matrices are not supported, only `coordinate`"),
+            ));
+        }
+        if !["real", "integer", "complex", "pattern"].contains(&field.as_str()) {
+            return Err(parse_error(1, format!("unknown field `{field}`")));
+        }
+        let mirrored = match symmetry.as_str() {
+            "general" => false,
+            "symmetric" | "skew-symmetric" | "hermitian" => true,
+            other => return Err(parse_error(1, format!("unknown symmetry `{other}`"))),
+        };
+
+        let mut entries = lines.filter_map(|(k, line)| match line {
+            Ok(line) if line.trim().is_empty() || line.starts_with('%') => None,
+            line => Some((k, line)),
+        });
+        let (k, size) = entries
+            .next()
+            .ok_or_else(|| Error::InvalidFormat("missing Matrix Market size line".to_string()))?;
+        let sizes: Vec<usize> = size?
+            .split_whitespace()
+            .map(|field| field.parse())
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|err| parse_error(k, format!("bad size line: {err}")))?;
+        let [rows, cols, nnz] = sizes[..] else {
+            return Err(parse_error(k, "size line is not `rows cols entries`".to_string()));
+        };
+        if mirrored && rows != cols {
+            return Err(parse_error(
+                k,
+                format!("{symmetry} matrix is {rows} by {cols}, not square"),
+            ));
+        }
+
+        let mut edges = Vec::with_capacity(nnz.min(1 << 20));
+        let mut count = 0;
+        for (k, line) in entries {
+            let line = line?;
+            let mut fields = line.split_whitespace();
+            let mut index = |len: usize| -> Result<usize> {
+                let field = fields
+                    .next()
+                    .ok_or_else(|| parse_error(k, "entry is missing an index".to_string()))?;
+                let index: usize = field
+                    .parse()
+                    .map_err(|err| parse_error(k, format!("bad index `{field}`: {err}")))?;
+                if index == 0 || index > len {
+                    return Err(parse_error(k, format!("index {index} is not in 1..={len}")));
+                }
+                Ok(index - 1)
+            };
+            let (i, j) = (index(rows)?, index(cols)?);
+            edges.push((i, j));
+            if mirrored && i != j {
+                edges.push((j, i));
+            }
+            count += 1;
+        }
+        if count != nnz {
+            return Err(Error::InvalidFormat(format!(
+                "size line declares {nnz} entries but the file holds {count}"
+            )));
+        }
+
+        BipartiteGraph::from_edges(rows, cols, edges)
+    }
+}
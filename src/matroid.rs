@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use crate::graph::Adjacency;
+
+pub struct TransversalMatroid<'a, G> {
+    graph: &'a G,
+    memo: HashMap<Vec<usize>, usize>,
+    last: Vec<usize>,
+    matched_right: Vec<Option<usize>>,
+    visited: Vec<usize>,
+    stamp: usize,
+}
+
+impl<'a, G: Adjacency> TransversalMatroid<'a, G> {
+    pub fn new(graph: &'a G) -> TransversalMatroid<'a, G> {
+        TransversalMatroid {
+            graph,
+            memo: HashMap::new(),
+            last: Vec::new(),
+            matched_right: vec![None; graph.right_len()],
+            visited: vec![0; graph.left_len()],
+            stamp: 0,
+        }
+    }
+
+    pub fn ground_set_len(&self) -> usize {
+        self.graph.left_len()
+    }
+
+    pub fn rank(&mut self, subset: &[usize]) -> usize {
+        let mut key = subset.to_vec();
+        key.sort_unstable();
+        key.dedup();
+        key.retain(|&v| v < self.graph.left_len());
+
+        if let Some(&rank) = self.memo.get(&key) {
+            return rank;
+        }
+
+        if !is_subset(&self.last, &key) {
+            self.last.clear();
+            self.matched_right.fill(None);
+        }
+
+        let mut matched_left = vec![false; self.graph.left_len()];
+        for &i in self.matched_right.iter().flatten() {
+            matched_left[i] = true;
+        }
+        for &root in &key {
+            if !matched_left[root] && self.augment(root) {
+                matched_left[root] = true;
+            }
+        }
+
+        let rank = self.matched_right.iter().flatten().count();
+        self.memo.insert(key.clone(), rank);
+        self.last = key;
+        rank
+    }
+
+    pub fn is_independent(&mut self, subset: &[usize]) -> bool {
+        let mut distinct = subset.to_vec();
+        distinct.sort_unstable();
+        distinct.dedup();
+        distinct.len() == self.rank(&distinct)
+    }
+
+    pub fn clear_cache(&mut self) {
+        self.memo.clear();
+    }
+
+    fn augment(&mut self, root: usize) -> bool {
+        let graph = self.graph;
+        self.stamp += 1;
+        let stamp = self.stamp;
+        self.visited[root] = stamp;
+        let mut stack = vec![(root, None, graph.left_neighbours(root))];
+
+        while let Some((v, _, neighbours)) = stack.last_mut() {
+            let v = *v;
+            let Some(to) = neighbours.next() else {
+                stack.pop();
+                continue;
+            };
+
+            match self.matched_right[to] {
+                None => {
+                    self.matched_right[to] = Some(v);
+                    while let Some((_, via, _)) = stack.pop() {
+                        if let (Some(via), Some((u, _, _))) = (via, stack.last()) {
+                            self.matched_right[via] = Some(*u);
+                        }
+                    }
+                    return true;
+                }
+                Some(u) if self.visited[u] != stamp => {
+                    self.visited[u] = stamp;
+                    stack.push((u, Some(to), graph.left_neighbours(u)));
+                }
+                Some(_) => {}
+            }
+        }
+        false
+    }
+}
+
+fn is_subset(small: &[usize], large: &[usize]) -> bool {
+    let mut rest = large.iter();
+    small.iter().all(|x| rest.any(|y| y == x))
+}
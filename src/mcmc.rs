@@ -0,0 +1,114 @@
+use rand::prelude::*;
+
+use crate::graph::Adjacency;
+use crate::hopcroft_karp::hopcroft_karp;
+use crate::matching::Matching;
+
+/// Markov chain for sampling maximum matchings close to uniformly, the Jerrum-Sinclair chain
+/// restricted to matchings of the maximum size `k` and of size `k - 1`. Each step is lazy with
+/// probability 1/2 and otherwise picks a uniform edge `(u, v)`:
+///
+/// - if it is matched and the matching has size `k`, it is removed;
+/// - if both ends are free and the matching has size `k - 1`, it is added;
+/// - if exactly one end is matched, by `f`, it replaces `f`.
+///
+/// Every move is undone by one edge, so the chain is symmetric and its stationary
+/// distribution uniform; conditioned on size `k` it is uniform over maximum matchings. The
+/// moves connect all of these states, but how fast depends on the graph: the chain spends
+/// time on size `k - 1` in proportion to how many such matchings there are.
+#[derive(Clone, Debug)]
+pub struct MatchingChain {
+    edges: Vec<(usize, usize)>,
+    matched_left: Vec<Option<usize>>,
+    matched_right: Vec<Option<usize>>,
+    size: usize,
+    maximum: usize,
+}
+
+impl MatchingChain {
+    /// Starts from the matching [`hopcroft_karp`] finds. Parallel edges count once.
+    pub fn new<G: Adjacency>(graph: &G) -> MatchingChain {
+        let mut edges: Vec<(usize, usize)> = (0..graph.left_len())
+            .flat_map(|i| graph.left_neighbours(i).map(move |j| (i, j)))
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+
+        let matching = hopcroft_karp(graph);
+        MatchingChain {
+            edges,
+            size: matching.len(),
+            maximum: matching.len(),
+            matched_left: matching.matched_left().to_vec(),
+            matched_right: matching.matched_right().to_vec(),
+        }
+    }
+
+    pub fn maximum_size(&self) -> usize {
+        self.maximum
+    }
+
+    /// Whether the current state is a maximum matching rather than one edge short.
+    pub fn is_maximum(&self) -> bool {
+        self.size == self.maximum
+    }
+
+    /// One transition; returns whether the state changed.
+    pub fn step(&mut self, rng: &mut StdRng) -> bool {
+        if self.edges.is_empty() || rng.gen::<bool>() {
+            return false;
+        }
+        let (u, v) = self.edges[rng.gen_range(0..self.edges.len())];
+        match (self.matched_left[u], self.matched_right[v]) {
+            (Some(w), _) if w == v => {
+                if !self.is_maximum() {
+                    return false;
+                }
+                self.matched_left[u] = None;
+                self.matched_right[v] = None;
+                self.size -= 1;
+            }
+            (None, None) => {
+                if self.is_maximum() {
+                    return false;
+                }
+                self.size += 1;
+                self.matched_left[u] = Some(v);
+                self.matched_right[v] = Some(u);
+            }
+            (Some(w), None) => {
+                self.matched_right[w] = None;
+                self.matched_left[u] = Some(v);
+                self.matched_right[v] = Some(u);
+            }
+            (None, Some(x)) => {
+                self.matched_left[x] = None;
+                self.matched_left[u] = Some(v);
+                self.matched_right[v] = Some(u);
+            }
+            (Some(_), Some(_)) => return false,
+        }
+        true
+    }
+
+    /// Runs blocks of `iterations` steps until one ends on a maximum matching, which it
+    /// returns. Stopping at the first maximum matching reached instead would favour those
+    /// that many smaller states lead into. Successive calls continue the same chain, so
+    /// `iterations` is the spacing between samples as well as the burn-in of the first, and
+    /// should be well above the mixing time.
+    pub fn sample(&mut self, rng: &mut StdRng, iterations: usize) -> Matching {
+        loop {
+            for _ in 0..iterations.max(1) {
+                self.step(rng);
+            }
+            if self.is_maximum() {
+                return self.current();
+            }
+        }
+    }
+
+    /// The current state, which may be one edge short of maximum.
+    pub fn current(&self) -> Matching {
+        Matching::from_matched_right(self.matched_left.len(), self.matched_right.clone())
+    }
+}
@@ -0,0 +1,96 @@
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Where and how a benchmark run was produced, written next to its results.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunMetadata {
+    pub crate_version: String,
+    pub git_hash: String,
+    pub profile: String,
+    pub opt_level: String,
+    pub target: String,
+    pub rustflags: String,
+    pub hostname: String,
+    pub cpu_model: String,
+    pub command: Vec<String>,
+    pub variants: Vec<String>,
+    pub seed: Option<u64>,
+    pub started_at: u64,
+}
+
+impl RunMetadata {
+    pub fn collect(command: Vec<String>, variants: Vec<String>, seed: Option<u64>) -> RunMetadata {
+        RunMetadata {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            git_hash: env!("KUHN_GIT_HASH").to_string(),
+            profile: env!("KUHN_PROFILE").to_string(),
+            opt_level: env!("KUHN_OPT_LEVEL").to_string(),
+            target: env!("KUHN_TARGET").to_string(),
+            rustflags: env!("KUHN_RUSTFLAGS").to_string(),
+            hostname: hostname(),
+            cpu_model: cpu_model(),
+            command,
+            variants,
+            seed,
+            started_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let list = |items: &[String]| {
+            let items: Vec<String> = items.iter().map(|s| json_string(s)).collect();
+            format!("[{}]", items.join(","))
+        };
+        let seed = self
+            .seed
+            .map_or("null".to_string(), |seed| seed.to_string());
+        format!(
+            "{{\"crate_version\":{},\"git_hash\":{},\"profile\":{},\"opt_level\":{},\"target\":{},\"rustflags\":{},\"hostname\":{},\"cpu_model\":{},\"command\":{},\"variants\":{},\"seed\":{seed},\"started_at\":{}}}",
+            json_string(&self.crate_version),
+            json_string(&self.git_hash),
+            json_string(&self.profile),
+            json_string(&self.opt_level),
+            json_string(&self.target),
+            json_string(&self.rustflags),
+            json_string(&self.hostname),
+            json_string(&self.cpu_model),
+            list(&self.command),
+            list(&self.variants),
+            self.started_at
+        )
+    }
+}
+
+pub(crate) fn json_string(s: &str) -> String {
+    let escaped: String = s
+        .chars()
+        .flat_map(|c| match c {
+            '"' => vec!['\\', '"'],
+            '\\' => vec!['\\', '\\'],
+            c if c.is_control() => format!("\\u{:04x}", c as u32).chars().collect(),
+            c => vec![c],
+        })
+        .collect();
+    format!("\"{escaped}\"")
+}
+
+fn hostname() -> String {
+    fs::read_to_string("/proc/sys/kernel/hostname")
+        .or_else(|_| fs::read_to_string("/etc/hostname"))
+        .map(|name| name.trim().to_string())
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+fn cpu_model() -> String {
+    fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|info| {
+            info.lines()
+                .find(|line| line.starts_with("model name"))
+                .and_then(|line| line.split_once(':'))
+                .map(|(_, model)| model.trim().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
@@ -0,0 +1,175 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::graph::BipartiteGraph;
+use crate::input::open_input;
+
+const BIPARTITE_COMMENT: &str = "bipartite";
+
+impl<T> BipartiteGraph<T> {
+    pub fn load_metis(path: &Path, left_len: Option<usize>) -> Result<BipartiteGraph<T>>
+    where
+        T: Default,
+    {
+        BipartiteGraph::read_metis(BufReader::new(open_input(path)?), left_len)
+    }
+
+    /// METIS adjacency lists with vertices `1..=L` on the left and the rest on the right.
+    /// Vertex sizes and weights and edge weights are read past. Without `left_len`, `L` comes
+    /// from a `% bipartite L R` comment ahead of the header, as [`write_metis`] emits.
+    ///
+    /// [`write_metis`]: BipartiteGraph::write_metis
+    pub fn read_metis<R: BufRead>(reader: R, left_len: Option<usize>) -> Result<BipartiteGraph<T>>
+    where
+        T: Default,
+    {
+        let parse_error = |line: usize, message: String| Error::ParseError { line, message };
+        let mut lines = reader.lines().enumerate().map(|(k, line)| (k + 1, line));
+
+        let mut split = left_len;
+        let (header_line, header) = loop {
+            let Some((k, line)) = lines.next() else {
+                return Err(Error::InvalidFormat("missing METIS header".to_string()));
+            };
+            let line = line?;
+            let line = line.trim();
+            if let Some(comment) = line.strip_prefix('%') {
+                let mut fields = comment.split_whitespace();
+                if left_len.is_none() && fields.next() == Some(BIPARTITE_COMMENT) {
+                    split = fields.next().and_then(|l| l.parse().ok());
+                }
+            } else if !line.is_empty() {
+                break (k, line.to_string());
+            }
+        };
+
+        let numbers: Vec<usize> = header
+            .split_whitespace()
+            .map(|field| field.parse())
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|err| parse_error(header_line, format!("bad header: {err}")))?;
+        let (n, m) = match numbers[..] {
+            [n, m, ..] if numbers.len() <= 4 => (n, m),
+            _ => {
+                return Err(parse_error(
+                    header_line,
+                    "header is not `n m [fmt [ncon]]`".to_string(),
+                ))
+            }
+        };
+        let fmt = header.split_whitespace().nth(2).unwrap_or("0");
+        if fmt.len() > 3 || !fmt.bytes().all(|b| b == b'0' || b == b'1') {
+            return Err(parse_error(header_line, format!("bad fmt `{fmt}`")));
+        }
+        let flag = |position: usize| fmt.as_bytes().iter().rev().nth(position) == Some(&b'1');
+        let ncon = numbers.get(3).copied().unwrap_or(1);
+        let skipped = flag(2) as usize + if flag(1) { ncon } else { 0 };
+        let stride = 1 + flag(0) as usize;
+
+        let l = split.ok_or_else(|| {
+            Error::InvalidParameter("the METIS file does not give the left side size".to_string())
+        })?;
+        if l > n {
+            return Err(Error::InvalidParameter(format!(
+                "left side of {l} vertices in a graph of {n}"
+            )));
+        }
+
+        let mut from_left = Vec::new();
+        let mut from_right = Vec::new();
+        let mut v = 0;
+        for (k, line) in lines {
+            let line = line?;
+            if line.trim_start().starts_with('%') {
+                continue;
+            }
+            if v == n {
+                if !line.trim().is_empty() {
+                    return Err(parse_error(
+                        k,
+                        "more adjacency lines than vertices".to_string(),
+                    ));
+                }
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().skip(skipped).collect();
+            if !fields.len().is_multiple_of(stride) {
+                return Err(parse_error(
+                    k,
+                    "edge weight without a neighbour".to_string(),
+                ));
+            }
+            for field in fields.iter().step_by(stride) {
+                let u: usize = field
+                    .parse()
+                    .map_err(|err| parse_error(k, format!("bad neighbour `{field}`: {err}")))?;
+                if u == 0 || u > n {
+                    return Err(parse_error(k, format!("neighbour {u} is not in 1..={n}")));
+                }
+                let u = u - 1;
+                match (v < l, u < l) {
+                    (true, false) => from_left.push((v, u - l)),
+                    (false, true) => from_right.push((u, v - l)),
+                    _ => {
+                        return Err(parse_error(
+                            k,
+                            format!("edge {} {} within one side", v + 1, u + 1),
+                        ))
+                    }
+                }
+            }
+            v += 1;
+        }
+        if v < n {
+            return Err(Error::InvalidFormat(format!(
+                "{v} adjacency lines for {n} vertices"
+            )));
+        }
+
+        if from_left.len() != m || from_right.len() != m {
+            return Err(Error::InvalidFormat(format!(
+                "header declares {m} edges but the lists hold {} and {} endpoints per side",
+                from_left.len(),
+                from_right.len()
+            )));
+        }
+        let edges = from_left.clone();
+        from_left.sort_unstable();
+        from_right.sort_unstable();
+        if from_left != from_right {
+            return Err(Error::InvalidFormat(
+                "adjacency lists are not symmetric".to_string(),
+            ));
+        }
+
+        BipartiteGraph::from_edges(l, n - l, edges)
+    }
+
+    pub fn save_metis(&self, path: &Path) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        self.write_metis(&mut w)?;
+        w.flush()
+    }
+
+    /// Writes the graph with vertices `1..=L` on the left, preceded by a `% bipartite L R`
+    /// comment so that [`read_metis`](BipartiteGraph::read_metis) can recover the split.
+    pub fn write_metis<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let (l, r) = (self.left_nodes.len(), self.right_nodes.len());
+        writeln!(w, "% {BIPARTITE_COMMENT} {l} {r}")?;
+        writeln!(w, "{} {}", l + r, self.num_edges())?;
+        for (offset, nodes) in [(l + 1, &self.left_nodes), (1, &self.right_nodes)] {
+            for node in nodes {
+                let neighbours: Vec<String> = node
+                    .neighbours
+                    .borrow()
+                    .iter()
+                    .map(|&u| (u + offset).to_string())
+                    .collect();
+                writeln!(w, "{}", neighbours.join(" "))?;
+            }
+        }
+        Ok(())
+    }
+}
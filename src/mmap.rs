@@ -0,0 +1,171 @@
+#![cfg(unix)]
+
+use std::fs::File;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use std::ptr;
+use std::slice;
+
+use crate::csr::{CsrGraph, BINARY_MAGIC};
+use crate::error::{Error, Result};
+use crate::graph::Adjacency;
+
+const BINARY_HEADER_LEN: usize = 32;
+
+pub struct MmapCsrGraph {
+    ptr: *mut libc::c_void,
+    len: usize,
+    left_len: usize,
+    right_len: usize,
+    num_edges: usize,
+}
+
+unsafe impl Send for MmapCsrGraph {}
+unsafe impl Sync for MmapCsrGraph {}
+
+impl CsrGraph {
+    /// Maps a file written by [`CsrGraph::write_binary`] instead of reading it into memory.
+    pub fn load_mmap(path: &Path) -> Result<MmapCsrGraph> {
+        MmapCsrGraph::open(path)
+    }
+}
+
+impl MmapCsrGraph {
+    /// Maps `path` and checks the header and offsets in `O(L)`, so neighbour lookups stay in
+    /// bounds. The targets are only range-checked by [`MmapCsrGraph::validate`].
+    pub fn open(path: &Path) -> Result<MmapCsrGraph> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len() as usize;
+        if len < BINARY_HEADER_LEN {
+            return Err(Error::InvalidFormat(
+                "file too short for a CSR header".to_string(),
+            ));
+        }
+
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        let mut graph = MmapCsrGraph {
+            ptr,
+            len,
+            left_len: 0,
+            right_len: 0,
+            num_edges: 0,
+        };
+
+        let bytes = graph.bytes();
+        if &bytes[..8] != BINARY_MAGIC {
+            return Err(Error::InvalidFormat("missing CSR magic".to_string()));
+        }
+        let header = |k: usize| u64::from_le_bytes(bytes[8 * k..8 * k + 8].try_into().unwrap());
+        let (left_len, right_len, num_edges) =
+            (header(1) as usize, header(2) as usize, header(3) as usize);
+
+        let words = left_len
+            .checked_add(1)
+            .and_then(|n| n.checked_add(num_edges))
+            .and_then(|n| n.checked_mul(8))
+            .ok_or(Error::Overflow)?;
+        if BINARY_HEADER_LEN + words != len {
+            return Err(Error::InvalidFormat(
+                "file size does not match the CSR header".to_string(),
+            ));
+        }
+
+        graph.left_len = left_len;
+        graph.right_len = right_len;
+        graph.num_edges = num_edges;
+
+        let offsets = graph.offsets();
+        if u64::from_le(offsets[0]) != 0 || u64::from_le(offsets[left_len]) != num_edges as u64 {
+            return Err(Error::InvalidFormat(
+                "offsets do not describe the targets array".to_string(),
+            ));
+        }
+        if offsets
+            .windows(2)
+            .any(|w| u64::from_le(w[0]) > u64::from_le(w[1]))
+        {
+            return Err(Error::InvalidFormat(
+                "offsets are not monotonic".to_string(),
+            ));
+        }
+        Ok(graph)
+    }
+
+    fn bytes(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+
+    fn words(&self) -> &[u64] {
+        let words = (self.len - BINARY_HEADER_LEN) / 8;
+        unsafe { slice::from_raw_parts(self.ptr.add(BINARY_HEADER_LEN) as *const u64, words) }
+    }
+
+    pub fn offsets(&self) -> &[u64] {
+        &self.words()[..self.left_len + 1]
+    }
+
+    pub fn targets(&self) -> &[u64] {
+        &self.words()[self.left_len + 1..]
+    }
+
+    pub fn num_edges(&self) -> usize {
+        self.num_edges
+    }
+
+    /// Checks in `O(E)` that every target is a right vertex.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(&j) = self
+            .targets()
+            .iter()
+            .find(|&&j| u64::from_le(j) as usize >= self.right_len)
+        {
+            return Err(Error::IndexOutOfRange {
+                index: u64::from_le(j) as usize,
+                len: self.right_len,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Adjacency for MmapCsrGraph {
+    fn left_len(&self) -> usize {
+        self.left_len
+    }
+
+    fn right_len(&self) -> usize {
+        self.right_len
+    }
+
+    fn left_neighbours(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        let offsets = self.offsets();
+        let (start, end) = (
+            u64::from_le(offsets[v]) as usize,
+            u64::from_le(offsets[v + 1]) as usize,
+        );
+        self.targets()[start..end]
+            .iter()
+            .map(|&j| u64::from_le(j) as usize)
+    }
+}
+
+impl Drop for MmapCsrGraph {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr, self.len);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::io::{self, Write};
+
+use crate::error::{Error, Result};
+use crate::graph::{BipartiteGraph, Side};
+use crate::matching::Matching;
+
+/// The directed graph augmenting paths walk: unmatched edges point left to right, matched
+/// edges right to left. Paths from a free left vertex alternate by construction, and reaching
+/// a free right vertex closes an augmenting path. Parallel copies of an edge count once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AlternatingOrientation {
+    /// Unmatched right neighbours of each left vertex, in CSR form, sorted.
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+    matched_left: Vec<Option<usize>>,
+    matched_right: Vec<Option<usize>>,
+}
+
+impl<T> BipartiteGraph<T> {
+    pub fn alternating_orientation(&self, matching: &Matching) -> Result<AlternatingOrientation> {
+        if !self.is_valid_matching(matching) {
+            return Err(Error::InvalidMatching);
+        }
+        let mut offsets = Vec::with_capacity(self.left_nodes.len() + 1);
+        let mut targets = Vec::new();
+        offsets.push(0);
+        for (i, node) in self.left_nodes.iter().enumerate() {
+            let mut unmatched: Vec<usize> = node
+                .neighbours
+                .borrow()
+                .iter()
+                .copied()
+                .filter(|&j| matching.match_of_left(i) != Some(j))
+                .collect();
+            unmatched.sort_unstable();
+            unmatched.dedup();
+            targets.extend(unmatched);
+            offsets.push(targets.len());
+        }
+        Ok(AlternatingOrientation {
+            offsets,
+            targets,
+            matched_left: matching.matched_left().to_vec(),
+            matched_right: matching.matched_right().to_vec(),
+        })
+    }
+}
+
+impl AlternatingOrientation {
+    pub fn left_len(&self) -> usize {
+        self.matched_left.len()
+    }
+
+    pub fn right_len(&self) -> usize {
+        self.matched_right.len()
+    }
+
+    pub fn num_arcs(&self) -> usize {
+        self.targets.len() + self.matched_right.iter().flatten().count()
+    }
+
+    /// Whether `(side, vertex)` is unmatched: free left vertices start augmenting paths and
+    /// free right vertices end them.
+    pub fn is_free(&self, side: Side, vertex: usize) -> bool {
+        match side {
+            Side::Left => self.matched_left[vertex].is_none(),
+            Side::Right => self.matched_right[vertex].is_none(),
+        }
+    }
+
+    /// Heads of the arcs leaving `(side, vertex)`: the unmatched right neighbours of a left
+    /// vertex, or the partner of a matched right vertex.
+    pub fn successors(&self, side: Side, vertex: usize) -> Vec<(Side, usize)> {
+        match side {
+            Side::Left => self.targets[self.offsets[vertex]..self.offsets[vertex + 1]]
+                .iter()
+                .map(|&j| (Side::Right, j))
+                .collect(),
+            Side::Right => self.matched_right[vertex]
+                .map(|i| (Side::Left, i))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Every arc as `(tail, head)`, left tails first, each side by index.
+    pub fn arcs(&self) -> impl Iterator<Item = ((Side, usize), (Side, usize))> + '_ {
+        let forward = (0..self.left_len()).flat_map(move |i| {
+            self.targets[self.offsets[i]..self.offsets[i + 1]]
+                .iter()
+                .map(move |&j| ((Side::Left, i), (Side::Right, j)))
+        });
+        let backward = self
+            .matched_right
+            .iter()
+            .enumerate()
+            .filter_map(|(j, m)| m.map(|i| ((Side::Right, j), (Side::Left, i))));
+        forward.chain(backward)
+    }
+
+    /// Vertices reachable from `sources` along arcs, the sources included, as one flag per
+    /// left and one per right vertex.
+    pub fn reachable<I>(&self, sources: I) -> (Vec<bool>, Vec<bool>)
+    where
+        I: IntoIterator<Item = (Side, usize)>,
+    {
+        let mut left = vec![false; self.left_len()];
+        let mut right = vec![false; self.right_len()];
+        let mut stack = Vec::new();
+        for (side, v) in sources {
+            let seen = match side {
+                Side::Left => &mut left[v],
+                Side::Right => &mut right[v],
+            };
+            if !std::mem::replace(seen, true) {
+                stack.push((side, v));
+            }
+        }
+        while let Some((side, v)) = stack.pop() {
+            for (side, w) in self.successors(side, v) {
+                let seen = match side {
+                    Side::Left => &mut left[w],
+                    Side::Right => &mut right[w],
+                };
+                if !std::mem::replace(seen, true) {
+                    stack.push((side, w));
+                }
+            }
+        }
+        (left, right)
+    }
+
+    /// Writes `from,to,matched` rows with vertices named `L3` and `R5`.
+    pub fn write_edge_list<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "from,to,matched")?;
+        for (tail, head) in self.arcs() {
+            writeln!(
+                w,
+                "{},{},{}",
+                vertex_name(tail),
+                vertex_name(head),
+                tail.0 == Side::Right
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the orientation for Graphviz with the node names of
+    /// [`BipartiteGraph::write_dot`]: matched arcs in red and free vertices doubled.
+    pub fn write_dot<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "digraph A {{")?;
+        writeln!(w, "\trankdir=LR")?;
+        writeln!(w, "\tsplines=false")?;
+        for (name, mates) in [('A', &self.matched_left), ('B', &self.matched_right)] {
+            writeln!(w, "\tsubgraph cluster_{name} {{")?;
+            writeln!(w, "\t\tmargin=30")?;
+            writeln!(w, "\t\tstyle=invis")?;
+            for (v, mate) in mates.iter().enumerate() {
+                match mate {
+                    None => writeln!(w, "\t\t{name}{v} [peripheries=2]")?,
+                    Some(_) => writeln!(w, "\t\t{name}{v}")?,
+                }
+            }
+            writeln!(w, "\t}}")?;
+        }
+        for (tail, head) in self.arcs() {
+            let attrs = if tail.0 == Side::Right {
+                " [color=red]"
+            } else {
+                ""
+            };
+            writeln!(w, "\t{} -> {}{attrs}", dot_name(tail), dot_name(head))?;
+        }
+        writeln!(w, "}}")
+    }
+}
+
+fn vertex_name((side, v): (Side, usize)) -> String {
+    match side {
+        Side::Left => format!("L{v}"),
+        Side::Right => format!("R{v}"),
+    }
+}
+
+fn dot_name((side, v): (Side, usize)) -> String {
+    match side {
+        Side::Left => format!("A{v}"),
+        Side::Right => format!("B{v}"),
+    }
+}
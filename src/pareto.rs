@@ -0,0 +1,140 @@
+use crate::assignment::{checked_total, CostMatrix};
+use crate::error::{Error, Result};
+use crate::matching::Matching;
+
+/// Two cost matrices over the same rows and columns, both minimised: a cost and a fairness
+/// penalty, say.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BiObjective {
+    cost: CostMatrix,
+    fairness: CostMatrix,
+}
+
+/// An assignment with its two totals and the weights whose combination it minimises.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParetoPoint {
+    pub matching: Matching,
+    pub cost: i64,
+    pub fairness: i64,
+    pub cost_weight: i64,
+    pub fairness_weight: i64,
+}
+
+impl ParetoPoint {
+    pub const CSV_HEADER: &'static str = "cost_weight,fairness_weight,cost,fairness";
+
+    pub fn csv_fields(&self) -> String {
+        format!(
+            "{},{},{},{}",
+            self.cost_weight, self.fairness_weight, self.cost, self.fairness
+        )
+    }
+}
+
+impl BiObjective {
+    pub fn new(cost: CostMatrix, fairness: CostMatrix) -> Result<BiObjective> {
+        if (cost.rows(), cost.cols()) != (fairness.rows(), fairness.cols()) {
+            return Err(Error::InvalidFormat(format!(
+                "a {}x{} cost matrix cannot pair with a {}x{} fairness matrix",
+                cost.rows(),
+                cost.cols(),
+                fairness.rows(),
+                fairness.cols()
+            )));
+        }
+        Ok(BiObjective { cost, fairness })
+    }
+
+    pub fn cost(&self) -> &CostMatrix {
+        &self.cost
+    }
+
+    pub fn fairness(&self) -> &CostMatrix {
+        &self.fairness
+    }
+
+    /// Assignment minimising `cost_weight * cost + fairness_weight * fairness`. Weights must
+    /// not be negative; the combined matrix must fit in an `i64`.
+    pub fn scalarized(&self, cost_weight: i64, fairness_weight: i64) -> Result<ParetoPoint> {
+        if cost_weight < 0 || fairness_weight < 0 {
+            return Err(Error::InvalidParameter(format!(
+                "weights {cost_weight} and {fairness_weight} must not be negative"
+            )));
+        }
+        let (rows, cols) = (self.cost.rows(), self.cost.cols());
+        let mut combined = Vec::with_capacity(rows * cols);
+        for i in 0..rows {
+            for j in 0..cols {
+                let value = cost_weight
+                    .checked_mul(self.cost.get(i, j))
+                    .zip(fairness_weight.checked_mul(self.fairness.get(i, j)))
+                    .and_then(|(a, b)| a.checked_add(b))
+                    .ok_or(Error::Overflow)?;
+                combined.push(value);
+            }
+        }
+        let (matching, _) = CostMatrix::new(rows, cols, combined)?.min_cost_assignment()?;
+        let total = |what, matrix: &CostMatrix| {
+            checked_total(what, matching.pairs().map(|(i, j)| matrix.get(i, j)))
+        };
+        Ok(ParetoPoint {
+            cost: total("total cost", &self.cost)?,
+            fairness: total("total fairness", &self.fairness)?,
+            matching,
+            cost_weight,
+            fairness_weight,
+        })
+    }
+
+    /// The supported Pareto frontier: every extreme point of the trade-off curve, by increasing
+    /// cost. Starts from the cost-first and fairness-first optima and scalarizes between each
+    /// adjacent pair with the weights that make both equally good, keeping any assignment that
+    /// beats them. Needs `2k - 1` solves for `k` points. Non-supported optima, inside the
+    /// convex hull, are not found by any weighted sum.
+    pub fn pareto_frontier(&self) -> Result<Vec<ParetoPoint>> {
+        let size = self.cost.rows().min(self.cost.cols()) as i64;
+        let tie_break = |matrix: &CostMatrix| {
+            let (mut lo, mut hi) = (0, 0);
+            for i in 0..matrix.rows() {
+                for j in 0..matrix.cols() {
+                    lo = matrix.get(i, j).min(lo);
+                    hi = matrix.get(i, j).max(hi);
+                }
+            }
+            // Larger than any difference between two assignments' totals.
+            hi.checked_sub(lo)
+                .and_then(|range| range.checked_mul(size))
+                .and_then(|spread| spread.checked_add(1))
+                .ok_or(Error::Overflow)
+        };
+
+        let cheapest = self.scalarized(tie_break(&self.fairness)?, 1)?;
+        let fairest = self.scalarized(1, tie_break(&self.cost)?)?;
+        if (cheapest.cost, cheapest.fairness) == (fairest.cost, fairest.fairness) {
+            return Ok(vec![cheapest]);
+        }
+
+        let mut frontier = vec![cheapest];
+        // The points still to the right of the last one in `frontier`, nearest last.
+        let mut pending = vec![fairest];
+        while let Some(right) = pending.pop() {
+            let left = frontier.last().expect("frontier starts with a point");
+            let cost_weight = left.fairness.checked_sub(right.fairness);
+            let fairness_weight = right.cost.checked_sub(left.cost);
+            let (Some(cost_weight), Some(fairness_weight)) = (cost_weight, fairness_weight) else {
+                return Err(Error::Overflow);
+            };
+            let value = |p: &ParetoPoint| {
+                cost_weight as i128 * p.cost as i128 + fairness_weight as i128 * p.fairness as i128
+            };
+            let between = self.scalarized(cost_weight, fairness_weight)?;
+            if value(&between) < value(left) {
+                pending.push(right);
+                pending.push(between);
+            } else {
+                frontier.push(right);
+            }
+        }
+        Ok(frontier)
+    }
+}
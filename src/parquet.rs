@@ -0,0 +1,393 @@
+//! A minimal Apache Parquet writer for bench results, so polars or pandas can load them
+//! without parsing CSV. A table becomes one row group holding each column as required
+//! (non-null) values, PLAIN-encoded and uncompressed, split into pages of at most
+//! [`PAGE_ROWS`] rows. The file metadata is written in the Thrift compact protocol by hand,
+//! so no Arrow or Parquet crate is linked into the crate.
+
+use std::io::{self, Write};
+
+use crate::error::{Error, Result};
+
+const MAGIC: &[u8; 4] = b"PAR1";
+pub const PAGE_ROWS: usize = 1 << 16;
+
+// Thrift compact protocol field types.
+const I32: u8 = 5;
+const I64: u8 = 6;
+const BINARY: u8 = 8;
+const LIST: u8 = 9;
+const STRUCT: u8 = 12;
+
+// Parquet enum values.
+const TYPE_INT64: i32 = 2;
+const TYPE_DOUBLE: i32 = 5;
+const TYPE_BYTE_ARRAY: i32 = 6;
+const REQUIRED: i32 = 0;
+const CONVERTED_UTF8: i32 = 0;
+const CONVERTED_UINT_64: i32 = 14;
+const ENCODING_PLAIN: i32 = 0;
+const ENCODING_RLE: i32 = 3;
+const UNCOMPRESSED: i32 = 0;
+const DATA_PAGE: i32 = 0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Column {
+    Int64(Vec<i64>),
+    /// Stored as INT64 bits annotated as unsigned, for seeds and other values above
+    /// `i64::MAX`.
+    UInt64(Vec<u64>),
+    Double(Vec<f64>),
+    Utf8(Vec<String>),
+}
+
+impl Column {
+    pub fn len(&self) -> usize {
+        match self {
+            Column::Int64(values) => values.len(),
+            Column::UInt64(values) => values.len(),
+            Column::Double(values) => values.len(),
+            Column::Utf8(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The narrowest column holding every value: signed then unsigned integers, then
+    /// floats, then strings.
+    pub fn infer(values: Vec<String>) -> Column {
+        if let Ok(ints) = values.iter().map(|v| v.parse()).collect() {
+            Column::Int64(ints)
+        } else if let Ok(ints) = values.iter().map(|v| v.parse()).collect() {
+            Column::UInt64(ints)
+        } else if let Ok(floats) = values.iter().map(|v| v.parse()).collect() {
+            Column::Double(floats)
+        } else {
+            Column::Utf8(values)
+        }
+    }
+
+    fn physical_type(&self) -> i32 {
+        match self {
+            Column::Int64(_) | Column::UInt64(_) => TYPE_INT64,
+            Column::Double(_) => TYPE_DOUBLE,
+            Column::Utf8(_) => TYPE_BYTE_ARRAY,
+        }
+    }
+
+    fn plain_page(&self, rows: std::ops::Range<usize>) -> Vec<u8> {
+        let mut out = Vec::new();
+        match self {
+            Column::Int64(values) => {
+                for v in &values[rows] {
+                    out.extend(v.to_le_bytes());
+                }
+            }
+            Column::UInt64(values) => {
+                for v in &values[rows] {
+                    out.extend(v.to_le_bytes());
+                }
+            }
+            Column::Double(values) => {
+                for v in &values[rows] {
+                    out.extend(v.to_le_bytes());
+                }
+            }
+            Column::Utf8(values) => {
+                for v in &values[rows] {
+                    out.extend((v.len() as u32).to_le_bytes());
+                    out.extend(v.as_bytes());
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Named columns of equal length, plus key-value metadata stored in the file footer.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Table {
+    names: Vec<String>,
+    columns: Vec<Column>,
+    metadata: Vec<(String, String)>,
+}
+
+impl Table {
+    pub fn new() -> Table {
+        Table::default()
+    }
+
+    pub fn push_column(&mut self, name: &str, column: Column) -> Result<()> {
+        if self.columns.first().is_some_and(|c| c.len() != column.len()) {
+            return Err(Error::InvalidParameter(format!(
+                "column `{name}` has {} rows, expected {}",
+                column.len(),
+                self.num_rows()
+            )));
+        }
+        self.names.push(name.to_string());
+        self.columns.push(column);
+        Ok(())
+    }
+
+    /// Splits rows in the format of the `csv_fields` methods against a comma-separated
+    /// header. Columns named in `text` stay strings, such as hex hashes that may happen to
+    /// be all digits, and columns named in `unsigned` are parsed as [`Column::UInt64`], so
+    /// a seed column has the same type in every run; the rest are typed with
+    /// [`Column::infer`].
+    pub fn from_csv_rows(
+        header: &str,
+        rows: &[String],
+        text: &[&str],
+        unsigned: &[&str],
+    ) -> Result<Table> {
+        let names: Vec<&str> = header.split(',').collect();
+        let mut values = vec![Vec::with_capacity(rows.len()); names.len()];
+        for (k, row) in rows.iter().enumerate() {
+            let fields: Vec<&str> = row.split(',').collect();
+            if fields.len() != names.len() {
+                return Err(Error::ParseError {
+                    line: k + 2,
+                    message: format!("{} fields for {} columns", fields.len(), names.len()),
+                });
+            }
+            for (column, field) in values.iter_mut().zip(fields) {
+                column.push(field.to_string());
+            }
+        }
+
+        let mut table = Table::new();
+        for (name, values) in names.into_iter().zip(values) {
+            let column = if text.contains(&name) {
+                Column::Utf8(values)
+            } else if unsigned.contains(&name) {
+                let parsed = values.iter().enumerate().map(|(k, v)| {
+                    v.parse().map_err(|_| Error::ParseError {
+                        line: k + 2,
+                        message: format!("`{v}` in column `{name}` is not an unsigned integer"),
+                    })
+                });
+                Column::UInt64(parsed.collect::<Result<_>>()?)
+            } else {
+                Column::infer(values)
+            };
+            table.push_column(name, column)?;
+        }
+        Ok(table)
+    }
+
+    pub fn set_metadata(&mut self, key: &str, value: &str) {
+        self.metadata.push((key.to_string(), value.to_string()));
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.columns.first().map_or(0, Column::len)
+    }
+
+    pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let rows = self.num_rows();
+        let mut offset = MAGIC.len();
+        w.write_all(MAGIC)?;
+
+        let mut chunks = Vec::with_capacity(self.columns.len());
+        for column in &self.columns {
+            let start = offset;
+            for first in (0..rows.max(1)).step_by(PAGE_ROWS) {
+                let range = first..(first + PAGE_ROWS).min(rows);
+                let values = range.len();
+                let data = column.plain_page(range);
+                let header = page_header(values, data.len())?;
+                w.write_all(&header)?;
+                w.write_all(&data)?;
+                offset += header.len() + data.len();
+            }
+            chunks.push((start, offset - start));
+        }
+
+        let footer = self.file_metadata(&chunks);
+        w.write_all(&footer)?;
+        w.write_all(&(footer.len() as u32).to_le_bytes())?;
+        w.write_all(MAGIC)
+    }
+
+    fn file_metadata(&self, chunks: &[(usize, usize)]) -> Vec<u8> {
+        let rows = self.num_rows() as i64;
+        let mut t = Compact::new();
+        t.i32(1, 1);
+
+        t.list(2, STRUCT, self.columns.len() + 1);
+        t.begin_element();
+        t.binary(4, b"schema");
+        t.i32(5, self.columns.len() as i32);
+        t.end_struct();
+        for (name, column) in self.names.iter().zip(&self.columns) {
+            t.begin_element();
+            t.i32(1, column.physical_type());
+            t.i32(3, REQUIRED);
+            t.binary(4, name.as_bytes());
+            match column {
+                Column::UInt64(_) => t.i32(6, CONVERTED_UINT_64),
+                Column::Utf8(_) => t.i32(6, CONVERTED_UTF8),
+                Column::Int64(_) | Column::Double(_) => {}
+            }
+            t.end_struct();
+        }
+
+        t.i64(3, rows);
+
+        let total: usize = chunks.iter().map(|&(_, size)| size).sum();
+        t.list(4, STRUCT, 1);
+        t.begin_element();
+        t.list(1, STRUCT, self.columns.len());
+        for ((name, column), &(start, size)) in self.names.iter().zip(&self.columns).zip(chunks) {
+            t.begin_element();
+            t.i64(2, start as i64);
+            t.begin_struct(3);
+            t.i32(1, column.physical_type());
+            t.list(2, I32, 1);
+            t.list_i32(ENCODING_PLAIN);
+            t.list(3, BINARY, 1);
+            t.list_binary(name.as_bytes());
+            t.i32(4, UNCOMPRESSED);
+            t.i64(5, rows);
+            t.i64(6, size as i64);
+            t.i64(7, size as i64);
+            t.i64(9, start as i64);
+            t.end_struct();
+            t.end_struct();
+        }
+        t.i64(2, total as i64);
+        t.i64(3, rows);
+        t.end_struct();
+
+        if !self.metadata.is_empty() {
+            t.list(5, STRUCT, self.metadata.len());
+            for (key, value) in &self.metadata {
+                t.begin_element();
+                t.binary(1, key.as_bytes());
+                t.binary(2, value.as_bytes());
+                t.end_struct();
+            }
+        }
+        t.binary(6, concat!("kuhn ", env!("CARGO_PKG_VERSION")).as_bytes());
+        t.finish()
+    }
+}
+
+fn page_header(values: usize, size: usize) -> io::Result<Vec<u8>> {
+    let too_large = |what: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("parquet page {what} exceeds i32"),
+        )
+    };
+    let size = i32::try_from(size).map_err(|_| too_large("size"))?;
+    let values = i32::try_from(values).map_err(|_| too_large("row count"))?;
+
+    let mut t = Compact::new();
+    t.i32(1, DATA_PAGE);
+    t.i32(2, size);
+    t.i32(3, size);
+    t.begin_struct(5);
+    t.i32(1, values);
+    t.i32(2, ENCODING_PLAIN);
+    t.i32(3, ENCODING_RLE);
+    t.i32(4, ENCODING_RLE);
+    t.end_struct();
+    Ok(t.finish())
+}
+
+/// Thrift compact protocol encoder for the few shapes Parquet metadata needs. Field ids are
+/// delta-encoded against the previous field of the enclosing struct.
+struct Compact {
+    out: Vec<u8>,
+    last: Vec<i16>,
+}
+
+impl Compact {
+    fn new() -> Compact {
+        Compact {
+            out: Vec::new(),
+            last: vec![0],
+        }
+    }
+
+    fn varint(&mut self, mut v: u64) {
+        while v >= 0x80 {
+            self.out.push(v as u8 | 0x80);
+            v >>= 7;
+        }
+        self.out.push(v as u8);
+    }
+
+    fn zigzag(&mut self, v: i64) {
+        self.varint(((v << 1) ^ (v >> 63)) as u64);
+    }
+
+    fn header(&mut self, id: i16, kind: u8) {
+        let last = self.last.last_mut().expect("inside a struct");
+        let delta = id - *last;
+        *last = id;
+        if (1..=15).contains(&delta) {
+            self.out.push((delta as u8) << 4 | kind);
+        } else {
+            self.out.push(kind);
+            self.zigzag(i64::from(id));
+        }
+    }
+
+    fn i32(&mut self, id: i16, v: i32) {
+        self.header(id, I32);
+        self.zigzag(i64::from(v));
+    }
+
+    fn i64(&mut self, id: i16, v: i64) {
+        self.header(id, I64);
+        self.zigzag(v);
+    }
+
+    fn binary(&mut self, id: i16, bytes: &[u8]) {
+        self.header(id, BINARY);
+        self.list_binary(bytes);
+    }
+
+    fn begin_struct(&mut self, id: i16) {
+        self.header(id, STRUCT);
+        self.last.push(0);
+    }
+
+    fn end_struct(&mut self) {
+        self.out.push(0);
+        self.last.pop();
+    }
+
+    fn list(&mut self, id: i16, kind: u8, len: usize) {
+        self.header(id, LIST);
+        if len < 15 {
+            self.out.push((len as u8) << 4 | kind);
+        } else {
+            self.out.push(0xf0 | kind);
+            self.varint(len as u64);
+        }
+    }
+
+    /// Starts a struct element of a list, closed by [`Compact::end_struct`].
+    fn begin_element(&mut self) {
+        self.last.push(0);
+    }
+
+    fn list_i32(&mut self, v: i32) {
+        self.zigzag(i64::from(v));
+    }
+
+    fn list_binary(&mut self, bytes: &[u8]) {
+        self.varint(bytes.len() as u64);
+        self.out.extend_from_slice(bytes);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.out.push(0);
+        self.out
+    }
+}
@@ -0,0 +1,118 @@
+use rand::prelude::*;
+
+use crate::error::{Error, Result};
+use crate::graph::BipartiteGraph;
+use crate::matching::Matching;
+
+/// A permutation of each side: old vertex `i` becomes `left[i]`, old `j` becomes `right[j]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Relabeling {
+    pub left: Vec<usize>,
+    pub right: Vec<usize>,
+}
+
+impl Relabeling {
+    pub fn identity(left_len: usize, right_len: usize) -> Relabeling {
+        Relabeling {
+            left: (0..left_len).collect(),
+            right: (0..right_len).collect(),
+        }
+    }
+
+    pub fn random(rng: &mut StdRng, left_len: usize, right_len: usize) -> Relabeling {
+        let mut relabeling = Relabeling::identity(left_len, right_len);
+        relabeling.left.shuffle(rng);
+        relabeling.right.shuffle(rng);
+        relabeling
+    }
+
+    pub fn inverse(&self) -> Relabeling {
+        let invert = |permutation: &[usize]| {
+            let mut inverse = vec![0; permutation.len()];
+            for (old, &new) in permutation.iter().enumerate() {
+                inverse[new] = old;
+            }
+            inverse
+        };
+        Relabeling {
+            left: invert(&self.left),
+            right: invert(&self.right),
+        }
+    }
+
+    /// A matching of the original graph carried over to the relabeled one.
+    pub fn apply(&self, matching: &Matching) -> Result<Matching> {
+        let (l, r) = (
+            matching.matched_left().len(),
+            matching.matched_right().len(),
+        );
+        if (l, r) != (self.left.len(), self.right.len()) {
+            return Err(Error::InvalidMatching);
+        }
+        let mut matched_right = vec![None; r];
+        for (i, j) in matching.pairs() {
+            matched_right[self.right[j]] = Some(self.left[i]);
+        }
+        Ok(Matching::from_matched_right(l, matched_right))
+    }
+
+    fn validate(&self, left_len: usize, right_len: usize) -> Result<()> {
+        for (permutation, len) in [(&self.left, left_len), (&self.right, right_len)] {
+            if permutation.len() != len {
+                return Err(Error::InvalidParameter(format!(
+                    "permutation of {} vertices for a side of {len}",
+                    permutation.len()
+                )));
+            }
+            let mut seen = vec![false; len];
+            for &new in permutation {
+                if new >= len {
+                    return Err(Error::IndexOutOfRange { index: new, len });
+                }
+                if std::mem::replace(&mut seen[new], true) {
+                    return Err(Error::InvalidParameter(format!(
+                        "vertex {new} appears twice in the permutation"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: Clone> BipartiteGraph<T> {
+    /// The isomorphic graph under `relabeling`. Neighbour lists come out sorted in the new
+    /// indices, so they carry no trace of the old order.
+    pub fn relabel(&self, relabeling: &Relabeling) -> Result<BipartiteGraph<T>> {
+        let (l, r) = (self.left_nodes.len(), self.right_nodes.len());
+        relabeling.validate(l, r)?;
+
+        let mut edges: Vec<(usize, usize)> = self
+            .edges()
+            .into_iter()
+            .map(|(i, j)| (relabeling.left[i], relabeling.right[j]))
+            .collect();
+        edges.sort_unstable();
+
+        let inverse = relabeling.inverse();
+        let left_data = inverse
+            .left
+            .iter()
+            .map(|&i| self.left_nodes[i].data.clone())
+            .collect();
+        let right_data = inverse
+            .right
+            .iter()
+            .map(|&j| self.right_nodes[j].data.clone())
+            .collect();
+        BipartiteGraph::from_edges_with_data(left_data, right_data, edges)
+    }
+
+    pub fn relabel_random(&self, rng: &mut StdRng) -> (BipartiteGraph<T>, Relabeling) {
+        let relabeling = Relabeling::random(rng, self.left_nodes.len(), self.right_nodes.len());
+        let graph = self
+            .relabel(&relabeling)
+            .expect("a random relabeling is a valid permutation");
+        (graph, relabeling)
+    }
+}
@@ -0,0 +1,151 @@
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::time::Instant;
+
+use rand::prelude::*;
+
+use crate::bench::{run_variant, RunOptions, Variant};
+use crate::graph::BipartiteGraph;
+use crate::kuhn::OpCounts;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReportRow {
+    pub instance: String,
+    pub variant: Variant,
+    pub time: u128,
+    pub size: usize,
+    pub ops: Option<OpCounts>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ReportFormat, String> {
+        match s {
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            _ => Err(format!(
+                "unknown report format `{s}` (expected markdown or html)"
+            )),
+        }
+    }
+}
+
+pub fn run_report<T>(
+    instances: &[(String, BipartiteGraph<T>)],
+    variants: &[Variant],
+    rng: &mut StdRng,
+) -> Vec<ReportRow> {
+    let mut rows = Vec::new();
+    for (name, graph) in instances {
+        for &variant in variants {
+            graph.sort_neighbours();
+            variant.prepare(graph);
+
+            let mut ops = OpCounts::default();
+            let start = Instant::now();
+            let matching = run_variant(graph, rng, variant, &RunOptions::default(), &mut ops);
+            let time = start.elapsed().as_nanos();
+
+            rows.push(ReportRow {
+                instance: name.clone(),
+                variant,
+                time,
+                size: matching.len(),
+                ops: variant.is_observable().then_some(ops),
+            });
+        }
+        graph.sort_neighbours();
+    }
+    rows
+}
+
+pub fn write_report<W: Write>(
+    w: &mut W,
+    rows: &[ReportRow],
+    format: ReportFormat,
+) -> io::Result<()> {
+    let header = [
+        "instance",
+        "algorithm",
+        "time (ms)",
+        "size",
+        "edge scans",
+        "augmentations",
+    ];
+
+    match format {
+        ReportFormat::Markdown => {
+            writeln!(w, "| {} |", header.join(" | "))?;
+            writeln!(w, "|{}", "---|".repeat(header.len()))?;
+        }
+        ReportFormat::Html => {
+            writeln!(w, "<table>")?;
+            let cells: Vec<String> = header.iter().map(|h| format!("<th>{h}</th>")).collect();
+            writeln!(w, "  <tr>{}</tr>", cells.concat())?;
+        }
+    }
+
+    for row in rows {
+        let winner = rows
+            .iter()
+            .filter(|other| other.instance == row.instance)
+            .map(|other| other.time)
+            .min()
+            == Some(row.time);
+
+        let (scans, augmentations) = match row.ops {
+            Some(ops) => (ops.edge_scans.to_string(), ops.augmentations.to_string()),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        let cells = [
+            row.instance.clone(),
+            row.variant.name().to_string(),
+            format!("{:.3}", row.time as f64 / 1e6),
+            row.size.to_string(),
+            scans,
+            augmentations,
+        ];
+
+        match format {
+            ReportFormat::Markdown => {
+                let cells: Vec<String> = cells
+                    .iter()
+                    .map(|c| {
+                        if winner {
+                            format!("**{c}**")
+                        } else {
+                            c.clone()
+                        }
+                    })
+                    .collect();
+                writeln!(w, "| {} |", cells.join(" | "))?;
+            }
+            ReportFormat::Html => {
+                let class = if winner { " class=\"winner\"" } else { "" };
+                let cells: Vec<String> = cells
+                    .iter()
+                    .map(|c| format!("<td>{}</td>", escape_html(c)))
+                    .collect();
+                writeln!(w, "  <tr{class}>{}</tr>", cells.concat())?;
+            }
+        }
+    }
+
+    if format == ReportFormat::Html {
+        writeln!(w, "</table>")?;
+    }
+    Ok(())
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
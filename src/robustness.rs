@@ -0,0 +1,269 @@
+use rand::prelude::*;
+use rand::seq::index::sample;
+
+use crate::graph::{BipartiteGraph, Node, Side};
+use crate::hopcroft_karp::hopcroft_karp;
+use crate::matching::Matching;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VertexFailure {
+    pub side: Side,
+    pub vertex: usize,
+    pub drop: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RobustnessReport {
+    pub baseline: usize,
+    pub failures: Vec<VertexFailure>,
+}
+
+impl RobustnessReport {
+    pub fn drop_histogram(&self) -> Vec<usize> {
+        let mut histogram = Vec::new();
+        for failure in &self.failures {
+            if failure.drop >= histogram.len() {
+                histogram.resize(failure.drop + 1, 0);
+            }
+            histogram[failure.drop] += 1;
+        }
+        histogram
+    }
+
+    pub fn mean_drop(&self) -> f64 {
+        let total: usize = self.failures.iter().map(|f| f.drop).sum();
+        total as f64 / self.failures.len().max(1) as f64
+    }
+
+    pub fn critical(&self) -> impl Iterator<Item = &VertexFailure> {
+        self.failures.iter().filter(|f| f.drop > 0)
+    }
+}
+
+/// The edges whose removal shrinks every maximum matching, found by [`BipartiteGraph::edge_criticality`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EdgeCriticality {
+    pub matching: Matching,
+    /// Sorted by left vertex. Each one is an edge of `matching`.
+    pub critical: Vec<(usize, usize)>,
+}
+
+impl EdgeCriticality {
+    pub fn is_critical(&self, i: usize, j: usize) -> bool {
+        self.critical.binary_search(&(i, j)).is_ok()
+    }
+}
+
+impl<T> BipartiteGraph<T> {
+    /// Finds the edges that belong to every maximum matching from one solve. A matched edge can
+    /// be avoided when its left end is reachable by an even alternating path from a free left
+    /// vertex, its right end by one from a free right vertex, or it lies on an alternating
+    /// cycle; the remaining matched edges are critical. Parallel copies count as one edge.
+    pub fn edge_criticality(&self) -> EdgeCriticality {
+        let matching = hopcroft_karp(self);
+        let (matched_left, matched_right) = (matching.matched_left(), matching.matched_right());
+        let even_left = even_from_free(&self.left_nodes, matched_left, matched_right);
+        let even_right = even_from_free(&self.right_nodes, matched_right, matched_left);
+        let on_cycle = on_alternating_cycle(&self.left_nodes, matched_left, matched_right);
+
+        let mut critical = matching
+            .pairs()
+            .filter(|&(i, j)| !even_left[i] && !even_right[j] && !on_cycle[i])
+            .collect::<Vec<_>>();
+        critical.sort_unstable();
+        EdgeCriticality { matching, critical }
+    }
+
+    pub fn vertex_failures(
+        &self,
+        rng: &mut StdRng,
+        sample_size: Option<usize>,
+    ) -> RobustnessReport {
+        let (l, r) = (self.left_nodes.len(), self.right_nodes.len());
+        let matching = hopcroft_karp(self);
+        let (matched_left, matched_right) = (matching.matched_left(), matching.matched_right());
+
+        let vertices: Vec<usize> = match sample_size {
+            Some(k) if k < l + r => {
+                let mut picked = sample(rng, l + r, k).into_vec();
+                picked.sort_unstable();
+                picked
+            }
+            _ => (0..l + r).collect(),
+        };
+
+        let failures = vertices
+            .into_iter()
+            .map(|code| {
+                let (side, vertex) = if code < l {
+                    (Side::Left, code)
+                } else {
+                    (Side::Right, code - l)
+                };
+                VertexFailure {
+                    side,
+                    vertex,
+                    drop: self.failure_drop(matched_left, matched_right, side, vertex),
+                }
+            })
+            .collect();
+
+        RobustnessReport {
+            baseline: matching.len(),
+            failures,
+        }
+    }
+
+    fn failure_drop(
+        &self,
+        matched_left: &[Option<usize>],
+        matched_right: &[Option<usize>],
+        side: Side,
+        vertex: usize,
+    ) -> usize {
+        let repaired = match side {
+            Side::Left => match matched_left[vertex] {
+                None => return 0,
+                Some(freed) => reaches_free(
+                    &self.right_nodes,
+                    &self.left_nodes,
+                    matched_left,
+                    freed,
+                    vertex,
+                ),
+            },
+            Side::Right => match matched_right[vertex] {
+                None => return 0,
+                Some(freed) => reaches_free(
+                    &self.left_nodes,
+                    &self.right_nodes,
+                    matched_right,
+                    freed,
+                    vertex,
+                ),
+            },
+        };
+        usize::from(!repaired)
+    }
+}
+
+fn reaches_free<T>(
+    start_side: &[Node<T>],
+    other_side: &[Node<T>],
+    mate: &[Option<usize>],
+    start: usize,
+    removed: usize,
+) -> bool {
+    let mut visited = vec![false; other_side.len()];
+    visited[removed] = true;
+    let mut stack = vec![start];
+
+    while let Some(x) = stack.pop() {
+        for &y in &*start_side[x].neighbours.borrow() {
+            if visited[y] {
+                continue;
+            }
+            visited[y] = true;
+            match mate[y] {
+                None => return true,
+                Some(next) => stack.push(next),
+            }
+        }
+    }
+    false
+}
+
+/// Vertices of `side` reachable from its free vertices by alternating paths, the free
+/// vertices included.
+fn even_from_free<T>(
+    side: &[Node<T>],
+    mate: &[Option<usize>],
+    other_mate: &[Option<usize>],
+) -> Vec<bool> {
+    let mut reached: Vec<bool> = mate.iter().map(Option::is_none).collect();
+    let mut stack: Vec<usize> = (0..side.len()).filter(|&x| reached[x]).collect();
+    while let Some(x) = stack.pop() {
+        for &y in &*side[x].neighbours.borrow() {
+            if let Some(next) = other_mate[y] {
+                if !reached[next] {
+                    reached[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+    }
+    reached
+}
+
+/// Left vertices whose matched edge lies on an alternating cycle, that is whose strongly
+/// connected component in the graph `u -> mate(v)` over unmatched edges `(u, v)` has more than
+/// one vertex. Tarjan's algorithm with an explicit stack.
+fn on_alternating_cycle<T>(
+    left: &[Node<T>],
+    matched_left: &[Option<usize>],
+    matched_right: &[Option<usize>],
+) -> Vec<bool> {
+    const UNVISITED: usize = usize::MAX;
+    let l = left.len();
+    let successors: Vec<Vec<usize>> = (0..l)
+        .map(|u| match matched_left[u] {
+            None => Vec::new(),
+            Some(own) => left[u]
+                .neighbours
+                .borrow()
+                .iter()
+                .filter(|&&v| v != own)
+                .filter_map(|&v| matched_right[v])
+                .collect(),
+        })
+        .collect();
+
+    let mut index = vec![UNVISITED; l];
+    let mut low = vec![0; l];
+    let mut on_stack = vec![false; l];
+    let mut component = Vec::new();
+    let mut on_cycle = vec![false; l];
+    let mut next_index = 0;
+
+    for root in 0..l {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        let mut calls = vec![(root, 0)];
+        index[root] = next_index;
+        low[root] = next_index;
+        next_index += 1;
+        component.push(root);
+        on_stack[root] = true;
+
+        while let Some(&mut (u, ref mut next)) = calls.last_mut() {
+            if let Some(&w) = successors[u].get(*next) {
+                *next += 1;
+                if index[w] == UNVISITED {
+                    index[w] = next_index;
+                    low[w] = next_index;
+                    next_index += 1;
+                    component.push(w);
+                    on_stack[w] = true;
+                    calls.push((w, 0));
+                } else if on_stack[w] {
+                    low[u] = low[u].min(index[w]);
+                }
+                continue;
+            }
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                low[parent] = low[parent].min(low[u]);
+            }
+            if low[u] == index[u] {
+                let start = component.iter().rposition(|&x| x == u).expect("root is on the stack");
+                let cyclic = component.len() - start > 1;
+                for x in component.drain(start..) {
+                    on_stack[x] = false;
+                    on_cycle[x] = cyclic;
+                }
+            }
+        }
+    }
+    on_cycle
+}
@@ -0,0 +1,82 @@
+use rand::prelude::*;
+use rand::seq::index::sample;
+
+use crate::error::{Error, Result};
+use crate::graph::BipartiteGraph;
+use crate::matching::Matching;
+
+/// An induced subgraph together with the original index of each of its vertices.
+pub struct SampledSubgraph<T> {
+    pub graph: BipartiteGraph<T>,
+    pub left: Vec<usize>,
+    pub right: Vec<usize>,
+}
+
+impl<T> SampledSubgraph<T> {
+    pub fn original_left(&self, i: usize) -> Option<usize> {
+        self.left.get(i).copied()
+    }
+
+    pub fn original_right(&self, j: usize) -> Option<usize> {
+        self.right.get(j).copied()
+    }
+
+    /// Pairs of a matching on the sample, translated to original indices.
+    pub fn original_pairs(&self, matching: &Matching) -> Vec<(usize, usize)> {
+        matching
+            .pairs()
+            .map(|(i, j)| (self.left[i], self.right[j]))
+            .collect()
+    }
+}
+
+impl<T: Clone> BipartiteGraph<T> {
+    pub fn sample_subgraph(
+        &self,
+        rng: &mut StdRng,
+        left_fraction: f64,
+        right_fraction: f64,
+    ) -> Result<SampledSubgraph<T>> {
+        let left = sample_indices(rng, self.left_nodes.len(), left_fraction)?;
+        let right = sample_indices(rng, self.right_nodes.len(), right_fraction)?;
+
+        let mut position = vec![usize::MAX; self.right_nodes.len()];
+        for (new, &old) in right.iter().enumerate() {
+            position[old] = new;
+        }
+
+        let mut edges = Vec::new();
+        for (new, &old) in left.iter().enumerate() {
+            for &j in &*self.left_nodes[old].neighbours.borrow() {
+                if position[j] != usize::MAX {
+                    edges.push((new, position[j]));
+                }
+            }
+        }
+
+        let left_data = left
+            .iter()
+            .map(|&i| self.left_nodes[i].data.clone())
+            .collect();
+        let right_data = right
+            .iter()
+            .map(|&j| self.right_nodes[j].data.clone())
+            .collect();
+        Ok(SampledSubgraph {
+            graph: BipartiteGraph::from_edges_with_data(left_data, right_data, edges)?,
+            left,
+            right,
+        })
+    }
+}
+
+fn sample_indices(rng: &mut StdRng, len: usize, fraction: f64) -> Result<Vec<usize>> {
+    if !(0.0..=1.0).contains(&fraction) {
+        return Err(Error::InvalidParameter(format!(
+            "sample fraction {fraction} is not in [0, 1]"
+        )));
+    }
+    let mut indices = sample(rng, len, (fraction * len as f64).round() as usize).into_vec();
+    indices.sort_unstable();
+    Ok(indices)
+}
@@ -0,0 +1,167 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::csr::read_csv_edges;
+use crate::error::{Error, Result};
+use crate::graph::{BipartiteGraph, Duplicates};
+use crate::hopcroft_karp::hopcroft_karp;
+use crate::metadata::json_string;
+
+const MAX_BODY: usize = 16 << 20;
+const MAX_HEAD: u64 = 16 << 10;
+const MAX_CONNECTIONS: usize = 64;
+/// Largest index plus one accepted on either side, checked before the graph is allocated.
+const MAX_VERTICES: usize = 1 << 20;
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Answers requests on `bind:port`, one thread per connection. Connections beyond
+/// [`MAX_CONNECTIONS`] get `503`, and one that stalls for [`TIMEOUT`] is dropped.
+pub fn serve(bind: &str, port: u16) -> io::Result<()> {
+    let listener = TcpListener::bind((bind, port))?;
+    let open = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        if open.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            open.fetch_sub(1, Ordering::SeqCst);
+            let _ = respond(
+                &mut stream,
+                "503 Service Unavailable",
+                &error_json("too many connections"),
+            );
+            continue;
+        }
+        let slot = Slot(Arc::clone(&open));
+        thread::spawn(move || {
+            let _slot = slot;
+            let _ = handle(stream);
+        });
+    }
+    Ok(())
+}
+
+/// A connection's share of [`MAX_CONNECTIONS`], given back on drop so that a handler that
+/// panics still frees it.
+struct Slot(Arc<AtomicUsize>);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn handle(stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    let mut head = (&mut reader).take(MAX_HEAD);
+    let mut request_line = String::new();
+    head.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if head.read_line(&mut header)? == 0 {
+            if head.limit() == 0 {
+                return respond(
+                    &mut writer,
+                    "431 Request Header Fields Too Large",
+                    &error_json("headers too large"),
+                );
+            }
+            break;
+        }
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    if target != "/" && target != "/match" {
+        return respond(&mut writer, "404 Not Found", &error_json("not found"));
+    }
+    if method != "POST" {
+        return respond(
+            &mut writer,
+            "405 Method Not Allowed",
+            &error_json("use POST"),
+        );
+    }
+    if content_length > MAX_BODY {
+        return respond(
+            &mut writer,
+            "413 Payload Too Large",
+            &error_json("body too large"),
+        );
+    }
+
+    let mut body = Vec::new();
+    reader.take(content_length as u64).read_to_end(&mut body)?;
+    if body.len() < content_length {
+        return respond(
+            &mut writer,
+            "400 Bad Request",
+            &error_json("body shorter than Content-Length"),
+        );
+    }
+    match solve(&body) {
+        Ok(json) => respond(&mut writer, "200 OK", &json),
+        Err(err) => respond(
+            &mut writer,
+            "400 Bad Request",
+            &error_json(&err.to_string()),
+        ),
+    }
+}
+
+fn solve(body: &[u8]) -> Result<String> {
+    let mut edges = Vec::new();
+    let mut too_large = None;
+    let declared = read_csv_edges(body, |i, j| {
+        if i >= MAX_VERTICES || j >= MAX_VERTICES {
+            too_large.get_or_insert(i.max(j));
+        } else {
+            edges.push((i, j));
+        }
+    })?;
+    // A declared size is the number of vertices, so its last index counts against the limit.
+    let declared_last = declared.and_then(|(l, r)| l.max(r).checked_sub(1));
+    if let Some(index) = too_large.or(declared_last.filter(|&last| last >= MAX_VERTICES)) {
+        return Err(Error::IndexOutOfRange {
+            index,
+            len: MAX_VERTICES,
+        });
+    }
+
+    let (l, r) = declared.unwrap_or_else(|| {
+        let l = edges.iter().map(|&(i, _)| i + 1).max().unwrap_or(0);
+        let r = edges.iter().map(|&(_, j)| j + 1).max().unwrap_or(0);
+        (l, r)
+    });
+    let graph = BipartiteGraph::<()>::from_edges_checked(l, r, edges, Duplicates::Reject)?;
+    Ok(hopcroft_karp(&graph).to_json())
+}
+
+fn respond(w: &mut TcpStream, status: &str, body: &str) -> io::Result<()> {
+    write!(
+        w,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    w.flush()
+}
+
+fn error_json(message: &str) -> String {
+    format!("{{\"error\":{}}}", json_string(message))
+}
@@ -0,0 +1,717 @@
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use rand::prelude::*;
+
+use crate::assignment::checked_total;
+use crate::error::{Error, Result};
+use crate::flow::FlowNetwork;
+use crate::matching::Matching;
+
+#[derive(Clone, Debug)]
+pub struct SmtiInstance {
+    men: Vec<Vec<Vec<usize>>>,
+    women: Vec<Vec<Vec<usize>>>,
+    men_rank: Vec<HashMap<usize, usize>>,
+    women_rank: Vec<HashMap<usize, usize>>,
+}
+
+impl SmtiInstance {
+    pub fn new(men: Vec<Vec<Vec<usize>>>, women: Vec<Vec<Vec<usize>>>) -> Result<SmtiInstance> {
+        let men_rank = ranks(&men, women.len())?;
+        let women_rank = ranks(&women, men.len())?;
+
+        let men: Vec<_> = men
+            .into_iter()
+            .enumerate()
+            .map(|(m, list)| mutually_acceptable(list, |w| women_rank[w].contains_key(&m)))
+            .collect();
+        let women: Vec<_> = women
+            .into_iter()
+            .enumerate()
+            .map(|(w, list)| mutually_acceptable(list, |m| men_rank[m].contains_key(&w)))
+            .collect();
+        let men_rank = ranks(&men, women_rank.len())?;
+        let women_rank = ranks(&women, men_rank.len())?;
+
+        Ok(SmtiInstance {
+            men,
+            women,
+            men_rank,
+            women_rank,
+        })
+    }
+
+    pub fn from_strict(men: Vec<Vec<usize>>, women: Vec<Vec<usize>>) -> Result<SmtiInstance> {
+        let untie = |lists: Vec<Vec<usize>>| -> Vec<Vec<Vec<usize>>> {
+            lists
+                .into_iter()
+                .map(|list| list.into_iter().map(|x| vec![x]).collect())
+                .collect()
+        };
+        SmtiInstance::new(untie(men), untie(women))
+    }
+
+    pub fn men_len(&self) -> usize {
+        self.men.len()
+    }
+
+    pub fn women_len(&self) -> usize {
+        self.women.len()
+    }
+
+    pub fn men_preferences(&self, m: usize) -> &[Vec<usize>] {
+        &self.men[m]
+    }
+
+    pub fn women_preferences(&self, w: usize) -> &[Vec<usize>] {
+        &self.women[w]
+    }
+
+    pub fn has_men_ties(&self) -> bool {
+        self.men.iter().flatten().any(|group| group.len() > 1)
+    }
+
+    pub fn gale_shapley(&self) -> Matching {
+        self.propose(false)
+    }
+
+    /// Király's promotion algorithm. The 3/2 guarantee needs ties on the women's side only;
+    /// ties in men's lists are broken by listed order.
+    pub fn kiraly(&self) -> Matching {
+        self.propose(true)
+    }
+
+    fn propose(&self, promotion: bool) -> Matching {
+        let lists: Vec<Vec<usize>> = self
+            .men
+            .iter()
+            .map(|list| list.iter().flatten().copied().collect())
+            .collect();
+
+        let mut husband: Vec<Option<usize>> = vec![None; self.women.len()];
+        let mut next = vec![0; self.men.len()];
+        let mut promoted = vec![false; self.men.len()];
+        let mut free: VecDeque<usize> = (0..self.men.len()).collect();
+
+        while let Some(m) = free.pop_front() {
+            if next[m] == lists[m].len() {
+                if promotion && !promoted[m] && !lists[m].is_empty() {
+                    promoted[m] = true;
+                    next[m] = 0;
+                    free.push_back(m);
+                }
+                continue;
+            }
+
+            let w = lists[m][next[m]];
+            next[m] += 1;
+
+            let accepted = match husband[w] {
+                None => true,
+                Some(h) => {
+                    let (rm, rh) = (self.women_rank[w][&m], self.women_rank[w][&h]);
+                    rm < rh || (rm == rh && promoted[m] && !promoted[h])
+                }
+            };
+
+            if accepted {
+                if let Some(h) = husband[w].replace(m) {
+                    free.push_back(h);
+                }
+            } else {
+                free.push_back(m);
+            }
+        }
+
+        Matching::from_matched_right(self.men.len(), husband)
+    }
+
+    pub fn is_weakly_stable(&self, matching: &Matching) -> bool {
+        let husband = matching.matched_right();
+        if husband.len() != self.women.len() {
+            return false;
+        }
+
+        let mut wife = vec![None; self.men.len()];
+        for (m, w) in matching.pairs() {
+            if m >= self.men.len() || wife[m].is_some() || !self.men_rank[m].contains_key(&w) {
+                return false;
+            }
+            wife[m] = Some(w);
+        }
+
+        for (m, ranks) in self.men_rank.iter().enumerate() {
+            for (&w, &rank) in ranks {
+                let man_prefers = wife[m].is_none_or(|current| rank < ranks[&current]);
+                let woman_prefers = husband[w]
+                    .is_none_or(|current| self.women_rank[w][&m] < self.women_rank[w][&current]);
+                if man_prefers && woman_prefers {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+/// Many-to-one market: students rank schools, each school ranks students by priority and
+/// admits up to its capacity. Both kinds of list are strict; anyone left off a list, or whose
+/// counterpart leaves them off, is unacceptable.
+#[derive(Clone, Debug)]
+pub struct SchoolChoice {
+    students: Vec<Vec<usize>>,
+    capacities: Vec<usize>,
+    student_rank: Vec<HashMap<usize, usize>>,
+    priority: Vec<HashMap<usize, usize>>,
+}
+
+/// Students' schools. Those from [`SchoolChoice::deferred_acceptance`] list each school's
+/// students in priority order, those from [`SchoolAssignment::new`] by index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchoolAssignment {
+    school: Vec<Option<usize>>,
+    enrolled: Vec<Vec<usize>>,
+}
+
+impl SchoolAssignment {
+    /// An assignment computed elsewhere, for checking with [`SchoolChoice::is_stable`].
+    pub fn new(schools_len: usize, school: Vec<Option<usize>>) -> Result<SchoolAssignment> {
+        let mut enrolled = vec![Vec::new(); schools_len];
+        for (s, &c) in school.iter().enumerate() {
+            if let Some(c) = c {
+                if c >= schools_len {
+                    return Err(Error::IndexOutOfRange {
+                        index: c,
+                        len: schools_len,
+                    });
+                }
+                enrolled[c].push(s);
+            }
+        }
+        Ok(SchoolAssignment { school, enrolled })
+    }
+
+    pub fn school_of(&self, student: usize) -> Option<usize> {
+        self.school.get(student).copied().flatten()
+    }
+
+    pub fn students_of(&self, school: usize) -> &[usize] {
+        self.enrolled.get(school).map_or(&[], Vec::as_slice)
+    }
+
+    /// Number of assigned students.
+    pub fn len(&self) -> usize {
+        self.school.iter().flatten().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl SchoolChoice {
+    pub fn new(
+        students: Vec<Vec<usize>>,
+        priorities: Vec<Vec<usize>>,
+        capacities: Vec<usize>,
+    ) -> Result<SchoolChoice> {
+        if priorities.len() != capacities.len() {
+            return Err(Error::InvalidParameter(format!(
+                "{} priority lists for {} capacities",
+                priorities.len(),
+                capacities.len()
+            )));
+        }
+        let strict = |lists: &[Vec<usize>], other_len: usize| {
+            let lists: Vec<Vec<Vec<usize>>> = lists
+                .iter()
+                .map(|list| list.iter().map(|&x| vec![x]).collect())
+                .collect();
+            ranks(&lists, other_len)
+        };
+        let student_rank = strict(&students, capacities.len())?;
+        let priority = strict(&priorities, students.len())?;
+
+        let students: Vec<Vec<usize>> = students
+            .into_iter()
+            .enumerate()
+            .map(|(s, list)| {
+                let mut seen = HashSet::new();
+                list.into_iter()
+                    .filter(|&c| priority[c].contains_key(&s) && seen.insert(c))
+                    .collect()
+            })
+            .collect();
+        let student_rank = student_rank
+            .into_iter()
+            .enumerate()
+            .map(|(s, mut rank)| {
+                rank.retain(|&c, _| priority[c].contains_key(&s));
+                rank
+            })
+            .collect();
+
+        Ok(SchoolChoice {
+            students,
+            capacities,
+            student_rank,
+            priority,
+        })
+    }
+
+    pub fn students_len(&self) -> usize {
+        self.students.len()
+    }
+
+    pub fn schools_len(&self) -> usize {
+        self.capacities.len()
+    }
+
+    pub fn capacity(&self, school: usize) -> usize {
+        self.capacities[school]
+    }
+
+    /// Student-proposing deferred acceptance: the stable assignment every student likes at
+    /// least as much as any other stable one. Each school holds its best proposals so far
+    /// in a heap keyed by priority and rejects the worst once over capacity.
+    pub fn deferred_acceptance(&self) -> SchoolAssignment {
+        let (n, k) = (self.students.len(), self.capacities.len());
+        let mut held: Vec<BinaryHeap<(usize, usize)>> = vec![BinaryHeap::new(); k];
+        let mut next = vec![0; n];
+        let mut free: VecDeque<usize> = (0..n).collect();
+
+        while let Some(s) = free.pop_front() {
+            let Some(&c) = self.students[s].get(next[s]) else {
+                continue;
+            };
+            next[s] += 1;
+
+            held[c].push((self.priority[c][&s], s));
+            if held[c].len() > self.capacities[c] {
+                let (_, rejected) = held[c].pop().expect("over capacity");
+                free.push_back(rejected);
+            }
+        }
+
+        let mut school = vec![None; n];
+        let enrolled = held
+            .into_iter()
+            .enumerate()
+            .map(|(c, heap)| {
+                let students: Vec<usize> = heap.into_sorted_vec().into_iter().map(|(_, s)| s).collect();
+                for &s in &students {
+                    school[s] = Some(c);
+                }
+                students
+            })
+            .collect();
+        SchoolAssignment { school, enrolled }
+    }
+
+    /// Feasible, individually rational and without a student who prefers a school that has
+    /// a free seat or admits someone of lower priority.
+    pub fn is_stable(&self, assignment: &SchoolAssignment) -> bool {
+        let (n, k) = (self.students.len(), self.capacities.len());
+        if assignment.school.len() != n || assignment.enrolled.len() != k {
+            return false;
+        }
+
+        let mut worst = vec![None; k];
+        for (c, students) in assignment.enrolled.iter().enumerate() {
+            if students.len() > self.capacities[c] {
+                return false;
+            }
+            for &s in students {
+                if s >= n
+                    || assignment.school[s] != Some(c)
+                    || !self.student_rank[s].contains_key(&c)
+                {
+                    return false;
+                }
+                let rank = self.priority[c][&s];
+                worst[c] = Some(worst[c].map_or(rank, |w: usize| w.max(rank)));
+            }
+        }
+        if assignment.len() != assignment.enrolled.iter().map(Vec::len).sum::<usize>() {
+            return false;
+        }
+
+        for (s, ranks) in self.student_rank.iter().enumerate() {
+            for (&c, &rank) in ranks {
+                let prefers = assignment.school[s].is_none_or(|current| rank < ranks[&current]);
+                let admits = assignment.enrolled[c].len() < self.capacities[c]
+                    || worst[c].is_some_and(|w| self.priority[c][&s] < w);
+                if prefers && admits {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Stable marriage where every agent values each acceptable partner by a utility, higher is
+/// better. Preferences follow the utilities and must be strict. Stable matchings correspond
+/// to the closed sets of rotations, so [`UtilityMarket::max_utility_stable`] can pick the one
+/// with the largest total utility by a minimum cut instead of enumerating them.
+#[derive(Clone, Debug)]
+pub struct UtilityMarket {
+    instance: SmtiInstance,
+    men_utility: Vec<HashMap<usize, i64>>,
+    women_utility: Vec<HashMap<usize, i64>>,
+}
+
+/// Men `m_i` matched to `w_i` who each move to `w_{i+1}` when the rotation is eliminated, the
+/// change in total utility that causes, and the rotations that must be eliminated first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rotation {
+    pub pairs: Vec<(usize, usize)>,
+    pub gain: i128,
+    pub predecessors: Vec<usize>,
+}
+
+impl UtilityMarket {
+    /// `men[m]` lists `(woman, utility)` for the women `m` accepts, in any order, and
+    /// `women[w]` the same for men. Pairs only one side lists are dropped.
+    pub fn new(men: Vec<Vec<(usize, i64)>>, women: Vec<Vec<(usize, i64)>>) -> Result<UtilityMarket> {
+        let men_utility = utilities(&men, "man")?;
+        let women_utility = utilities(&women, "woman")?;
+        let order = |lists: &[Vec<(usize, i64)>]| -> Vec<Vec<usize>> {
+            lists
+                .iter()
+                .map(|list| {
+                    let mut list = list.clone();
+                    list.sort_by_key(|&(x, u)| (std::cmp::Reverse(u), x));
+                    list.into_iter().map(|(x, _)| x).collect()
+                })
+                .collect()
+        };
+        let instance = SmtiInstance::from_strict(order(&men), order(&women))?;
+        Ok(UtilityMarket {
+            instance,
+            men_utility,
+            women_utility,
+        })
+    }
+
+    /// The ordinal market the utilities induce.
+    pub fn instance(&self) -> &SmtiInstance {
+        &self.instance
+    }
+
+    pub fn is_stable(&self, matching: &Matching) -> bool {
+        self.instance.is_weakly_stable(matching)
+    }
+
+    /// Sum of both partners' utilities over the matched pairs.
+    pub fn utility(&self, matching: &Matching) -> Result<i64> {
+        let mut values = Vec::with_capacity(2 * matching.len());
+        for (m, w) in matching.pairs() {
+            let (Some(&um), Some(&uw)) = (
+                self.men_utility.get(m).and_then(|u| u.get(&w)),
+                self.women_utility.get(w).and_then(|u| u.get(&m)),
+            ) else {
+                return Err(Error::InvalidMatching);
+            };
+            values.extend([um, uw]);
+        }
+        checked_total("total utility", values)
+    }
+
+    pub fn man_optimal(&self) -> Matching {
+        self.instance.gale_shapley()
+    }
+
+    pub fn woman_optimal(&self) -> Matching {
+        let swapped = SmtiInstance {
+            men: self.instance.women.clone(),
+            women: self.instance.men.clone(),
+            men_rank: self.instance.women_rank.clone(),
+            women_rank: self.instance.men_rank.clone(),
+        };
+        Matching::from_matched_right(
+            self.instance.men_len(),
+            swapped.gale_shapley().matched_left().to_vec(),
+        )
+    }
+
+    /// Every rotation, in an order where each comes after its predecessors, found by
+    /// eliminating exposed rotations from the man-optimal matching until the woman-optimal
+    /// one is reached. Predecessors come from the two labelling rules of Gusfield and Irving;
+    /// their transitive closure is the rotation poset.
+    pub fn rotations(&self) -> Vec<Rotation> {
+        let inst = &self.instance;
+        let start = self.man_optimal();
+        let mut wife = start.matched_left().to_vec();
+        let mut husband = start.matched_right().to_vec();
+        let last = self.woman_optimal().matched_left().to_vec();
+        let position = |m: usize, w: usize| inst.men_rank[m][&w];
+
+        let mut next: Vec<usize> = wife
+            .iter()
+            .enumerate()
+            .map(|(m, w)| w.map_or(0, |w| position(m, w) + 1))
+            .collect();
+        let mut on_stack = vec![false; inst.men_len()];
+        let mut stack = Vec::new();
+        let mut cursor = 0;
+        let mut cycles: Vec<Vec<usize>> = Vec::new();
+        let mut moves: Vec<Vec<(usize, usize, usize)>> = vec![Vec::new(); inst.men_len()];
+        let mut gains = Vec::new();
+
+        loop {
+            let Some(&m) = stack.last() else {
+                while cursor < inst.men_len() && wife[cursor] == last[cursor] {
+                    cursor += 1;
+                }
+                if cursor == inst.men_len() {
+                    break;
+                }
+                stack.push(cursor);
+                on_stack[cursor] = true;
+                continue;
+            };
+
+            // The next woman on m's list who would rather have him than her husband. Her
+            // husband only improves, so the pointer never has to move back.
+            let list = inst.men[m].iter().flatten();
+            let w = *list
+                .skip(next[m])
+                .find(|&&w| {
+                    husband[w].is_some_and(|h| inst.women_rank[w][&m] < inst.women_rank[w][&h])
+                })
+                .expect("a man short of his woman-optimal partner has a next woman");
+            next[m] = position(m, w);
+            let h = husband[w].expect("only matched women are considered");
+            if !on_stack[h] {
+                stack.push(h);
+                on_stack[h] = true;
+                continue;
+            }
+
+            let start = stack.iter().rposition(|&x| x == h).expect("h is on the stack");
+            let men: Vec<usize> = stack.drain(start..).collect();
+            let women: Vec<usize> = men.iter().map(|&x| wife[x].expect("matched")).collect();
+            let k = cycles.len();
+            let mut gain = 0;
+            for (i, &x) in men.iter().enumerate() {
+                on_stack[x] = false;
+                let (from, to) = (women[i], women[(i + 1) % men.len()]);
+                gain += i128::from(self.men_utility[x][&to]) - i128::from(self.men_utility[x][&from]);
+                let old = men[(i + 1) % men.len()];
+                gain += i128::from(self.women_utility[to][&x]) - i128::from(self.women_utility[to][&old]);
+                moves[x].push((position(x, from), position(x, to), k));
+            }
+            for (i, &x) in men.iter().enumerate() {
+                let to = women[(i + 1) % men.len()];
+                wife[x] = Some(to);
+                husband[to] = Some(x);
+                next[x] = position(x, to) + 1;
+            }
+            cycles.push(men);
+            gains.push((gain, women));
+        }
+
+        // Rule 2 labels: the rotation after which woman w stops accepting man m, for every m
+        // she ranks strictly between her new and her old husband.
+        let mut refusal: HashMap<(usize, usize), usize> = HashMap::new();
+        for (k, (men, (_, women))) in cycles.iter().zip(&gains).enumerate() {
+            for i in 0..men.len() {
+                let w = women[(i + 1) % men.len()];
+                let (better, worse) = (men[i], men[(i + 1) % men.len()]);
+                let ranks = &inst.women_rank[w];
+                for &m in inst.women[w]
+                    .iter()
+                    .flatten()
+                    .skip(ranks[&better] + 1)
+                    .take(ranks[&worse] - ranks[&better] - 1)
+                {
+                    refusal.insert((m, w), k);
+                }
+            }
+        }
+
+        let mut predecessors = vec![Vec::new(); cycles.len()];
+        for (m, moves) in moves.iter().enumerate() {
+            let list: Vec<usize> = inst.men[m].iter().flatten().copied().collect();
+            for (t, &(from, to, k)) in moves.iter().enumerate() {
+                if t > 0 {
+                    predecessors[k].push(moves[t - 1].2);
+                }
+                for &w in &list[from + 1..to] {
+                    if let Some(&r) = refusal.get(&(m, w)) {
+                        predecessors[k].push(r);
+                    }
+                }
+            }
+        }
+
+        cycles
+            .into_iter()
+            .zip(gains)
+            .zip(predecessors)
+            .map(|((men, (gain, women)), mut predecessors)| {
+                predecessors.sort_unstable();
+                predecessors.dedup();
+                Rotation {
+                    pairs: men.into_iter().zip(women).collect(),
+                    gain,
+                    predecessors,
+                }
+            })
+            .collect()
+    }
+
+    /// The stable matching with the largest total utility and that total. The man-optimal
+    /// matching plus a closed set of rotations covers every stable matching, so this is a
+    /// maximum-weight closure of the rotation poset, found by a minimum cut. Ties go to the
+    /// matching closest to man-optimal.
+    pub fn max_utility_stable(&self) -> Result<(Matching, i64)> {
+        let rotations = self.rotations();
+        let n = rotations.len();
+        let (source, sink) = (n, n + 1);
+
+        let positive: i128 = rotations.iter().map(|r| r.gain.max(0)).sum();
+        let capacity = |value: i128| {
+            usize::try_from(value)
+                .map_err(|_| Error::CostOverflow(format!("rotation gain total {positive}")))
+        };
+        let unbounded = capacity(positive + 1)?;
+        let mut network = FlowNetwork::new(n + 2);
+        for (k, rotation) in rotations.iter().enumerate() {
+            if rotation.gain > 0 {
+                network.add_edge(source, k, capacity(rotation.gain)?)?;
+            } else if rotation.gain < 0 {
+                network.add_edge(k, sink, capacity(-rotation.gain)?)?;
+            }
+            for &p in &rotation.predecessors {
+                network.add_edge(k, p, unbounded)?;
+            }
+        }
+        network.max_flow(source, sink);
+        let chosen = network.source_side(source);
+
+        let mut wife = self.man_optimal().matched_left().to_vec();
+        for (k, rotation) in rotations.iter().enumerate() {
+            if !chosen[k] {
+                continue;
+            }
+            let len = rotation.pairs.len();
+            for (i, &(m, _)) in rotation.pairs.iter().enumerate() {
+                wife[m] = Some(rotation.pairs[(i + 1) % len].1);
+            }
+        }
+        let mut husband = vec![None; self.instance.women_len()];
+        for (m, w) in wife.iter().enumerate() {
+            if let Some(w) = *w {
+                husband[w] = Some(m);
+            }
+        }
+        let matching = Matching::from_matched_right(self.instance.men_len(), husband);
+        let total = self.utility(&matching)?;
+        Ok((matching, total))
+    }
+}
+
+fn utilities(lists: &[Vec<(usize, i64)>], agent: &str) -> Result<Vec<HashMap<usize, i64>>> {
+    lists
+        .iter()
+        .enumerate()
+        .map(|(a, list)| {
+            let mut utility = HashMap::new();
+            let mut seen = HashMap::new();
+            for &(x, u) in list {
+                if utility.insert(x, u).is_some() {
+                    return Err(Error::InvalidParameter(format!(
+                        "{agent} {a} lists partner {x} twice"
+                    )));
+                }
+                if let Some(y) = seen.insert(u, x) {
+                    return Err(Error::InvalidParameter(format!(
+                        "{agent} {a} gives {y} and {x} the same utility; preferences must be strict"
+                    )));
+                }
+            }
+            Ok(utility)
+        })
+        .collect()
+}
+
+pub fn correlated_market(
+    rng: &mut StdRng,
+    men: usize,
+    women: usize,
+    men_correlation: f64,
+    women_correlation: f64,
+) -> Result<SmtiInstance> {
+    for correlation in [men_correlation, women_correlation] {
+        if !(0.0..=1.0).contains(&correlation) {
+            return Err(Error::InvalidParameter(format!(
+                "correlation {correlation} is not in [0, 1]"
+            )));
+        }
+    }
+
+    let men_lists = correlated_lists(rng, men, women, men_correlation);
+    let women_lists = correlated_lists(rng, women, men, women_correlation);
+    SmtiInstance::from_strict(men_lists, women_lists)
+}
+
+fn correlated_lists(
+    rng: &mut StdRng,
+    agents: usize,
+    targets: usize,
+    correlation: f64,
+) -> Vec<Vec<usize>> {
+    let common: Vec<f64> = (0..targets).map(|_| rng.gen()).collect();
+    (0..agents)
+        .map(|_| {
+            let utility: Vec<f64> = common
+                .iter()
+                .map(|&c| correlation * c + (1.0 - correlation) * rng.gen::<f64>())
+                .collect();
+            let mut list: Vec<usize> = (0..targets).collect();
+            list.sort_by(|&a, &b| utility[b].total_cmp(&utility[a]));
+            list
+        })
+        .collect()
+}
+
+fn ranks(lists: &[Vec<Vec<usize>>], other_len: usize) -> Result<Vec<HashMap<usize, usize>>> {
+    lists
+        .iter()
+        .map(|list| {
+            let mut rank = HashMap::new();
+            for (r, group) in list.iter().enumerate() {
+                for &x in group {
+                    if x >= other_len {
+                        return Err(Error::IndexOutOfRange {
+                            index: x,
+                            len: other_len,
+                        });
+                    }
+                    rank.entry(x).or_insert(r);
+                }
+            }
+            Ok(rank)
+        })
+        .collect()
+}
+
+fn mutually_acceptable(list: Vec<Vec<usize>>, accepts: impl Fn(usize) -> bool) -> Vec<Vec<usize>> {
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
+    for group in list {
+        let mut kept = Vec::new();
+        for x in group {
+            if accepts(x) && seen.insert(x) {
+                kept.push(x);
+            }
+        }
+        if !kept.is_empty() {
+            result.push(kept);
+        }
+    }
+    result
+}
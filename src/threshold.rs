@@ -0,0 +1,162 @@
+use crate::error::{Error, Result};
+use crate::weights::EdgeWeights;
+
+/// Which weights an edge may have to stay in the graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Threshold {
+    AtMost(i64),
+    AtLeast(i64),
+}
+
+impl Threshold {
+    pub fn keeps(self, weight: i64) -> bool {
+        match self {
+            Threshold::AtMost(bound) => weight <= bound,
+            Threshold::AtLeast(bound) => weight >= bound,
+        }
+    }
+}
+
+/// Direction of a [`threshold_curve`]: `AtMost` raises an upper bound on the weights, `AtLeast`
+/// lowers a lower bound.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sweep {
+    AtMost,
+    AtLeast,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ThresholdPoint {
+    pub threshold: i64,
+    /// Maximum matching size over the edges the threshold keeps.
+    pub size: usize,
+}
+
+impl ThresholdPoint {
+    pub const CSV_HEADER: &'static str = "threshold,size";
+
+    pub fn csv_fields(&self) -> String {
+        format!("{},{}", self.threshold, self.size)
+    }
+}
+
+impl EdgeWeights {
+    /// The edges `threshold` keeps, to hand any weighted solver a restricted instance.
+    pub fn within(&self, threshold: Threshold) -> EdgeWeights {
+        let mut kept = EdgeWeights::new();
+        for ((i, j), w) in self.iter() {
+            if threshold.keeps(w) {
+                kept.set(i, j, w);
+            }
+        }
+        kept
+    }
+}
+
+/// Maximum matching size at every distinct weight used as the threshold, in sweep order.
+/// Edges are added by weight into one growing graph and the matching is only ever augmented,
+/// so each threshold costs one search pass that finds nothing instead of a solve from scratch.
+pub fn threshold_curve(
+    left_len: usize,
+    right_len: usize,
+    weights: &EdgeWeights,
+    sweep: Sweep,
+) -> Result<Vec<ThresholdPoint>> {
+    let mut edges: Vec<(i64, usize, usize)> = Vec::with_capacity(weights.len());
+    for ((i, j), w) in weights.iter() {
+        if i >= left_len {
+            return Err(Error::IndexOutOfRange {
+                index: i,
+                len: left_len,
+            });
+        }
+        if j >= right_len {
+            return Err(Error::IndexOutOfRange {
+                index: j,
+                len: right_len,
+            });
+        }
+        edges.push((w, i, j));
+    }
+    edges.sort_unstable();
+    if sweep == Sweep::AtLeast {
+        edges.reverse();
+    }
+
+    let mut adjacency = vec![Vec::new(); left_len];
+    let mut matched_left = vec![None; left_len];
+    let mut matched_right = vec![None; right_len];
+    let mut visited = vec![false; left_len];
+    let mut size = 0;
+    let mut curve = Vec::new();
+
+    let mut start = 0;
+    while start < edges.len() {
+        let threshold = edges[start].0;
+        let end = start + edges[start..].partition_point(|&(w, _, _)| w == threshold);
+        for &(_, i, j) in &edges[start..end] {
+            adjacency[i].push(j);
+        }
+        start = end;
+
+        // A pass that augments nowhere proves the matching maximum, since with no flips its
+        // shared visited set is a plain search from every free left vertex.
+        loop {
+            visited.fill(false);
+            let mut augmented = false;
+            for root in 0..left_len {
+                if matched_left[root].is_none()
+                    && augment(
+                        root,
+                        &adjacency,
+                        &mut matched_left,
+                        &mut matched_right,
+                        &mut visited,
+                    )
+                {
+                    size += 1;
+                    augmented = true;
+                }
+            }
+            if !augmented {
+                break;
+            }
+        }
+        curve.push(ThresholdPoint { threshold, size });
+    }
+    Ok(curve)
+}
+
+fn augment(
+    root: usize,
+    adjacency: &[Vec<usize>],
+    matched_left: &mut [Option<usize>],
+    matched_right: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool {
+    let mut stack = vec![(root, 0)];
+    visited[root] = true;
+    while let Some(&mut (v, ref mut next)) = stack.last_mut() {
+        let Some(&j) = adjacency[v].get(*next) else {
+            stack.pop();
+            continue;
+        };
+        *next += 1;
+        match matched_right[j] {
+            None => {
+                for &(u, k) in &stack {
+                    let to = adjacency[u][k - 1];
+                    matched_left[u] = Some(to);
+                    matched_right[to] = Some(u);
+                }
+                return true;
+            }
+            Some(u) if !visited[u] => {
+                visited[u] = true;
+                stack.push((u, 0));
+            }
+            Some(_) => {}
+        }
+    }
+    false
+}
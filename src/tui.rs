@@ -0,0 +1,317 @@
+use std::fmt::Write as _;
+use std::io::{self, Read, Write};
+
+use crate::graph::BipartiteGraph;
+use crate::kuhn::{KuhnState, Observer};
+
+const WINDOW: usize = 20;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const INVERSE: &str = "\x1b[7m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+
+/// What the last keypress did: the root searched, the flips of its augmenting path from the
+/// root outwards (empty when none was found) and the edges scanned.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Step {
+    pub root: usize,
+    pub path: Vec<(usize, usize)>,
+    pub edge_scans: u64,
+}
+
+#[derive(Default)]
+struct StepRecorder {
+    flips: Vec<(usize, usize)>,
+    edge_scans: u64,
+}
+
+impl Observer for StepRecorder {
+    fn edge_scan(&mut self, _left: usize, _right: usize) {
+        self.edge_scans += 1;
+    }
+
+    fn match_flip(&mut self, left: usize, right: usize) {
+        self.flips.push((left, right));
+    }
+}
+
+/// Kuhn's algorithm one root per step, with the state the terminal view draws.
+pub struct Inspector<'g, T> {
+    graph: &'g BipartiteGraph<T>,
+    state: KuhnState<'g, T>,
+    next_root: usize,
+    selected: usize,
+    last: Option<Step>,
+}
+
+impl<'g, T> Inspector<'g, T> {
+    pub fn new(graph: &'g BipartiteGraph<T>) -> Inspector<'g, T> {
+        Inspector {
+            graph,
+            state: KuhnState::new(graph),
+            next_root: 0,
+            selected: 0,
+            last: None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_root == self.graph.left_nodes.len()
+    }
+
+    pub fn last_step(&self) -> Option<&Step> {
+        self.last.as_ref()
+    }
+
+    /// Searches from the next left root. Returns false once every root has been tried.
+    pub fn step(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        let root = self.next_root;
+        self.next_root += 1;
+        let mut recorder = StepRecorder::default();
+        if !self.state.augment_observed(root, &mut recorder) {
+            recorder.flips.clear();
+        }
+        recorder.flips.reverse();
+        self.selected = root;
+        self.last = Some(Step {
+            root,
+            path: recorder.flips,
+            edge_scans: recorder.edge_scans,
+        });
+        true
+    }
+
+    pub fn reset(&mut self) {
+        self.state = KuhnState::new(self.graph);
+        self.next_root = 0;
+        self.last = None;
+    }
+
+    pub fn select(&mut self, v: usize) {
+        self.selected = v.min(self.graph.left_nodes.len().saturating_sub(1));
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    fn mate_of_left(&self, v: usize) -> Option<usize> {
+        self.state
+            .is_left_matched(v)
+            .then(|| {
+                self.graph.left_nodes[v]
+                    .neighbours
+                    .borrow()
+                    .iter()
+                    .copied()
+                    .find(|&j| self.state.match_of_right(j) == Some(v))
+            })
+            .flatten()
+    }
+
+    /// Edges of the last augmenting path, with the matched ones it replaced.
+    fn path_edges(&self) -> Vec<(usize, usize)> {
+        let Some(step) = &self.last else {
+            return Vec::new();
+        };
+        let mut edges = step.path.clone();
+        for pair in step.path.windows(2) {
+            edges.push((pair[1].0, pair[0].1));
+        }
+        edges
+    }
+
+    /// The whole screen as text with ANSI colours: matched edges in green, the last augmenting
+    /// path in yellow, the selected left vertex inverted and roots not yet tried dimmed.
+    pub fn render(&self) -> String {
+        let (l, r) = (self.graph.left_nodes.len(), self.graph.right_nodes.len());
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{BOLD}{l} x {r}, {} edges, matching {}{RESET}",
+            self.graph.num_edges(),
+            self.state.len()
+        );
+        match &self.last {
+            None => out.push_str("no root searched yet\n"),
+            Some(step) if step.path.is_empty() => {
+                let _ = writeln!(
+                    out,
+                    "root L{}: no augmenting path ({} edges scanned)",
+                    step.root, step.edge_scans
+                );
+            }
+            Some(step) => {
+                let mut path = String::new();
+                for (k, &(i, j)) in step.path.iter().enumerate() {
+                    if k > 0 {
+                        path.push_str(" - ");
+                    }
+                    let _ = write!(path, "L{i} = R{j}");
+                }
+                let _ = writeln!(
+                    out,
+                    "root L{}: {YELLOW}{path}{RESET} ({} edges scanned)",
+                    step.root, step.edge_scans
+                );
+            }
+        }
+        out.push('\n');
+
+        let path = self.path_edges();
+        let start = self.selected.saturating_sub(WINDOW / 2).min(l.saturating_sub(WINDOW));
+        for v in start..l.min(start + WINDOW) {
+            let mate = self.mate_of_left(v);
+            let label = format!(
+                "L{v:<4} {}",
+                mate.map_or("-".to_string(), |j| format!("R{j}"))
+            );
+            let style = if v == self.selected {
+                INVERSE
+            } else if v >= self.next_root {
+                DIM
+            } else {
+                ""
+            };
+            let _ = write!(out, "{style}{label:<12}{RESET} :");
+            for &j in self.graph.left_nodes[v].neighbours.borrow().iter() {
+                let colour = if path.contains(&(v, j)) {
+                    YELLOW
+                } else if mate == Some(j) {
+                    GREEN
+                } else {
+                    ""
+                };
+                let _ = write!(out, " {colour}R{j}{RESET}");
+            }
+            out.push('\n');
+        }
+        if l > WINDOW {
+            let _ = writeln!(out, "{DIM}rows {start}..{} of {l}{RESET}", l.min(start + WINDOW));
+        }
+
+        let selected = self.selected;
+        if selected < l {
+            let _ = write!(out, "\nneighbours of L{selected}:");
+            for &j in self.graph.left_nodes[selected].neighbours.borrow().iter() {
+                match self.state.match_of_right(j) {
+                    Some(i) => {
+                        let _ = write!(out, " R{j}={GREEN}L{i}{RESET}");
+                    }
+                    None => {
+                        let _ = write!(out, " R{j}=free");
+                    }
+                }
+            }
+            out.push('\n');
+        }
+        out.push_str("\nspace next root - a run to the end - r reset - j/k select - q quit\n");
+        out
+    }
+}
+
+/// Puts the terminal in non-canonical, no-echo mode until dropped.
+struct RawMode(libc::termios);
+
+impl RawMode {
+    fn enter() -> io::Result<RawMode> {
+        // SAFETY: `termios` is plain data that `tcgetattr` fills in before it is read.
+        let saved = unsafe {
+            let mut termios = std::mem::zeroed::<libc::termios>();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut termios) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            termios
+        };
+        let mut raw = saved;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+        raw.c_cc[libc::VMIN] = 1;
+        raw.c_cc[libc::VTIME] = 0;
+        // SAFETY: `raw` is a valid `termios` copied from the current settings.
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(RawMode(saved))
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        // SAFETY: restores the settings read in `enter`.
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.0);
+        }
+    }
+}
+
+enum Key {
+    Step,
+    RunAll,
+    Reset,
+    Down,
+    Up,
+    Quit,
+    Other,
+}
+
+fn read_key(input: &mut impl Read) -> io::Result<Key> {
+    let mut byte = [0];
+    if input.read(&mut byte)? == 0 {
+        return Ok(Key::Quit);
+    }
+    Ok(match byte[0] {
+        b' ' | b'n' | b'\n' => Key::Step,
+        b'a' => Key::RunAll,
+        b'r' => Key::Reset,
+        b'j' => Key::Down,
+        b'k' => Key::Up,
+        b'q' => Key::Quit,
+        0x1b => {
+            let mut sequence = [0; 2];
+            input.read_exact(&mut sequence)?;
+            match sequence {
+                [b'[', b'B'] => Key::Down,
+                [b'[', b'A'] => Key::Up,
+                _ => Key::Other,
+            }
+        }
+        _ => Key::Other,
+    })
+}
+
+/// Steps through Kuhn's algorithm on `graph` one keypress at a time in the terminal.
+pub fn inspect<T>(graph: &BipartiteGraph<T>) -> io::Result<()> {
+    let mut inspector = Inspector::new(graph);
+    let _raw = RawMode::enter()?;
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    write!(stdout, "\x1b[?25l")?;
+
+    let result = loop {
+        let drawn = write!(stdout, "\x1b[2J\x1b[H{}", inspector.render()).and_then(|()| stdout.flush());
+        if let Err(err) = drawn {
+            break Err(err);
+        }
+        match read_key(&mut stdin) {
+            Ok(Key::Step) => {
+                inspector.step();
+            }
+            Ok(Key::RunAll) => while inspector.step() {},
+            Ok(Key::Reset) => inspector.reset(),
+            Ok(Key::Down) => inspector.select(inspector.selected() + 1),
+            Ok(Key::Up) => inspector.select(inspector.selected().saturating_sub(1)),
+            Ok(Key::Quit) => break Ok(()),
+            Ok(Key::Other) => {}
+            Err(err) => break Err(err),
+        }
+    };
+    write!(stdout, "\x1b[?25h")?;
+    stdout.flush()?;
+    result
+}
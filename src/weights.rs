@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use rand::prelude::*;
+
+use crate::assignment::checked_total;
+use crate::error::{Error, Result};
+use crate::generate::GenConfig;
+use crate::graph::BipartiteGraph;
+use crate::matching::Matching;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WeightDistribution {
+    Uniform { min: i64, max: i64 },
+    Exponential { mean: f64 },
+    Normal { mean: f64, stddev: f64 },
+    Zipf { n: usize, exponent: f64 },
+}
+
+impl WeightDistribution {
+    pub(crate) fn validate(self) -> Result<()> {
+        self.sampler().map(|_| ())
+    }
+
+    fn sampler(self) -> Result<WeightSampler> {
+        let invalid = |message: &str| Err(Error::InvalidParameter(message.to_string()));
+        match self {
+            WeightDistribution::Uniform { min, max } if min > max => {
+                invalid("uniform weights need min <= max")
+            }
+            WeightDistribution::Exponential { mean } if !(mean > 0.0 && mean.is_finite()) => {
+                invalid("exponential weights need a positive mean")
+            }
+            WeightDistribution::Normal { mean, stddev }
+                if !(mean.is_finite() && stddev >= 0.0 && stddev.is_finite()) =>
+            {
+                invalid("normal weights need a finite mean and a non-negative stddev")
+            }
+            WeightDistribution::Zipf { n, exponent } if n == 0 || !exponent.is_finite() => {
+                invalid("zipf weights need n >= 1 and a finite exponent")
+            }
+            WeightDistribution::Zipf { n, exponent } => {
+                let mut cdf = Vec::with_capacity(n);
+                let mut total = 0.0;
+                for k in 1..=n {
+                    total += (k as f64).powf(-exponent);
+                    cdf.push(total);
+                }
+                for p in &mut cdf {
+                    *p /= total;
+                }
+                Ok(WeightSampler {
+                    distribution: self,
+                    zipf_cdf: cdf,
+                })
+            }
+            _ => Ok(WeightSampler {
+                distribution: self,
+                zipf_cdf: Vec::new(),
+            }),
+        }
+    }
+}
+
+struct WeightSampler {
+    distribution: WeightDistribution,
+    zipf_cdf: Vec<f64>,
+}
+
+impl WeightSampler {
+    fn sample(&self, rng: &mut StdRng) -> i64 {
+        match self.distribution {
+            WeightDistribution::Uniform { min, max } => rng.gen_range(min..=max),
+            WeightDistribution::Exponential { mean } => {
+                let u: f64 = rng.gen();
+                (-mean * (1.0 - u).ln()).round() as i64
+            }
+            WeightDistribution::Normal { mean, stddev } => {
+                let u1: f64 = 1.0 - rng.gen::<f64>();
+                let u2: f64 = rng.gen();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos();
+                (mean + stddev * z).round() as i64
+            }
+            WeightDistribution::Zipf { .. } => {
+                let u: f64 = rng.gen();
+                let rank = self.zipf_cdf.partition_point(|&p| p < u);
+                rank.min(self.zipf_cdf.len() - 1) as i64 + 1
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EdgeWeights {
+    weights: HashMap<(usize, usize), i64>,
+}
+
+impl EdgeWeights {
+    pub fn new() -> EdgeWeights {
+        EdgeWeights::default()
+    }
+
+    pub fn set(&mut self, left: usize, right: usize, weight: i64) {
+        self.weights.insert((left, right), weight);
+    }
+
+    pub fn get(&self, left: usize, right: usize) -> Option<i64> {
+        self.weights.get(&(left, right)).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    pub fn random<I>(
+        rng: &mut StdRng,
+        edges: I,
+        distribution: WeightDistribution,
+    ) -> Result<EdgeWeights>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        let sampler = distribution.sampler()?;
+        let mut weights = EdgeWeights::new();
+        for (i, j) in edges {
+            weights.set(i, j, sampler.sample(rng));
+        }
+        Ok(weights)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), i64)> + '_ {
+        self.weights.iter().map(|(&edge, &w)| (edge, w))
+    }
+
+    pub fn total(&self, matching: &Matching) -> Result<i64> {
+        checked_total(
+            "total weight",
+            matching.pairs().map(|(i, j)| self.get(i, j).unwrap_or(0)),
+        )
+    }
+}
+
+impl<T> BipartiteGraph<T> {
+    pub fn random_weighted(
+        rng: &mut StdRng,
+        l: usize,
+        r: usize,
+        num_edges: usize,
+        distribution: WeightDistribution,
+    ) -> Result<(BipartiteGraph<T>, EdgeWeights)>
+    where
+        T: Default,
+    {
+        let config = GenConfig::builder()
+            .sizes(l, r)
+            .edges(num_edges)
+            .weights(distribution)
+            .build()?;
+        let (graph, weights) = config.weighted_graph(rng)?;
+        Ok((graph, weights.unwrap_or_default()))
+    }
+}
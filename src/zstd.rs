@@ -0,0 +1,900 @@
+//! In-process Zstandard (RFC 8878) decoding, so reading a compressed input needs no external
+//! tool. Frames are decoded one block at a time, keeping only the frame's window of history
+//! between blocks. Skippable frames are passed over, frames that need a dictionary are
+//! rejected, and content checksums and declared content sizes are verified.
+
+use std::io::{self, BufRead, Read};
+
+const FRAME_MAGIC: u32 = 0xfd2f_b528;
+const SKIPPABLE_MAGIC: u32 = 0x184d_2a50;
+const MAX_BLOCK: usize = 128 << 10;
+/// Largest window accepted, bounding the history kept in memory.
+const MAX_WINDOW: u64 = 1 << 30;
+
+const LL_DEFAULT: [i16; 36] = [
+    4, 3, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 3, 2, 1, 1, 1, 1, 1,
+    -1, -1, -1, -1,
+];
+const ML_DEFAULT: [i16; 53] = [
+    1, 4, 3, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1, -1, -1,
+];
+const OF_DEFAULT: [i16; 29] = [
+    1, 1, 1, 1, 1, 1, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1,
+];
+
+const LL_BASE: [u32; 36] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 18, 20, 22, 24, 28, 32, 40, 48, 64,
+    128, 256, 512, 1024, 2048, 4096, 8192, 16384, 32768, 65536,
+];
+const LL_EXTRA: [u8; 36] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 3, 3, 4, 6, 7, 8, 9, 10, 11,
+    12, 13, 14, 15, 16,
+];
+const ML_BASE: [u32; 53] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27,
+    28, 29, 30, 31, 32, 33, 34, 35, 37, 39, 41, 43, 47, 51, 59, 67, 83, 99, 131, 259, 515, 1027,
+    2051, 4099, 8195, 16387, 32771, 65539,
+];
+const ML_EXTRA: [u8; 53] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 1, 1, 1, 2, 2, 3, 3, 4, 4, 5, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+];
+
+fn corrupt(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("corrupt zstd data: {message}"))
+}
+
+/// Decompresses a stream of Zstandard frames as it is read.
+pub struct ZstdDecoder<R> {
+    inner: R,
+    frame: Option<Frame>,
+    /// Whether a frame has been seen, so that the end of input is a clean finish.
+    started: bool,
+    out: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: BufRead> ZstdDecoder<R> {
+    pub fn new(inner: R) -> ZstdDecoder<R> {
+        ZstdDecoder {
+            inner,
+            frame: None,
+            started: false,
+            out: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Decodes the next block, or the next frame header. Returns false at the end of input.
+    fn step(&mut self) -> io::Result<bool> {
+        let Some(frame) = &mut self.frame else {
+            if self.started && self.inner.fill_buf()?.is_empty() {
+                return Ok(false);
+            }
+            self.started = true;
+            self.out.clear();
+            self.pos = 0;
+            self.frame = Frame::start(&mut self.inner)?;
+            return Ok(true);
+        };
+
+        // Trim the history only once it doubles, so each byte is moved a bounded number of times.
+        if self.out.len() > 2 * frame.window.max(MAX_BLOCK) {
+            self.out.drain(..self.out.len() - frame.window);
+            self.pos = self.out.len();
+        }
+        let start = self.out.len();
+        let last = frame.block(&mut self.inner, &mut self.out)?;
+        frame.checksum.update(&self.out[start..]);
+        frame.produced += (self.out.len() - start) as u64;
+        if last {
+            let frame = self.frame.take().expect("frame in progress");
+            frame.finish(&mut self.inner)?;
+        }
+        Ok(true)
+    }
+}
+
+impl<R: BufRead> Read for ZstdDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.out.len() {
+            if !self.step()? {
+                return Ok(0);
+            }
+        }
+        let n = buf.len().min(self.out.len() - self.pos);
+        buf[..n].copy_from_slice(&self.out[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+fn read_array<R: Read, const N: usize>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_le<R: Read>(reader: &mut R, len: usize) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes[..len])?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+struct Frame {
+    window: usize,
+    content_size: Option<u64>,
+    produced: u64,
+    checksum: Xxh64,
+    verify: bool,
+    repeats: [usize; 3],
+    huffman: Option<HuffmanTable>,
+    tables: [Option<FseTable>; 3],
+}
+
+impl Frame {
+    /// Reads a frame header, skipping any skippable frames first; `None` when the input ends
+    /// after skippable frames.
+    fn start<R: BufRead>(reader: &mut R) -> io::Result<Option<Frame>> {
+        let magic = loop {
+            let magic = u32::from_le_bytes(read_array(reader)?);
+            if magic & 0xffff_fff0 != SKIPPABLE_MAGIC {
+                break magic;
+            }
+            let len = u32::from_le_bytes(read_array(reader)?);
+            io::copy(&mut reader.take(u64::from(len)), &mut io::sink())?;
+            if reader.fill_buf()?.is_empty() {
+                return Ok(None);
+            }
+        };
+        if magic != FRAME_MAGIC {
+            return Err(corrupt("missing frame magic"));
+        }
+
+        let [descriptor] = read_array(reader)?;
+        let size_flag = descriptor >> 6;
+        let single_segment = descriptor & 0x20 != 0;
+        if descriptor & 0x08 != 0 {
+            return Err(corrupt("reserved frame header bit is set"));
+        }
+        let mut window = if single_segment {
+            None
+        } else {
+            let [byte] = read_array(reader)?;
+            let base = 1u64 << (10 + (byte >> 3));
+            Some(base + (base / 8) * u64::from(byte & 7))
+        };
+        let dictionary = read_le(reader, [0, 1, 2, 4][usize::from(descriptor & 3)])?;
+        if dictionary != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "zstd frames that need a dictionary are not supported",
+            ));
+        }
+        let content_size = match (size_flag, single_segment) {
+            (0, false) => None,
+            (0, true) => Some(read_le(reader, 1)?),
+            (1, _) => Some(read_le(reader, 2)? + 256),
+            (2, _) => Some(read_le(reader, 4)?),
+            _ => Some(read_le(reader, 8)?),
+        };
+        if single_segment {
+            window = content_size;
+        }
+        let window = window.unwrap_or(0);
+        if window > MAX_WINDOW {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("zstd window of {window} bytes exceeds the {MAX_WINDOW}-byte limit"),
+            ));
+        }
+
+        Ok(Some(Frame {
+            window: window as usize,
+            content_size,
+            produced: 0,
+            checksum: Xxh64::new(),
+            verify: descriptor & 0x04 != 0,
+            repeats: [1, 4, 8],
+            huffman: None,
+            tables: [None, None, None],
+        }))
+    }
+
+    /// Appends the next block to `out` and returns whether it was the last.
+    fn block<R: BufRead>(&mut self, reader: &mut R, out: &mut Vec<u8>) -> io::Result<bool> {
+        let header = read_le(reader, 3)?;
+        let last = header & 1 == 1;
+        let size = (header >> 3) as usize;
+        let limit = self.window.clamp(1, MAX_BLOCK);
+        match (header >> 1) & 3 {
+            0 | 1 if size > limit => return Err(corrupt("block larger than the window")),
+            0 => {
+                let start = out.len();
+                out.resize(start + size, 0);
+                reader.read_exact(&mut out[start..])?;
+            }
+            1 => {
+                let [byte] = read_array(reader)?;
+                out.resize(out.len() + size, byte);
+            }
+            2 => {
+                if size > MAX_BLOCK {
+                    return Err(corrupt("compressed block is too large"));
+                }
+                let mut block = vec![0; size];
+                reader.read_exact(&mut block)?;
+                self.compressed(&block, out, limit)?;
+            }
+            _ => return Err(corrupt("reserved block type")),
+        }
+        Ok(last)
+    }
+
+    fn finish<R: BufRead>(self, reader: &mut R) -> io::Result<()> {
+        if self.content_size.is_some_and(|size| size != self.produced) {
+            return Err(corrupt("content size does not match the frame header"));
+        }
+        if self.verify {
+            let expected = u32::from_le_bytes(read_array(reader)?);
+            if self.checksum.digest() as u32 != expected {
+                return Err(corrupt("content checksum mismatch"));
+            }
+        }
+        Ok(())
+    }
+
+    fn compressed(&mut self, block: &[u8], out: &mut Vec<u8>, limit: usize) -> io::Result<()> {
+        let (literals, used) = self.literals(block)?;
+        let rest = &block[used..];
+
+        let [first, ..] = *rest else {
+            return Err(corrupt("missing sequences header"));
+        };
+        let (count, header) = match first {
+            0 => (0, 1),
+            1..=127 => (usize::from(first), 1),
+            128..=254 => {
+                let second = *rest.get(1).ok_or_else(|| corrupt("truncated sequences header"))?;
+                ((usize::from(first) - 128) << 8 | usize::from(second), 2)
+            }
+            255 => {
+                let bytes = rest.get(1..3).ok_or_else(|| corrupt("truncated sequences header"))?;
+                (usize::from(bytes[0]) + (usize::from(bytes[1]) << 8) + 0x7f00, 3)
+            }
+        };
+        let start = out.len();
+        if count == 0 {
+            if header != rest.len() {
+                return Err(corrupt("data after an empty sequences section"));
+            }
+            out.extend_from_slice(&literals);
+        } else {
+            self.sequences(&rest[header..], count, &literals, out)?;
+        }
+        if out.len() - start > limit {
+            return Err(corrupt("block decodes past the block size limit"));
+        }
+        Ok(())
+    }
+
+    /// Decodes the literals section at the start of a compressed block, returning the
+    /// literals and the section's length in bytes.
+    fn literals(&mut self, block: &[u8]) -> io::Result<(Vec<u8>, usize)> {
+        let truncated = || corrupt("truncated literals section");
+        let first = *block.first().ok_or_else(truncated)?;
+        let kind = first & 3;
+        let format = (first >> 2) & 3;
+        let header_value = |len: usize| -> io::Result<usize> {
+            let bytes = block.get(..len).ok_or_else(truncated)?;
+            Ok(bytes
+                .iter()
+                .rev()
+                .fold(0, |value, &byte| value << 8 | usize::from(byte)))
+        };
+
+        if kind < 2 {
+            let (header, size) = match format {
+                0 | 2 => (1, usize::from(first >> 3)),
+                1 => (2, header_value(2)? >> 4),
+                _ => (3, header_value(3)? >> 4),
+            };
+            let literals = if kind == 0 {
+                block.get(header..header + size).ok_or_else(truncated)?.to_vec()
+            } else {
+                vec![*block.get(header).ok_or_else(truncated)?; size]
+            };
+            let used = header + if kind == 0 { size } else { 1 };
+            return Ok((literals, used));
+        }
+
+        let (header, bits, streams) = match format {
+            0 => (3, 10, 1),
+            1 => (3, 10, 4),
+            2 => (4, 14, 4),
+            _ => (5, 18, 4),
+        };
+        let value = header_value(header)?;
+        let mask = (1 << bits) - 1;
+        let regenerated = (value >> 4) & mask;
+        let compressed = (value >> (4 + bits)) & mask;
+        if regenerated > MAX_BLOCK {
+            return Err(corrupt("too many literals"));
+        }
+        let mut data = block
+            .get(header..header + compressed)
+            .ok_or_else(truncated)?;
+        if kind == 2 {
+            let (table, used) = HuffmanTable::read(data)?;
+            self.huffman = Some(table);
+            data = &data[used..];
+        }
+        let table = self
+            .huffman
+            .as_ref()
+            .ok_or_else(|| corrupt("repeated Huffman table with none before it"))?;
+
+        let mut literals = Vec::with_capacity(regenerated);
+        if streams == 1 {
+            table.decode_stream(data, regenerated, &mut literals)?;
+        } else {
+            let jump = data.get(..6).ok_or_else(truncated)?;
+            let sizes: Vec<usize> = jump
+                .chunks(2)
+                .map(|pair| usize::from(u16::from_le_bytes([pair[0], pair[1]])))
+                .collect();
+            let mut data = &data[6..];
+            let each = regenerated.div_ceil(4);
+            for size in sizes {
+                let stream = data.get(..size).ok_or_else(truncated)?;
+                data = &data[size..];
+                table.decode_stream(stream, each, &mut literals)?;
+            }
+            let rest = regenerated
+                .checked_sub(3 * each)
+                .ok_or_else(|| corrupt("too few literals for four streams"))?;
+            table.decode_stream(data, rest, &mut literals)?;
+        }
+        Ok((literals, header + compressed))
+    }
+
+    fn sequences(
+        &mut self,
+        data: &[u8],
+        count: usize,
+        literals: &[u8],
+        out: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        let modes = *data.first().ok_or_else(|| corrupt("missing compression modes"))?;
+        if modes & 3 != 0 {
+            return Err(corrupt("reserved compression mode bits are set"));
+        }
+        let mut used = 1;
+        // Literal lengths, offsets and match lengths, in the order their tables appear.
+        let kinds = [
+            (modes >> 6, &LL_DEFAULT[..], 6, 9, 35),
+            (modes >> 4 & 3, &OF_DEFAULT[..], 5, 8, 31),
+            (modes >> 2 & 3, &ML_DEFAULT[..], 6, 9, 52),
+        ];
+        for (k, &(mode, default, default_log, max_log, max_symbol)) in kinds.iter().enumerate() {
+            let table = match mode {
+                0 => FseTable::new(default, default_log)?,
+                1 => {
+                    let symbol = *data.get(used).ok_or_else(|| corrupt("missing RLE symbol"))?;
+                    if usize::from(symbol) > max_symbol {
+                        return Err(corrupt("RLE symbol out of range"));
+                    }
+                    used += 1;
+                    FseTable::rle(symbol)
+                }
+                2 => {
+                    let (table, len) = FseTable::read(&data[used..], max_log, max_symbol + 1)?;
+                    used += len;
+                    table
+                }
+                _ => self.tables[k]
+                    .take()
+                    .ok_or_else(|| corrupt("repeated sequence table with none before it"))?,
+            };
+            self.tables[k] = Some(table);
+        }
+        let [Some(ll), Some(of), Some(ml)] = &self.tables else {
+            unreachable!("every table was just set");
+        };
+
+        let mut bits = BackwardBits::new(&data[used..])?;
+        let mut ll_state = bits.read(ll.log)? as usize;
+        let mut of_state = bits.read(of.log)? as usize;
+        let mut ml_state = bits.read(ml.log)? as usize;
+        let mut literals = literals;
+        for k in 0..count {
+            let of_code = of.symbols[of_state];
+            let ml_code = usize::from(ml.symbols[ml_state]);
+            let ll_code = usize::from(ll.symbols[ll_state]);
+            if of_code > 31 {
+                return Err(corrupt("offset code out of range"));
+            }
+            let offset_value = (1u64 << of_code) + bits.read(of_code)?;
+            let match_len = ML_BASE[ml_code] as usize + bits.read(ML_EXTRA[ml_code])? as usize;
+            let literal_len = LL_BASE[ll_code] as usize + bits.read(LL_EXTRA[ll_code])? as usize;
+            if k + 1 < count {
+                ll_state = ll.next(ll_state, &mut bits)?;
+                ml_state = ml.next(ml_state, &mut bits)?;
+                of_state = of.next(of_state, &mut bits)?;
+            }
+
+            let offset = Frame::offset(&mut self.repeats, offset_value as usize, literal_len)?;
+            let taken = literals
+                .get(..literal_len)
+                .ok_or_else(|| corrupt("sequence uses more literals than decoded"))?;
+            out.extend_from_slice(taken);
+            literals = &literals[literal_len..];
+            if offset > out.len() {
+                return Err(corrupt("match offset reaches before the window"));
+            }
+            let start = out.len() - offset;
+            if offset >= match_len {
+                out.extend_from_within(start..start + match_len);
+            } else {
+                for k in 0..match_len {
+                    let byte = out[start + k];
+                    out.push(byte);
+                }
+            }
+        }
+        if !bits.is_exhausted() {
+            return Err(corrupt("sequence bitstream has bits left over"));
+        }
+        out.extend_from_slice(literals);
+        Ok(())
+    }
+
+    /// Resolves an offset value against the repeat offsets and updates them.
+    fn offset(rep: &mut [usize; 3], value: usize, literal_len: usize) -> io::Result<usize> {
+        if value > 3 {
+            let offset = value - 3;
+            *rep = [offset, rep[0], rep[1]];
+            return Ok(offset);
+        }
+        let index = value - 1 + usize::from(literal_len == 0);
+        let offset = match index {
+            0 => rep[0],
+            1 => {
+                *rep = [rep[1], rep[0], rep[2]];
+                rep[0]
+            }
+            2 => {
+                *rep = [rep[2], rep[0], rep[1]];
+                rep[0]
+            }
+            _ => {
+                let offset = rep[0]
+                    .checked_sub(1)
+                    .filter(|&offset| offset > 0)
+                    .ok_or_else(|| corrupt("zero repeat offset"))?;
+                *rep = [offset, rep[0], rep[1]];
+                offset
+            }
+        };
+        Ok(offset)
+    }
+}
+
+/// A bitstream read from its end towards its start, as Zstandard writes its entropy-coded
+/// streams. Reading past the start yields zero bits and leaves the position negative.
+struct BackwardBits<'a> {
+    data: &'a [u8],
+    offset: isize,
+}
+
+impl<'a> BackwardBits<'a> {
+    fn new(data: &'a [u8]) -> io::Result<BackwardBits<'a>> {
+        let last = *data.last().ok_or_else(|| corrupt("empty bitstream"))?;
+        if last == 0 {
+            return Err(corrupt("bitstream has no end marker"));
+        }
+        let marker = 7 - last.leading_zeros() as isize;
+        Ok(BackwardBits {
+            data,
+            offset: (data.len() as isize - 1) * 8 + marker,
+        })
+    }
+
+    fn read(&mut self, n: u8) -> io::Result<u64> {
+        if n == 0 {
+            return Ok(0);
+        }
+        self.offset -= isize::from(n);
+        if self.offset >= 0 {
+            return Ok(self.bits_at(self.offset as usize, n));
+        }
+        let available = isize::from(n) + self.offset;
+        if available <= 0 {
+            return Ok(0);
+        }
+        Ok(self.bits_at(0, available as u8) << -self.offset)
+    }
+
+    /// The `n` bits starting at bit `start`, counting from the least significant bit of the
+    /// first byte.
+    fn bits_at(&self, start: usize, n: u8) -> u64 {
+        let first = start / 8;
+        let mut word = 0;
+        for (k, &byte) in self.data[first..].iter().take(8).enumerate() {
+            word |= u64::from(byte) << (8 * k);
+        }
+        (word >> (start % 8)) & ((1 << n) - 1)
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.offset == 0
+    }
+}
+
+/// A finite state entropy decoding table: in state `s` the decoder emits `symbols[s]`, then
+/// reads `bits[s]` bits and adds them to `base[s]` for the next state.
+struct FseTable {
+    log: u8,
+    symbols: Vec<u8>,
+    bits: Vec<u8>,
+    base: Vec<u16>,
+}
+
+impl FseTable {
+    fn rle(symbol: u8) -> FseTable {
+        FseTable {
+            log: 0,
+            symbols: vec![symbol],
+            bits: vec![0],
+            base: vec![0],
+        }
+    }
+
+    /// Reads a table description, returning the table and the description's length.
+    fn read(data: &[u8], max_log: u8, max_symbols: usize) -> io::Result<(FseTable, usize)> {
+        let mut bits = ForwardBits { data, offset: 0 };
+        let log = bits.read(4)? as u8 + 5;
+        if log > max_log {
+            return Err(corrupt("accuracy log too large"));
+        }
+        let mut remaining = 1i32 << log;
+        let mut counts = Vec::new();
+        while remaining > 0 {
+            if counts.len() >= max_symbols {
+                return Err(corrupt("too many symbols in a table description"));
+            }
+            let width = 32 - (remaining + 1).leading_zeros();
+            let raw = bits.read(width as u8)? as i32;
+            let low_mask = (1 << (width - 1)) - 1;
+            let threshold = (1 << width) - 1 - (remaining + 1);
+            let value = if raw & low_mask < threshold {
+                bits.offset -= 1;
+                raw & low_mask
+            } else if raw > low_mask {
+                raw - threshold
+            } else {
+                raw
+            };
+            let count = value - 1;
+            remaining -= count.abs();
+            counts.push(count as i16);
+            if count == 0 {
+                loop {
+                    let repeat = bits.read(2)?;
+                    counts.resize(counts.len() + repeat as usize, 0);
+                    if repeat != 3 {
+                        break;
+                    }
+                }
+                if counts.len() > max_symbols {
+                    return Err(corrupt("too many symbols in a table description"));
+                }
+            }
+        }
+        if remaining != 0 {
+            return Err(corrupt("table probabilities do not add up"));
+        }
+        let used = bits.offset.div_ceil(8);
+        if used > data.len() {
+            return Err(corrupt("truncated table description"));
+        }
+        Ok((FseTable::new(&counts, log)?, used))
+    }
+
+    fn new(counts: &[i16], log: u8) -> io::Result<FseTable> {
+        let size = 1usize << log;
+        let mut symbols = vec![0; size];
+        let mut next = vec![0u16; counts.len()];
+        let mut high = size;
+        for (symbol, &count) in counts.iter().enumerate() {
+            if count == -1 {
+                high -= 1;
+                symbols[high] = symbol as u8;
+                next[symbol] = 1;
+            }
+        }
+        let step = (size >> 1) + (size >> 3) + 3;
+        let mut position = 0;
+        for (symbol, &count) in counts.iter().enumerate() {
+            if count <= 0 {
+                continue;
+            }
+            next[symbol] = count as u16;
+            for _ in 0..count {
+                symbols[position] = symbol as u8;
+                loop {
+                    position = (position + step) & (size - 1);
+                    if position < high {
+                        break;
+                    }
+                }
+            }
+        }
+        if position != 0 {
+            return Err(corrupt("table probabilities do not fill the table"));
+        }
+
+        let mut bits = vec![0; size];
+        let mut base = vec![0; size];
+        for state in 0..size {
+            let symbol = usize::from(symbols[state]);
+            let next_state = next[symbol];
+            next[symbol] += 1;
+            let width = log - (15 - next_state.leading_zeros() as u8);
+            bits[state] = width;
+            base[state] = ((u32::from(next_state) << width) - size as u32) as u16;
+        }
+        Ok(FseTable {
+            log,
+            symbols,
+            bits,
+            base,
+        })
+    }
+
+    fn next(&self, state: usize, bits: &mut BackwardBits) -> io::Result<usize> {
+        Ok(usize::from(self.base[state]) + bits.read(self.bits[state])? as usize)
+    }
+}
+
+/// A bitstream read from its start, least significant bit first, for table descriptions.
+/// Reading past the end yields zero bits; the caller checks the final position.
+struct ForwardBits<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl ForwardBits<'_> {
+    fn read(&mut self, n: u8) -> io::Result<u64> {
+        if self.offset > self.data.len() * 8 {
+            return Err(corrupt("truncated table description"));
+        }
+        let mut value = 0;
+        for k in 0..usize::from(n) {
+            let bit = self.offset + k;
+            let byte = self.data.get(bit / 8).copied().unwrap_or(0);
+            value |= u64::from(byte >> (bit % 8) & 1) << k;
+        }
+        self.offset += usize::from(n);
+        Ok(value)
+    }
+}
+
+/// A Huffman table for literals, indexed by the next `max_bits` bits of the stream.
+struct HuffmanTable {
+    max_bits: u8,
+    symbols: Vec<u8>,
+    bits: Vec<u8>,
+}
+
+impl HuffmanTable {
+    /// Reads a tree description, returning the table and the description's length.
+    fn read(data: &[u8]) -> io::Result<(HuffmanTable, usize)> {
+        let truncated = || corrupt("truncated Huffman tree description");
+        let header = usize::from(*data.first().ok_or_else(truncated)?);
+        let (mut weights, used) = if header >= 128 {
+            let count = header - 127;
+            let packed = data.get(1..1 + count.div_ceil(2)).ok_or_else(truncated)?;
+            let weights = (0..count)
+                .map(|k| (packed[k / 2] >> if k % 2 == 0 { 4 } else { 0 }) & 0xf)
+                .collect();
+            (weights, 1 + count.div_ceil(2))
+        } else {
+            let data = data.get(1..1 + header).ok_or_else(truncated)?;
+            let (table, len) = FseTable::read(data, 6, 256)?;
+            let mut bits = BackwardBits::new(&data[len..])?;
+            let mut states = [bits.read(table.log)? as usize, bits.read(table.log)? as usize];
+            let mut weights = Vec::new();
+            'decode: loop {
+                for k in 0..2 {
+                    weights.push(table.symbols[states[k]]);
+                    states[k] = table.next(states[k], &mut bits)?;
+                    if bits.offset < 0 {
+                        weights.push(table.symbols[states[1 - k]]);
+                        break 'decode;
+                    }
+                }
+                if weights.len() > 255 {
+                    return Err(corrupt("too many Huffman weights"));
+                }
+            }
+            (weights, 1 + header)
+        };
+        if weights.len() > 255 || weights.iter().any(|&w| w > 11) {
+            return Err(corrupt("invalid Huffman weights"));
+        }
+
+        let total: u32 = weights
+            .iter()
+            .filter(|&&w| w > 0)
+            .map(|&w| 1 << (w - 1))
+            .sum();
+        if total == 0 {
+            return Err(corrupt("empty Huffman tree"));
+        }
+        let max_bits = 32 - total.leading_zeros();
+        let left = (1 << max_bits) - total;
+        if !left.is_power_of_two() || max_bits > 11 {
+            return Err(corrupt("Huffman weights do not form a tree"));
+        }
+        weights.push(left.trailing_zeros() as u8 + 1);
+
+        let max_bits = max_bits as u8;
+        let lengths: Vec<u8> = weights
+            .iter()
+            .map(|&w| if w > 0 { max_bits + 1 - w } else { 0 })
+            .collect();
+        let mut ranks = [0usize; 13];
+        for &len in &lengths {
+            ranks[usize::from(len)] += 1;
+        }
+        let mut start = [0usize; 13];
+        for len in (1..=usize::from(max_bits)).rev() {
+            start[len - 1] = start[len] + (ranks[len] << (usize::from(max_bits) - len));
+        }
+        let size = 1 << max_bits;
+        let mut symbols = vec![0; size];
+        let mut bits = vec![0; size];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len == 0 {
+                continue;
+            }
+            let span = 1 << (max_bits - len);
+            let at = start[usize::from(len)];
+            symbols[at..at + span].fill(symbol as u8);
+            bits[at..at + span].fill(len);
+            start[usize::from(len)] += span;
+        }
+        Ok((
+            HuffmanTable {
+                max_bits,
+                symbols,
+                bits,
+            },
+            used,
+        ))
+    }
+
+    fn decode_stream(&self, data: &[u8], count: usize, out: &mut Vec<u8>) -> io::Result<()> {
+        let mut bits = BackwardBits::new(data)?;
+        let mask = (1 << self.max_bits) - 1;
+        let mut state = bits.read(self.max_bits)? as usize;
+        for _ in 0..count {
+            out.push(self.symbols[state]);
+            let len = self.bits[state];
+            state = ((state << len) | bits.read(len)? as usize) & mask;
+        }
+        if bits.offset != -isize::from(self.max_bits) {
+            return Err(corrupt("literal stream length does not match its symbols"));
+        }
+        Ok(())
+    }
+}
+
+const PRIME_1: u64 = 0x9e37_79b1_85eb_ca87;
+const PRIME_2: u64 = 0xc2b2_ae3d_27d4_eb4f;
+const PRIME_3: u64 = 0x1656_67b1_9e37_79f9;
+const PRIME_4: u64 = 0x85eb_ca77_c2b2_ae63;
+const PRIME_5: u64 = 0x27d4_eb2f_1656_67c5;
+
+/// Streaming XXH64 with seed 0, whose low 32 bits are a frame's content checksum.
+struct Xxh64 {
+    lanes: [u64; 4],
+    pending: Vec<u8>,
+    total: u64,
+}
+
+impl Xxh64 {
+    fn new() -> Xxh64 {
+        Xxh64 {
+            lanes: [
+                PRIME_1.wrapping_add(PRIME_2),
+                PRIME_2,
+                0,
+                0u64.wrapping_sub(PRIME_1),
+            ],
+            pending: Vec::with_capacity(32),
+            total: 0,
+        }
+    }
+
+    fn round(acc: u64, input: u64) -> u64 {
+        acc.wrapping_add(input.wrapping_mul(PRIME_2))
+            .rotate_left(31)
+            .wrapping_mul(PRIME_1)
+    }
+
+    fn stripe(&mut self, stripe: &[u8]) {
+        for (lane, word) in self.lanes.iter_mut().zip(stripe.chunks_exact(8)) {
+            *lane = Xxh64::round(*lane, u64::from_le_bytes(word.try_into().unwrap()));
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        self.total += data.len() as u64;
+        if !self.pending.is_empty() {
+            let take = data.len().min(32 - self.pending.len());
+            self.pending.extend_from_slice(&data[..take]);
+            data = &data[take..];
+            if self.pending.len() < 32 {
+                return;
+            }
+            let stripe = std::mem::take(&mut self.pending);
+            self.stripe(&stripe);
+            self.pending = stripe;
+            self.pending.clear();
+        }
+        let mut stripes = data.chunks_exact(32);
+        for stripe in &mut stripes {
+            self.stripe(stripe);
+        }
+        self.pending.extend_from_slice(stripes.remainder());
+    }
+
+    fn digest(&self) -> u64 {
+        let [v1, v2, v3, v4] = self.lanes;
+        let mut h = if self.total >= 32 {
+            let mut h = v1
+                .rotate_left(1)
+                .wrapping_add(v2.rotate_left(7))
+                .wrapping_add(v3.rotate_left(12))
+                .wrapping_add(v4.rotate_left(18));
+            for v in self.lanes {
+                h = (h ^ Xxh64::round(0, v))
+                    .wrapping_mul(PRIME_1)
+                    .wrapping_add(PRIME_4);
+            }
+            h
+        } else {
+            PRIME_5
+        };
+        h = h.wrapping_add(self.total);
+
+        let mut rest = &self.pending[..];
+        while rest.len() >= 8 {
+            let k = Xxh64::round(0, u64::from_le_bytes(rest[..8].try_into().unwrap()));
+            h = (h ^ k).rotate_left(27).wrapping_mul(PRIME_1).wrapping_add(PRIME_4);
+            rest = &rest[8..];
+        }
+        if rest.len() >= 4 {
+            let k = u64::from(u32::from_le_bytes(rest[..4].try_into().unwrap()));
+            h = (h ^ k.wrapping_mul(PRIME_1))
+                .rotate_left(23)
+                .wrapping_mul(PRIME_2)
+                .wrapping_add(PRIME_3);
+            rest = &rest[4..];
+        }
+        for &byte in rest {
+            h = (h ^ u64::from(byte).wrapping_mul(PRIME_5))
+                .rotate_left(11)
+                .wrapping_mul(PRIME_1);
+        }
+
+        h ^= h >> 33;
+        h = h.wrapping_mul(PRIME_2);
+        h ^= h >> 29;
+        h = h.wrapping_mul(PRIME_3);
+        h ^ (h >> 32)
+    }
+}
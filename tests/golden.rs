@@ -0,0 +1,228 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use rand::prelude::*;
+
+use kuhn::bench::{test_graph, Metric, Variant};
+use kuhn::{
+    BipartiteGraph, DotLayout, DotOrder, EdgeWeights, GenConfig, Matching, NodeLink, Side,
+    SimpleGraph,
+};
+
+fn assert_golden(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+
+    if env::var_os("KUHN_BLESS").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|err| {
+        panic!(
+            "missing golden file {} ({err}); rerun with KUHN_BLESS=1",
+            path.display()
+        )
+    });
+    assert!(
+        expected == actual,
+        "output differs from {}; rerun with KUHN_BLESS=1 if the change is intended\n\
+         --- expected\n{expected}\n--- actual\n{actual}",
+        path.display()
+    );
+}
+
+#[cfg(feature = "parquet")]
+fn assert_golden_bytes(name: &str, actual: &[u8]) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+
+    if env::var_os("KUHN_BLESS").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read(&path).unwrap_or_else(|err| {
+        panic!(
+            "missing golden file {} ({err}); rerun with KUHN_BLESS=1",
+            path.display()
+        )
+    });
+    assert!(
+        expected == actual,
+        "output differs from {} ({} bytes expected, {} actual); rerun with KUHN_BLESS=1 if \
+         the change is intended",
+        path.display(),
+        expected.len(),
+        actual.len()
+    );
+}
+
+fn canonical_graph() -> BipartiteGraph<&'static str> {
+    BipartiteGraph::from_edges_with_data(
+        vec!["a", "b", "c", "d"],
+        vec!["x", "y", "x", "z"],
+        [(0, 0), (0, 1), (1, 0), (2, 1), (2, 2), (3, 2), (3, 3)],
+    )
+    .unwrap()
+}
+
+fn render(write: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>) -> String {
+    let mut out = Vec::new();
+    write(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn dot_plain() {
+    let graph = canonical_graph();
+    let matching = graph.kuhn_deterministic();
+    assert_golden("dot_plain.dot", &render(|w| graph.write_dot(w, &matching)));
+}
+
+#[test]
+fn dot_with_path() {
+    let graph = canonical_graph();
+    let matching = graph.kuhn_deterministic();
+    let path = [(1, 0), (0, 0), (0, 1)];
+    assert_golden(
+        "dot_with_path.dot",
+        &render(|w| graph.write_dot_with_path(w, &matching, &path)),
+    );
+}
+
+#[test]
+fn dot_weighted() {
+    let graph = canonical_graph();
+    let matching = graph.kuhn_deterministic();
+    let mut weights = EdgeWeights::new();
+    for (k, (i, j)) in graph.edges().into_iter().enumerate() {
+        weights.set(i, j, k as i64 * 3 - 4);
+    }
+    assert_golden(
+        "dot_weighted.dot",
+        &render(|w| graph.write_dot_weighted(w, &matching, &weights)),
+    );
+}
+
+#[test]
+fn dot_grouped() {
+    let graph = canonical_graph();
+    let matching = graph.kuhn_deterministic();
+    assert_golden(
+        "dot_grouped.dot",
+        &render(|w| graph.write_dot_grouped(w, &matching, |d| *d)),
+    );
+}
+
+#[test]
+fn dot_degree_order() {
+    let graph = canonical_graph();
+    let matching = graph.kuhn_deterministic();
+    let layout = DotLayout {
+        same_rank: true,
+        order: DotOrder::Degree,
+    };
+    assert_golden(
+        "dot_degree_order.dot",
+        &render(|w| graph.write_dot_with_layout(w, &matching, layout)),
+    );
+}
+
+#[test]
+fn dot_diff() {
+    let graph = canonical_graph();
+    let before = graph.kuhn_deterministic();
+    let after = Matching::try_from_matched_right(4, vec![Some(1), Some(0), Some(3), None]).unwrap();
+    assert_golden(
+        "dot_diff.dot",
+        &render(|w| graph.write_dot_diff(w, &before, &after)),
+    );
+}
+
+#[test]
+fn dot_linked() {
+    let graph = canonical_graph();
+    let matching = graph.kuhn_deterministic();
+    let link = |side, v, data: &&str| match side {
+        Side::Left => NodeLink {
+            tooltip: Some(format!("worker {data} \"{v}\"")),
+            url: Some(format!("https://example.org/workers/{v}")),
+        },
+        Side::Right if v == 3 => NodeLink::default(),
+        Side::Right => NodeLink {
+            tooltip: Some(format!("task {data}\nslot {v}")),
+            url: None,
+        },
+    };
+    assert_golden(
+        "dot_linked.dot",
+        &render(|w| graph.write_dot_linked(w, &matching, link)),
+    );
+}
+
+#[test]
+fn matching_text_and_json() {
+    let graph = canonical_graph();
+    let matching = graph.kuhn_deterministic();
+    assert_golden("matching.txt", &render(|w| matching.write_to(w)));
+    assert_golden("matching.json", &format!("{}\n", matching.to_json()));
+    assert_golden(
+        "matching_empty.json",
+        &format!("{}\n", Matching::empty(2, 3).to_json()),
+    );
+}
+
+#[test]
+fn graph6_and_sparse6() {
+    let graph = canonical_graph();
+    let simple = SimpleGraph::from_bipartite(&graph);
+    let text = format!("{}\n{}\n", simple.to_graph6(), simple.to_sparse6());
+    assert_golden("canonical.g6", &text);
+}
+
+#[test]
+fn bench_csv_schema() {
+    let mut text = String::new();
+    for metric in [Metric::Time, Metric::Ops, Metric::Both, Metric::Phases] {
+        text.push_str(metric.csv_header());
+        text.push('\n');
+    }
+
+    let config = GenConfig::builder()
+        .sizes(50, 50)
+        .edges(200)
+        .build()
+        .unwrap();
+    let mut rng = StdRng::seed_from_u64(7);
+    for variant in [
+        Variant::Plain,
+        Variant::Degree,
+        Variant::Bfs,
+        Variant::HopcroftKarp,
+    ] {
+        let sample = test_graph(&mut rng, &config, variant, Metric::Ops).unwrap();
+        text.push_str(&format!("{},{}\n", variant.name(), sample.csv_fields()));
+    }
+    assert_golden("bench_schema.csv", &text);
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn parquet_table() {
+    let header = "n,m,time,seed,rng,instance";
+    let rows = [
+        "10,20,1.5,18446744073709551615,00ff,0123".to_string(),
+        "30,-40,2.25,7,abcd,4567".to_string(),
+    ];
+    let mut table =
+        kuhn::parquet::Table::from_csv_rows(header, &rows, &["rng", "instance"], &["seed"])
+            .unwrap();
+    table.set_metadata("kuhn.run_metadata", "{}");
+    let mut out = Vec::new();
+    table.write(&mut out).unwrap();
+    assert_golden_bytes("table.parquet", &out);
+}